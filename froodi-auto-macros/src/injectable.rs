@@ -160,7 +160,9 @@ fn generate_function_quotes(
         .map(|(ty, span)| {
             quote_spanned! { *span =>
                 ::froodi::Dependency {
-                    type_info: <#ty as ::froodi::DependencyResolver>::type_info()
+                    type_info: <#ty as ::froodi::DependencyResolver>::type_info(),
+                    is_transient: <#ty as ::froodi::DependencyResolver>::is_transient(),
+                    is_weak: <#ty as ::froodi::DependencyResolver>::is_weak(),
                 }
             }
         })
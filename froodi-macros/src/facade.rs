@@ -0,0 +1,257 @@
+use alloc::{format, string::ToString as _, vec::Vec};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned as _,
+    Error, Expr, FnArg, GenericArgument, ItemTrait, PathArguments, ReturnType, Signature, TraitItem, Type, TypePath,
+};
+
+mod kw {
+    syn::custom_keyword!(scope);
+}
+
+#[derive(Default)]
+pub(crate) struct FacadeArgs {
+    scope: Option<Expr>,
+}
+
+impl Parse for FacadeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let kw = input.parse::<kw::scope>()?;
+            input.parse::<syn::Token![=]>()?;
+            if args.scope.replace(input.parse()?).is_some() {
+                return Err(Error::new_spanned(kw, "`scope` specified more than once"));
+            }
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// How a facade method resolves its dependency
+struct Method<'a> {
+    sig: &'a Signature,
+    dependency: &'a Type,
+    /// `Arc<T>`/`Rc<T>` returned, resolved with `get`, otherwise with `get_transient`
+    scoped: bool,
+    /// `Result<_, E>` returned, otherwise the method panics on errors
+    fallible: bool,
+}
+
+impl<'a> Method<'a> {
+    fn new(sig: &'a Signature) -> syn::Result<Self> {
+        if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+            return Err(Error::new_spanned(&sig.generics, "facade methods can't be generic"));
+        }
+        let receiver = match sig.inputs.iter().collect::<Vec<_>>()[..] {
+            [FnArg::Receiver(receiver)] => receiver,
+            _ => return Err(Error::new_spanned(&sig.inputs, "facade methods must take only `&self`")),
+        };
+        if receiver.reference.is_none() || receiver.mutability.is_some() {
+            return Err(Error::new_spanned(receiver, "facade methods must take only `&self`"));
+        }
+        let ReturnType::Type(_, ty) = &sig.output else {
+            return Err(Error::new_spanned(sig, "facade methods must return the dependency"));
+        };
+
+        let (ty, fallible) = match single_argument(ty, &["Result"]) {
+            Some(ty) => (ty, true),
+            None => (&**ty, false),
+        };
+        let (dependency, scoped) = match single_argument(ty, &["Arc", "Rc", "RcThreadSafety"]) {
+            Some(dependency) => (dependency, true),
+            None => (ty, false),
+        };
+        Ok(Self {
+            sig,
+            dependency,
+            scoped,
+            fallible,
+        })
+    }
+
+    fn body(&self, facade: &str, is_async: bool) -> TokenStream {
+        let dependency = self.dependency;
+        let mut body = if self.scoped {
+            quote!(self.get::<#dependency>())
+        } else {
+            quote!(self.get_transient::<#dependency>())
+        };
+        if is_async {
+            body = quote!(#body.await);
+        }
+        if self.fallible {
+            quote!(#body.map_err(::core::convert::Into::into))
+        } else {
+            let message = format!("Failed to resolve the dependency of `{facade}::{}`: {{}}", self.sig.ident);
+            quote!(#body.unwrap_or_else(|err| ::core::panic!(#message, err)))
+        }
+    }
+}
+
+/// `T` of `Name<T>` and `Name<T, ...>`, if the last path segment is one of the names
+fn single_argument<'a>(ty: &'a Type, names: &[&str]) -> Option<&'a Type> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let segment = path.segments.last()?;
+    if !names.iter().any(|name| segment.ident == name) {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+pub(crate) fn expand(args: FacadeArgs, mut item: ItemTrait) -> syn::Result<TokenStream> {
+    if !item.generics.params.is_empty() {
+        return Err(Error::new_spanned(&item.generics, "facade traits can't be generic"));
+    }
+
+    let facade = item.ident.to_string();
+    let mut methods = Vec::new();
+    for trait_item in &item.items {
+        match trait_item {
+            // Methods with a body are left to the user
+            TraitItem::Fn(method) if method.default.is_none() => methods.push(Method::new(&method.sig)?),
+            TraitItem::Fn(_) => {}
+            item => return Err(Error::new(item.span(), "facade traits can only contain methods")),
+        }
+    }
+
+    let ident = &item.ident;
+    let sync_methods = methods.iter().map(|method| {
+        let sig = method.sig;
+        let body = method.body(&facade, false);
+        quote!(#sig { #body })
+    });
+    let sync_impl = quote! {
+        impl #ident for ::froodi::Container {
+            #( #sync_methods )*
+        }
+    };
+    // Sync methods can't be implemented with the async container
+    let async_impl = (!methods.is_empty() && methods.iter().all(|method| method.sig.asyncness.is_some())).then(|| {
+        let async_methods = methods.iter().map(|method| {
+            let sig = method.sig;
+            let body = method.body(&facade, true);
+            quote!(#sig { #body })
+        });
+        quote! {
+            ::froodi::facade_async_impl! {
+                impl #ident for ::froodi::async_impl::Container {
+                    #( #async_methods )*
+                }
+            }
+        }
+    });
+
+    let scope = match &args.scope {
+        Some(scope) => quote!(::core::option::Option::Some(::core::convert::Into::into(#scope))),
+        None => quote!(::core::option::Option::None),
+    };
+    let names = methods.iter().map(|method| method.sig.ident.to_string());
+    let dependencies = methods.iter().map(|method| method.dependency);
+    item.items.push(syn::parse_quote! {
+        /// Checks that the registry provides the dependency of each method of the facade,
+        /// see `froodi::FacadeRegistry::check_facade`
+        ///
+        /// # Errors
+        /// Returns `froodi::ValidationErrorKind` of the first method without an accessible provider
+        fn check(registry: &impl ::froodi::FacadeRegistry) -> ::core::result::Result<(), ::froodi::ValidationErrorKind>
+        where
+            Self: Sized,
+        {
+            registry.check_facade(
+                #facade,
+                #scope,
+                &[ #( ::froodi::FacadeMethod::of::<#dependencies>(#names) ),* ],
+            )
+        }
+    });
+
+    Ok(quote! {
+        #item
+        #sync_impl
+        #async_impl
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+    use quote::quote;
+    use syn::parse_quote;
+
+    use super::{expand, FacadeArgs};
+
+    #[test]
+    fn test_generates_impls() {
+        let output = expand(
+            parse_quote!(scope = Request),
+            parse_quote! {
+                trait RequestDeps {
+                    fn repo(&self) -> Arc<UserRepo>;
+                    fn clock(&self) -> Result<Clock, ResolveErrorKind>;
+                    fn name(&self) -> &'static str {
+                        "request"
+                    }
+                }
+            },
+        )
+        .unwrap()
+        .to_string();
+        assert!(output.contains(&quote!(self.get::<UserRepo>().unwrap_or_else).to_string()));
+        assert!(output.contains(&quote!(self.get_transient::<Clock>().map_err).to_string()));
+        assert!(output.contains(&quote!(impl RequestDeps for ::froodi::Container).to_string()));
+        // The async container is implemented only for async methods
+        assert!(!output.contains("facade_async_impl"));
+
+        let output = expand(
+            FacadeArgs::default(),
+            parse_quote! {
+                trait RequestDeps {
+                    async fn repo(&self) -> Arc<UserRepo>;
+                }
+            },
+        )
+        .unwrap()
+        .to_string();
+        assert!(output.contains(&quote!(self.get::<UserRepo>().await.unwrap_or_else).to_string()));
+        assert!(output.contains(&quote!(impl RequestDeps for ::froodi::async_impl::Container).to_string()));
+    }
+
+    #[test]
+    fn test_reports_problems() {
+        let error = expand(
+            FacadeArgs::default(),
+            parse_quote! {
+                trait RequestDeps {
+                    fn repo(&self, id: u8) -> Arc<UserRepo>;
+                }
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "facade methods must take only `&self`");
+
+        let error = expand(
+            FacadeArgs::default(),
+            parse_quote! {
+                trait RequestDeps {
+                    fn repo(&self);
+                }
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "facade methods must return the dependency");
+    }
+}
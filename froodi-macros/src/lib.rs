@@ -0,0 +1,234 @@
+#![no_std]
+
+extern crate alloc;
+
+use proc_macro::TokenStream;
+use syn::parse_macro_input;
+
+mod facade;
+mod scope;
+mod static_container;
+mod static_registry;
+
+/// Same as `froodi::registry!`, but checks the wiring of the entries at compile time.
+///
+/// The macro accepts the syntax of `froodi::registry!` and generates the same [`Registry`](https://docs.rs/froodi/latest/froodi/struct.Registry.html).
+/// In addition, types supplied from outside the registry (for example, through a `Context`)
+/// can be declared with `external(...)`, as `Registry::with_external` does.
+///
+/// The following problems among the entries the macro can see are reported as compile errors:
+/// - cyclic dependencies
+/// - dependencies on types provided in a child scope of the dependent
+/// - dependencies that aren't provided by any entry and aren't declared as `external`
+///
+/// The macro only sees the tokens of the entries, so:
+/// - the provided type is taken from the return type of a closure (`|| -> Result<A, E> { ... }`),
+///   from `Ok::<A, _>(...)`, or from `Ok(A)`, `Ok(A(...))` and `Ok(A { ... })` bodies
+/// - dependencies are taken from `Inject<T>` and `InjectTransient<T>` closure arguments
+/// - types are compared by their full paths, so `a::Config` and `b::Config` are different types,
+///   but a dependency isn't reported as missing if a type with the same name is provided under another path
+/// - scopes are checked only if they're written as [`DefaultScope`](https://docs.rs/froodi/latest/froodi/enum.DefaultScope.html)
+///   variants with the `DefaultScope::` prefix, since a bare `App` may be a variant of another scope type
+///
+/// Entries the macro can't see through, like function instantiators, are checked at runtime as in `froodi::registry!`.
+/// Missing dependencies aren't reported if there's such an entry or an `extend(...)`, because they may provide them.
+///
+/// # Examples
+/// ```rust
+/// use froodi::{static_registry, DefaultScope::*, Inject, InstantiateErrorKind};
+///
+/// struct Config;
+/// struct Service(Config);
+/// struct Handler;
+///
+/// let registry = static_registry! {
+///     scope(App) [
+///         provide(|| Ok(Config)),
+///     ],
+///     scope(Request) [
+///         provide(|| -> Result<Service, InstantiateErrorKind> { Ok(Service(Config)) }),
+///         provide(|Inject(_): Inject<Service>, Inject(_): Inject<u8>| Ok(Handler)),
+///     ],
+///     external(u8),
+/// };
+/// ```
+///
+/// Depending on a type of a child scope fails to compile:
+/// ```rust,compile_fail
+/// use froodi::{static_registry, DefaultScope, Inject};
+///
+/// struct Config;
+/// struct Session;
+///
+/// let registry = static_registry! {
+///     scope(DefaultScope::App) [
+///         provide(|Inject(_): Inject<Session>| Ok(Config)),
+///     ],
+///     scope(DefaultScope::Request) [
+///         provide(|| Ok(Session)),
+///     ],
+/// };
+/// ```
+#[proc_macro]
+pub fn static_registry(input: TokenStream) -> TokenStream {
+    static_registry::expand(input.into()).into()
+}
+
+/// Implements a trait of dependency getters for `froodi::Container` and, with the `async` feature, `froodi::async_impl::Container`.
+///
+/// Each method without a body must take only `&self` and return the dependency:
+/// - `Arc<T>` (`Rc<T>` without the `thread_safe` feature) is resolved with `get::<T>()`, other types with `get_transient`
+/// - in `Result<_, E>`, errors are converted with `Into` into `E`, otherwise the method panics on errors
+///
+/// The async container is implemented only if all the methods are `async fn`.
+/// The sync container returns ready values for them.
+///
+/// The macro adds a `check` function to the trait that verifies that a registry provides each returned type,
+/// in the scope given by `#[facade(scope = ...)]` or its ancestors, so a typo'd or misplaced type fails a unit test
+/// instead of a request.
+///
+/// # Examples
+/// ```rust
+/// use froodi::{facade, registry, Container, DefaultScope::*};
+/// use std::sync::Arc;
+///
+/// struct UserRepo;
+/// struct Clock(u64);
+///
+/// #[facade(scope = Request)]
+/// trait RequestDeps {
+///     fn repo(&self) -> Arc<UserRepo>;
+///     fn clock(&self) -> Clock;
+/// }
+///
+/// let registry = registry! {
+///     scope(App) [
+///         provide(|| Ok(UserRepo)),
+///     ],
+///     scope(Request) [
+///         provide(|| Ok(Clock(1))),
+///     ],
+/// };
+/// <Container as RequestDeps>::check(&registry).unwrap();
+///
+/// let request = Container::new(registry).enter_build().unwrap();
+/// let _repo = request.repo();
+/// assert_eq!(request.clock().0, 1);
+/// ```
+#[proc_macro_attribute]
+pub fn facade(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args);
+    let input = parse_macro_input!(input);
+    facade::expand(args, input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Generates a container struct per scope with the wiring compiled away.
+///
+/// Each `scope(...) as Name [ ... ]` block declares a struct for the scope, the first one is the root scope
+/// and each next one is a child of the previous one, entered with `enter`.
+/// A struct has a field per provided type caching its instance, and calls the instantiators directly,
+/// without boxed services, `dyn Any` downcasts and map lookups.
+///
+/// Entries are `provide(instantiator [, config = ...] [, finalizer = ...])`, as in `froodi::registry!`, but:
+/// - instantiators must be closures without captures that provide a type visible to the macro,
+///   from the return type (`|| -> Result<A, E> { ... }`) or from `Ok(A)`, `Ok(A(...))` and `Ok(A { ... })` bodies
+/// - dependencies are `Inject<T>` and `InjectTransient<T>` closure arguments of types provided by the same or a parent scope
+/// - only `cache_provides` and `retry` of the config are supported, setting other fields is a compile error,
+///   so the config must be `Config::default()` with `cache_provides` and `retry` builder calls
+///
+/// Finalizers are called on `close` and when the last clone of a container is dropped, in LIFO order, as with `froodi::Container`.
+/// `into_container` converts a generated container into a dynamic `froodi::Container` of the same scope that shares its instances.
+///
+/// Duplicate providers, cycles, and dependencies that aren't provided or provided in a child scope are reported as compile errors.
+///
+/// # Examples
+/// ```rust
+/// use froodi::{static_container, DefaultScope::*, Inject};
+///
+/// struct Config(u8);
+/// struct Service(u8);
+///
+/// static_container! {
+///     scope(App) as AppContainer [
+///         provide(|| Ok(Config(1))),
+///     ],
+///     scope(Request) as RequestContainer [
+///         provide(|Inject(config): Inject<Config>| Ok(Service(config.0 + 1)), finalizer = |_service| {}),
+///     ],
+/// }
+///
+/// let app = AppContainer::new();
+/// let request = app.clone().enter();
+/// assert_eq!(request.get::<Service>().unwrap().0, 2);
+/// assert_eq!(app.get::<Config>().unwrap().0, 1);
+/// request.close();
+///
+/// let request = app.enter().into_container();
+/// assert_eq!(request.get::<Service>().unwrap().0, 2);
+/// ```
+///
+/// Depending on a type of a child scope fails to compile:
+/// ```rust,compile_fail
+/// use froodi::{static_container, DefaultScope::*, Inject};
+///
+/// struct Config;
+/// struct Session;
+///
+/// static_container! {
+///     scope(App) as AppContainer [
+///         provide(|Inject(_): Inject<Session>| Ok(Config)),
+///     ],
+///     scope(Request) as RequestContainer [
+///         provide(|| Ok(Session)),
+///     ],
+/// }
+/// ```
+#[proc_macro]
+pub fn static_container(input: TokenStream) -> TokenStream {
+    static_container::expand(input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `froodi::Scope` and `froodi::Scopes` for an enum of unit variants.
+///
+/// Variants are ordered by priority, so the first variant is the root scope.
+/// The name of a scope is the variant name in `snake_case`.
+/// The enum must also implement `Ord`, which is usually derived along with the scope.
+///
+/// Variant attributes:
+/// - `#[scope(skip)]` marks the scope as skipped by default, see `froodi::Scope::is_skipped_by_default`
+/// - `#[scope(name = "...")]` overrides the name of the scope
+/// - `#[scope(parent = Variant)]` makes the scope a child of `Variant` instead of the preceding variant,
+///   see `froodi::Scope::parent`. The parent must be declared before the scope
+///
+/// # Examples
+/// ```rust
+/// use froodi::{registry, Container, Scope};
+///
+/// #[derive(Scope, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// enum JobScope {
+///     #[scope(skip)]
+///     Runtime,
+///     App,
+///     #[scope(name = "job")]
+///     BackgroundJob,
+///     Step,
+/// }
+///
+/// struct Job;
+///
+/// let app = Container::new(registry! {
+///     scope(JobScope::BackgroundJob) [
+///         provide(|| Ok(Job)),
+///     ],
+/// });
+/// let job = app.enter().with_scope(JobScope::BackgroundJob).build().unwrap();
+/// assert!(job.get::<Job>().is_ok());
+/// assert_eq!(JobScope::BackgroundJob.name(), "job");
+/// ```
+#[proc_macro_derive(Scope, attributes(scope))]
+pub fn derive_scope(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+    scope::expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
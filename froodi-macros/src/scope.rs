@@ -0,0 +1,223 @@
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Data, DeriveInput, Error, Fields, Ident, LitStr, Token,
+};
+
+mod kw {
+    syn::custom_keyword!(skip);
+    syn::custom_keyword!(name);
+    syn::custom_keyword!(parent);
+}
+
+#[derive(Default)]
+struct ScopeArgs {
+    skip: Option<kw::skip>,
+    name: Option<LitStr>,
+    parent: Option<Ident>,
+}
+
+impl Parse for ScopeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Self::default();
+        while !input.is_empty() {
+            let lh = input.lookahead1();
+            if lh.peek(kw::skip) {
+                let kw = input.parse::<kw::skip>()?;
+                if args.skip.is_some() {
+                    return Err(Error::new_spanned(kw, "`skip` specified more than once"));
+                }
+                args.skip = Some(kw);
+            } else if lh.peek(kw::name) {
+                let kw = input.parse::<kw::name>()?;
+                input.parse::<Token![=]>()?;
+                if args.name.is_some() {
+                    return Err(Error::new_spanned(kw, "`name` specified more than once"));
+                }
+                args.name = Some(input.parse()?);
+            } else if lh.peek(kw::parent) {
+                let kw = input.parse::<kw::parent>()?;
+                input.parse::<Token![=]>()?;
+                if args.parent.is_some() {
+                    return Err(Error::new_spanned(kw, "`parent` specified more than once"));
+                }
+                args.parent = Some(input.parse()?);
+            } else {
+                return Err(lh.error());
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl ScopeArgs {
+    fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("scope")) {
+            let Self { skip, name, parent } = attr.parse_args()?;
+            if let Some(skip) = skip {
+                if args.skip.replace(skip).is_some() {
+                    return Err(Error::new_spanned(attr, "`skip` specified more than once"));
+                }
+            }
+            if let Some(name) = name {
+                if args.name.replace(name).is_some() {
+                    return Err(Error::new_spanned(attr, "`name` specified more than once"));
+                }
+            }
+            if let Some(parent) = parent {
+                if args.parent.replace(parent).is_some() {
+                    return Err(Error::new_spanned(attr, "`parent` specified more than once"));
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+pub(crate) fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(ident, "`Scope` can only be derived for enums"));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(&input.generics, "`Scope` can't be derived for generic enums"));
+    }
+    if data.variants.is_empty() {
+        return Err(Error::new_spanned(ident, "`Scope` enum must have at least one variant"));
+    }
+    if data.variants.len() > usize::from(u8::MAX) + 1 {
+        return Err(Error::new_spanned(ident, "`Scope` enum can't have more than 256 variants"));
+    }
+
+    let mut variants = Vec::with_capacity(data.variants.len());
+    let mut names: Vec<String> = Vec::with_capacity(data.variants.len());
+    let mut skips = Vec::with_capacity(data.variants.len());
+    let mut parents = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "scope variants must be unit variants"));
+        }
+        let args = ScopeArgs::from_attrs(&variant.attrs)?;
+        let name = match &args.name {
+            Some(name) => name.value(),
+            None => to_snake_case(&variant.ident.to_string()),
+        };
+        if names.contains(&name) {
+            let span = args.name.map_or_else(|| variant.ident.span(), |name| name.span());
+            return Err(Error::new(span, alloc::format!("scope name `{name}` is used more than once")));
+        }
+        let parent = match args.parent {
+            Some(parent) if variants.contains(&&parent) => quote!(::core::option::Option::Some(Self::#parent)),
+            Some(parent) => {
+                return Err(Error::new_spanned(
+                    parent,
+                    "parent scope must be one of the variants declared before the scope",
+                ));
+            }
+            None => quote!(::core::option::Option::None),
+        };
+        variants.push(&variant.ident);
+        names.push(name);
+        skips.push(args.skip.is_some());
+        parents.push(parent);
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let priorities = (0..variants.len()).map(|priority| priority as u8);
+    let n = variants.len() - 1;
+    let first = variants[0];
+    let rest = &variants[1..];
+
+    Ok(quote! {
+        impl ::froodi::Scope for #ident {
+            #[inline]
+            fn name(&self) -> &'static str {
+                match self {
+                    #( Self::#variants => #names, )*
+                }
+            }
+
+            #[inline]
+            fn priority(&self) -> u8 {
+                match self {
+                    #( Self::#variants => #priorities, )*
+                }
+            }
+
+            #[inline]
+            fn is_skipped_by_default(&self) -> bool {
+                match self {
+                    #( Self::#variants => #skips, )*
+                }
+            }
+
+            #[inline]
+            fn parent(&self) -> ::core::option::Option<Self> {
+                match self {
+                    #( Self::#variants => #parents, )*
+                }
+            }
+        }
+
+        impl ::core::convert::From<#ident> for ::froodi::ScopeData {
+            fn from(scope: #ident) -> Self {
+                Self {
+                    priority: ::froodi::Scope::priority(&scope),
+                    name: ::core::convert::Into::into(::froodi::Scope::name(&scope)),
+                    is_skipped_by_default: ::froodi::Scope::is_skipped_by_default(&scope),
+                    parent: ::froodi::Scope::parent(&scope).map(|parent| ::froodi::Scope::priority(&parent)),
+                }
+            }
+        }
+
+        impl ::froodi::Scopes<#n> for #ident {
+            type Scope = Self;
+
+            #[inline]
+            fn all() -> (Self::Scope, [Self::Scope; #n]) {
+                (Self::#first, [#( Self::#rest ),*])
+            }
+        }
+    })
+}
+
+/// `BackgroundJob` -> `background_job`, `HTTPRequest` -> `http_request`
+fn to_snake_case(ident: &str) -> String {
+    let chars: Vec<char> = ident.chars().collect();
+    let mut snake = String::with_capacity(ident.len() + 4);
+    for (index, &char) in chars.iter().enumerate() {
+        if char.is_uppercase() && index > 0 {
+            let prev = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).map_or(false, |next| next.is_lowercase());
+            if prev != '_' && (prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_is_lower)) {
+                snake.push('_');
+            }
+        }
+        snake.extend(char.to_lowercase());
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_snake_case;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("App"), "app");
+        assert_eq!(to_snake_case("BackgroundJob"), "background_job");
+        assert_eq!(to_snake_case("HTTPRequest"), "http_request");
+        assert_eq!(to_snake_case("Step2Retry"), "step2_retry");
+        assert_eq!(to_snake_case("Already_Snake"), "already_snake");
+    }
+}
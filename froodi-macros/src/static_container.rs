@@ -0,0 +1,609 @@
+use alloc::{collections::BTreeMap, format, vec::Vec};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    bracketed, parenthesized,
+    parse::{Parse, ParseStream, Parser as _},
+    punctuated::Punctuated,
+    spanned::Spanned as _,
+    Error, Expr, Ident, Token, Type, Visibility,
+};
+
+use crate::static_registry::{find_cycle, parse_exprs, provided_type, Entry, Kind, Provider};
+
+mod kw {
+    syn::custom_keyword!(scope);
+}
+
+/// `scope(App) as pub AppContainer [ ... ]`
+struct Level {
+    scope: Expr,
+    vis: Visibility,
+    ident: Ident,
+    entries: Vec<LevelEntry>,
+}
+
+struct LevelEntry {
+    instantiator: Expr,
+    provides: Type,
+    config: Option<Expr>,
+    finalizer: Option<Expr>,
+}
+
+impl Parse for Level {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<kw::scope>()?;
+        let scope;
+        parenthesized!(scope in input);
+        let scope = scope.parse()?;
+        input.parse::<Token![as]>()?;
+        let vis = input.parse()?;
+        let ident = input.parse()?;
+
+        let content;
+        let brackets = bracketed!(content in input);
+        let entries = Punctuated::<LevelEntry, Token![,]>::parse_terminated(&content)?;
+        if entries.is_empty() {
+            return Err(Error::new(brackets.span.join(), "`scope` block must contain at least one entry"));
+        }
+        Ok(Self {
+            scope,
+            vis,
+            ident,
+            entries: entries.into_iter().collect(),
+        })
+    }
+}
+
+impl Parse for LevelEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let kind: Ident = input.parse()?;
+        if kind != "provide" {
+            return Err(Error::new_spanned(
+                &kind,
+                format!("Unknown entry `{kind}(...)`. `static_container!` only supports `provide(...)` entries"),
+            ));
+        }
+        let args;
+        let parens = parenthesized!(args in input);
+        let mut args = parse_exprs(args.parse()?)?.into_iter();
+        let instantiator = args
+            .next()
+            .ok_or_else(|| Error::new(parens.span.join(), "`provide` must include an instantiator"))?;
+        let provides = provided_type(&instantiator).ok_or_else(|| {
+            Error::new(
+                instantiator.span(),
+                "The provided type isn't visible to `static_container!`. \
+                 Use a closure with a return type, like `|| -> Result<T, InstantiateErrorKind> { ... }`, or returning `Ok(T { ... })`",
+            )
+        })?;
+
+        let mut config = None;
+        let mut finalizer = None;
+        for arg in args {
+            let Expr::Assign(assign) = &arg else {
+                return Err(Error::new(arg.span(), "Expected `config = ...` or `finalizer = ...`"));
+            };
+            let option = match &*assign.left {
+                Expr::Path(path) if path.path.is_ident("config") => &mut config,
+                Expr::Path(path) if path.path.is_ident("finalizer") => &mut finalizer,
+                left => return Err(Error::new(left.span(), "Expected `config = ...` or `finalizer = ...`")),
+            };
+            if option.replace((*assign.right).clone()).is_some() {
+                return Err(Error::new(assign.left.span(), "Option specified more than once"));
+            }
+        }
+        if let Some(config) = &config {
+            check_config(config)?;
+        }
+        Ok(Self {
+            instantiator,
+            provides,
+            config,
+            finalizer,
+        })
+    }
+}
+
+/// Builder methods of `froodi::Config` setting the fields used by the generated containers
+const SUPPORTED_CONFIG_FIELDS: [&str; 2] = ["cache_provides", "retry"];
+
+/// Rejects configs setting fields the generated containers ignore.
+/// The fields are only visible in builder calls on `default()`, other fields of which are left as is
+fn check_config(config: &Expr) -> syn::Result<()> {
+    match config {
+        Expr::Call(call)
+            if call.args.is_empty()
+                && matches!(&*call.func, Expr::Path(func) if func.path.segments.last().map_or(false, |segment| segment.ident == "default")) =>
+        {
+            Ok(())
+        }
+        Expr::MethodCall(call) => {
+            if !SUPPORTED_CONFIG_FIELDS.iter().any(|supported| call.method == supported) {
+                let name = &call.method;
+                return Err(Error::new(
+                    name.span(),
+                    format!("`{name}` of the config isn't supported by `static_container!`, only `cache_provides` and `retry` are"),
+                ));
+            }
+            check_config(&call.receiver)
+        }
+        Expr::Paren(paren) => check_config(&paren.expr),
+        _ => Err(Error::new(
+            config.span(),
+            "The config must be `Config::default()` with builder calls, like `Config::default().cache_provides(false)`, \
+             so `static_container!` can check that it sets only supported fields",
+        )),
+    }
+}
+
+pub(crate) fn expand(input: TokenStream) -> syn::Result<TokenStream> {
+    let levels: Vec<Level> = Punctuated::<Level, Token![,]>::parse_terminated
+        .parse2(input)?
+        .into_iter()
+        .collect();
+    if levels.is_empty() {
+        return Err(Error::new(
+            proc_macro2::Span::call_site(),
+            "`static_container!` must have at least one scope",
+        ));
+    }
+    check(&levels)?;
+
+    let root_scope = &levels[0].scope;
+    let mut output = TokenStream::new();
+    for (index, level) in levels.iter().enumerate() {
+        let parent = index.checked_sub(1).map(|index| &levels[index]);
+        let child = levels.get(index + 1);
+        output.extend(expand_level(level, parent, child, &levels[..index], root_scope));
+    }
+    Ok(output)
+}
+
+#[allow(clippy::too_many_lines)]
+fn expand_level(level: &Level, parent: Option<&Level>, child: Option<&Level>, ancestors: &[Level], root_scope: &Expr) -> TokenStream {
+    let Level {
+        scope,
+        vis,
+        ident,
+        entries,
+    } = level;
+    let inner = format_ident!("{}Inner", ident);
+    let slots: Vec<Ident> = (0..entries.len()).map(|index| format_ident!("slot_{}", index)).collect();
+    let types: Vec<&Type> = entries.iter().map(|entry| &entry.provides).collect();
+
+    let parent_field = parent.map(|parent| {
+        let parent = &parent.ident;
+        quote!(parent: #parent,)
+    });
+    let constructor = match parent {
+        Some(parent) => {
+            let parent = &parent.ident;
+            quote! {
+                fn from_parent(parent: #parent) -> Self {
+                    Self {
+                        inner: ::froodi::utils::thread_safety::RcThreadSafety::new(#inner {
+                            parent,
+                            #( #slots: ::core::default::Default::default(), )*
+                            resolved: ::core::default::Default::default(),
+                        }),
+                    }
+                }
+            }
+        }
+        None => quote! {
+            /// Creates the container of the root scope
+            #[must_use]
+            pub fn new() -> Self {
+                Self {
+                    inner: ::froodi::utils::thread_safety::RcThreadSafety::new(#inner {
+                        #( #slots: ::core::default::Default::default(), )*
+                        resolved: ::core::default::Default::default(),
+                    }),
+                }
+            }
+        },
+    };
+    let default = parent.is_none().then(|| {
+        quote! {
+            impl ::core::default::Default for #ident {
+                #[inline]
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        }
+    });
+    let enter = child.map(|child| {
+        let child = &child.ident;
+        quote! {
+            /// Enters the child scope
+            #[inline]
+            #[must_use]
+            pub fn enter(self) -> #child {
+                #child::from_parent(self)
+            }
+        }
+    });
+
+    let parent_entries = parent.map(|_| quote!(self.inner.parent.registry_entries(entries);));
+    let child_scopes = ancestors.iter().skip(1).map(|level| &level.scope).chain(parent.map(|_| scope));
+
+    let mut resolve_impls = TokenStream::new();
+    let mut finalize_arms = TokenStream::new();
+    let mut registry_entries = TokenStream::new();
+    for (index, (entry, slot)) in entries.iter().zip(&slots).enumerate() {
+        let LevelEntry {
+            instantiator,
+            provides,
+            config,
+            finalizer,
+        } = entry;
+        let config = config.clone().unwrap_or_else(|| syn::parse_quote!(::froodi::Config::default()));
+        let push_pending = finalizer.as_ref().map(|_| {
+            quote! {
+                self.inner.#slot.push_pending(::core::clone::Clone::clone(&dependency));
+                self.inner.resolved.push(#index);
+            }
+        });
+        let push_created = finalizer.as_ref().map(|_| {
+            quote! {
+                if created {
+                    #push_pending
+                }
+            }
+        });
+
+        resolve_impls.extend(quote! {
+            impl ::froodi::StaticResolve<#provides> for #ident {
+                #[inline]
+                fn resolve(&self) -> ::core::result::Result<::froodi::utils::thread_safety::RcThreadSafety<#provides>, ::froodi::ResolveErrorKind> {
+                    let config: ::froodi::Config = #config;
+                    if !config.cache_provides {
+                        let dependency = ::froodi::utils::thread_safety::RcThreadSafety::new(
+                            ::froodi::macros_utils::static_container::instantiate(self, #instantiator, &config)?,
+                        );
+                        #push_pending
+                        return ::core::result::Result::Ok(dependency);
+                    }
+                    #[allow(unused_variables)]
+                    let (dependency, created) = self
+                        .inner
+                        .#slot
+                        .get_or_try_init(|| ::froodi::macros_utils::static_container::instantiate(self, #instantiator, &config))?;
+                    #push_created
+                    ::core::result::Result::Ok(dependency)
+                }
+            }
+
+            impl ::froodi::StaticResolveTransient<#provides> for #ident {
+                #[inline]
+                fn resolve_transient(&self) -> ::core::result::Result<#provides, ::froodi::ResolveErrorKind> {
+                    let config: ::froodi::Config = #config;
+                    ::froodi::macros_utils::static_container::instantiate(self, #instantiator, &config)
+                }
+            }
+        });
+        if let Some(finalizer) = finalizer {
+            finalize_arms.extend(quote! {
+                #index => {
+                    if let ::core::option::Option::Some(dependency) = self.#slot.pop_pending() {
+                        ::froodi::macros_utils::static_container::finalize(#finalizer, dependency);
+                    }
+                }
+            });
+        }
+        registry_entries.extend(quote! {
+            entries.push(::froodi::macros_utils::static_container::make_entry(
+                #scope,
+                ::core::clone::Clone::clone(self),
+                &(#instantiator),
+                #config,
+            ));
+        });
+    }
+
+    // Dependencies of ancestor scopes are resolved by the parent
+    for ancestor in ancestors {
+        for LevelEntry { provides, .. } in &ancestor.entries {
+            resolve_impls.extend(quote! {
+                impl ::froodi::StaticResolve<#provides> for #ident {
+                    #[inline]
+                    fn resolve(&self) -> ::core::result::Result<::froodi::utils::thread_safety::RcThreadSafety<#provides>, ::froodi::ResolveErrorKind> {
+                        ::froodi::StaticResolve::<#provides>::resolve(&self.inner.parent)
+                    }
+                }
+
+                impl ::froodi::StaticResolveTransient<#provides> for #ident {
+                    #[inline]
+                    fn resolve_transient(&self) -> ::core::result::Result<#provides, ::froodi::ResolveErrorKind> {
+                        ::froodi::StaticResolveTransient::<#provides>::resolve_transient(&self.inner.parent)
+                    }
+                }
+            });
+        }
+    }
+
+    let finalize = (!finalize_arms.is_empty()).then(|| {
+        quote! {
+            while let ::core::option::Option::Some(slot) = self.resolved.pop() {
+                match slot {
+                    #finalize_arms
+                    _ => {}
+                }
+            }
+        }
+    });
+
+    quote! {
+        #vis struct #ident {
+            inner: ::froodi::utils::thread_safety::RcThreadSafety<#inner>,
+        }
+
+        struct #inner {
+            #parent_field
+            #( #slots: ::froodi::macros_utils::static_container::Slot<#types>, )*
+            resolved: ::froodi::macros_utils::static_container::ResolvedOrder,
+        }
+
+        impl #ident {
+            #constructor
+
+            /// Gets a scoped dependency, see `froodi::Container::get`
+            #[inline]
+            #[allow(clippy::missing_errors_doc)]
+            pub fn get<Dep>(&self) -> ::core::result::Result<::froodi::utils::thread_safety::RcThreadSafety<Dep>, ::froodi::ResolveErrorKind>
+            where
+                Self: ::froodi::StaticResolve<Dep>,
+            {
+                ::froodi::StaticResolve::<Dep>::resolve(self)
+            }
+
+            /// Creates a fresh dependency, see `froodi::Container::get_transient`
+            #[inline]
+            #[allow(clippy::missing_errors_doc)]
+            pub fn get_transient<Dep>(&self) -> ::core::result::Result<Dep, ::froodi::ResolveErrorKind>
+            where
+                Self: ::froodi::StaticResolveTransient<Dep>,
+            {
+                ::froodi::StaticResolveTransient::<Dep>::resolve_transient(self)
+            }
+
+            #enter
+
+            /// Closes the container, calling finalizers for resolved dependencies in LIFO order, see `froodi::Container::close`
+            #[inline]
+            pub fn close(&self) {
+                self.inner.close();
+            }
+
+            /// Converts the container into a dynamic `froodi::Container` of the same scope,
+            /// which resolves dependencies from this container and shares its instances
+            #[must_use]
+            #[allow(clippy::missing_panics_doc)]
+            pub fn into_container(self) -> ::froodi::Container {
+                let mut entries = ::froodi::macros_utils::aliases::Vec::new();
+                self.registry_entries(&mut entries);
+                let registry = ::froodi::macros_utils::static_container::build_registry(&(#root_scope), entries);
+                let container = ::froodi::Container::new_with_start_scope(registry, #root_scope);
+                #(
+                    let container = container
+                        .enter()
+                        .with_scope(#child_scopes)
+                        .build()
+                        .expect("scope of a static container should be in its registry");
+                )*
+                container
+            }
+
+            fn registry_entries(&self, entries: &mut ::froodi::macros_utils::aliases::Vec<(::froodi::TypeInfo, ::froodi::InstantiatorData)>) {
+                #parent_entries
+                #registry_entries
+            }
+        }
+
+        #default
+
+        impl ::core::clone::Clone for #ident {
+            #[inline]
+            fn clone(&self) -> Self {
+                Self {
+                    inner: ::core::clone::Clone::clone(&self.inner),
+                }
+            }
+        }
+
+        impl #inner {
+            fn close(&self) {
+                #finalize
+                #( self.#slots.reset(); )*
+            }
+        }
+
+        impl ::core::ops::Drop for #inner {
+            fn drop(&mut self) {
+                self.close();
+            }
+        }
+
+        #resolve_impls
+    }
+}
+
+/// Reports duplicate providers, dependencies on types that aren't provided or provided in a child scope, and cycles
+fn check(levels: &[Level]) -> syn::Result<()> {
+    let mut providers = Vec::new();
+    let mut depths = Vec::new();
+    for (depth, level) in levels.iter().enumerate() {
+        for entry in &level.entries {
+            providers.push(Provider::new(Entry {
+                kind: Kind::Provide,
+                scope: level.scope.clone(),
+                instantiator: entry.instantiator.clone(),
+            }));
+            depths.push(depth);
+        }
+    }
+
+    let mut errors: Vec<Error> = Vec::new();
+    let mut effective: BTreeMap<&str, usize> = BTreeMap::new();
+    for (index, provider) in providers.iter().enumerate() {
+        let key = provider.provides.as_deref().unwrap_or_default();
+        if effective.insert(key, index).is_some() {
+            errors.push(Error::new(provider.span, format!("`{key}` is provided more than once")));
+        }
+    }
+
+    let name = |index: usize| providers[index].provides.as_deref().unwrap_or_default();
+    for (index, provider) in providers.iter().enumerate() {
+        for dependency in &provider.dependencies {
+            match effective.get(dependency.key.as_str()) {
+                Some(&dependency_index) if depths[dependency_index] > depths[index] => {
+                    errors.push(Error::new(
+                        dependency.span,
+                        format!(
+                            "`{}` (container `{}`) depends on `{}`, which is provided in the child container `{}` \
+                             and isn't accessible from `{}`",
+                            name(index),
+                            levels[depths[index]].ident,
+                            dependency.key,
+                            levels[depths[dependency_index]].ident,
+                            levels[depths[index]].ident,
+                        ),
+                    ));
+                }
+                Some(_) => {}
+                // The same type written with another path, like `crate::A` and `A`
+                None if providers
+                    .iter()
+                    .any(|other| other.provides_name.as_deref() == Some(dependency.name.as_str())) => {}
+                None => errors.push(Error::new(
+                    dependency.span,
+                    format!(
+                        "`{}` depends on `{}`, which isn't provided by the container",
+                        name(index),
+                        dependency.key
+                    ),
+                )),
+            }
+        }
+    }
+
+    if let Some(cycle) = find_cycle(&providers, &effective) {
+        let path: Vec<_> = cycle.iter().map(|&index| format!("`{}`", name(index))).collect();
+        errors.push(Error::new(
+            providers[cycle[0]].span,
+            format!("Cyclic dependency: {}", path.join(" -> ")),
+        ));
+    }
+
+    let mut errors = errors.into_iter();
+    match errors.next() {
+        Some(mut error) => {
+            error.extend(errors);
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+    use quote::quote;
+
+    use super::expand;
+
+    fn errors(input: proc_macro2::TokenStream) -> alloc::string::String {
+        expand(input).unwrap_err().to_compile_error().to_string()
+    }
+
+    #[test]
+    fn test_generates_containers() {
+        let output = expand(quote! {
+            scope(App) as pub AppContainer [
+                provide(|| Ok(A)),
+            ],
+            scope(Request) as RequestContainer [
+                provide(|Inject(_): Inject<A>| Ok(B), finalizer = |_: Arc<B>| {}),
+            ],
+        })
+        .unwrap()
+        .to_string();
+        assert!(output.contains("pub struct AppContainer"));
+        assert!(output.contains("struct RequestContainerInner"));
+        assert!(output.contains("impl :: froodi :: StaticResolve < A > for RequestContainer"));
+        assert!(output.contains("pub fn enter (self) -> RequestContainer"));
+    }
+
+    #[test]
+    fn test_reports_problems() {
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+            ],
+            scope(Request) as RequestContainer [
+                provide(|| -> Result<B, E> { Ok(B(1)) }),
+            ],
+        });
+        assert!(
+            output.contains("`A` (container `AppContainer`) depends on `B`, which is provided in the child container `RequestContainer`")
+        );
+
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+                provide(|InjectTransient(_): InjectTransient<A>| Ok(B { value: 1 })),
+            ],
+        });
+        assert!(output.contains("Cyclic dependency: `A` -> `B` -> `A`"));
+
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+                provide(|| Ok(A)),
+            ],
+        });
+        assert!(output.contains("`B`, which isn't provided by the container"));
+        assert!(output.contains("`A` is provided more than once"));
+
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(make_a),
+            ],
+        });
+        assert!(output.contains("The provided type isn't visible"));
+    }
+
+    #[test]
+    fn test_rejects_unsupported_config() {
+        expand(quote! {
+            scope(App) as AppContainer [
+                provide(|| Ok(A), config = Config::default().cache_provides(false).retry(RETRY)),
+                provide(|| Ok(B), config = froodi::Config::default()),
+            ],
+        })
+        .unwrap();
+
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(|| Ok(A), config = Config::default().ttl(Some(Duration::from_secs(1)))),
+            ],
+        });
+        assert!(output.contains("`ttl` of the config isn't supported by `static_container!`"));
+
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(|| Ok(A), config = CONFIG),
+            ],
+        });
+        assert!(output.contains("The config must be `Config::default()` with builder calls"));
+
+        let output = errors(quote! {
+            scope(App) as AppContainer [
+                provide(|| Ok(A), config = CONFIG.cache_provides(false)),
+            ],
+        });
+        assert!(output.contains("The config must be `Config::default()` with builder calls"));
+    }
+}
@@ -0,0 +1,612 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::Parser as _, punctuated::Punctuated, spanned::Spanned as _, Block, Expr, GenericArgument, Pat, Path, PathArguments, ReturnType,
+    Stmt, Token, Type, TypePath,
+};
+
+const DEFAULT_SCOPES: [&str; 6] = ["Runtime", "App", "Session", "Request", "Action", "Step"];
+
+pub(crate) fn expand(input: TokenStream) -> TokenStream {
+    let mut forwarded = TokenStream::new();
+    let mut externals = Vec::new();
+    let mut providers = Vec::new();
+    // Set if there's something providing types the macro can't see
+    let mut opaque = false;
+
+    for (content, raw) in split_by_commas(input) {
+        match parse_item(content) {
+            Item::External(types) => {
+                externals.extend(types);
+                continue;
+            }
+            Item::Entries(entries) => providers.extend(entries.into_iter().map(Provider::new)),
+            Item::Opaque => opaque = true,
+        }
+        forwarded.extend(raw);
+    }
+
+    let errors = check(&providers, &externals, opaque);
+    if let Some(mut error) = errors.first().cloned() {
+        for other in &errors[1..] {
+            error.combine(other.clone());
+        }
+        return error.to_compile_error();
+    }
+
+    let mut registry = quote! { ::froodi::registry! { #forwarded } };
+    for ty in externals {
+        registry = quote! { ::froodi::Registry::with_external::<#ty>(#registry) };
+    }
+    registry
+}
+
+/// Splits the tokens by top-level commas. Returns the content of each part and its raw tokens, including the comma
+fn split_by_commas(input: TokenStream) -> Vec<(TokenStream, TokenStream)> {
+    let mut parts = Vec::new();
+    let mut content = TokenStream::new();
+    for token in input {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => {
+                let mut raw = content.clone();
+                raw.extend([token]);
+                parts.push((core::mem::take(&mut content), raw));
+            }
+            _ => content.extend([token]),
+        }
+    }
+    if !content.is_empty() {
+        parts.push((content.clone(), content));
+    }
+    parts
+}
+
+enum Item {
+    Entries(Vec<Entry>),
+    External(Vec<Type>),
+    /// `extend(...)` or syntax left for `registry!` to report
+    Opaque,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Kind {
+    Default,
+    Provide,
+    Override,
+}
+
+impl Kind {
+    pub(crate) fn from_ident(ident: &str) -> Option<Self> {
+        match ident {
+            "provide" => Some(Self::Provide),
+            "provide_default" => Some(Self::Default),
+            "override" => Some(Self::Override),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) struct Entry {
+    pub(crate) kind: Kind,
+    pub(crate) scope: Expr,
+    pub(crate) instantiator: Expr,
+}
+
+fn parse_item(content: TokenStream) -> Item {
+    let tokens: Vec<TokenTree> = content.into_iter().collect();
+    let (ident, args, body) = match tokens.as_slice() {
+        [TokenTree::Ident(ident), TokenTree::Group(args)] if args.delimiter() == Delimiter::Parenthesis => (ident, args, None),
+        [TokenTree::Ident(ident), TokenTree::Group(args), TokenTree::Group(body)]
+            if args.delimiter() == Delimiter::Parenthesis && body.delimiter() == Delimiter::Bracket =>
+        {
+            (ident, args, Some(body))
+        }
+        _ => return Item::Opaque,
+    };
+    let ident = ident.to_string();
+
+    let parsed = match (ident.as_str(), body) {
+        ("external", None) => Punctuated::<Type, Token![,]>::parse_terminated
+            .parse2(args.stream())
+            .map(|types| Item::External(types.into_iter().collect())),
+        ("scope", Some(body)) => parse_exprs(args.stream()).and_then(|scope| {
+            let scope = scope
+                .into_iter()
+                .next()
+                .ok_or_else(|| syn::Error::new(args.span(), "missing scope"))?;
+            split_by_commas(body.stream())
+                .into_iter()
+                .map(|(entry, _)| parse_entry(entry, Some(&scope)))
+                .collect::<Option<Vec<_>>>()
+                .map(Item::Entries)
+                .ok_or_else(|| syn::Error::new(body.span(), "malformed entries"))
+        }),
+        (_, None) => parse_entry(tokens.iter().cloned().collect(), None)
+            .map(|entry| Item::Entries(alloc::vec![entry]))
+            .ok_or_else(|| syn::Error::new(args.span(), "malformed entry")),
+        _ => return Item::Opaque,
+    };
+    parsed.unwrap_or(Item::Opaque)
+}
+
+/// Parses `kind(instantiator, ...)` inside a `scope(...)` block, or `kind(scope, instantiator, ...)` outside of it
+fn parse_entry(tokens: TokenStream, scope: Option<&Expr>) -> Option<Entry> {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let [TokenTree::Ident(ident), TokenTree::Group(args)] = tokens.as_slice() else {
+        return None;
+    };
+    let kind = Kind::from_ident(&ident.to_string())?;
+    let mut args = parse_exprs(args.stream()).ok()?.into_iter();
+    let scope = match scope {
+        Some(scope) => scope.clone(),
+        None => args.next()?,
+    };
+    Some(Entry {
+        kind,
+        scope,
+        instantiator: args.next()?,
+    })
+}
+
+pub(crate) fn parse_exprs(tokens: TokenStream) -> syn::Result<Punctuated<Expr, Token![,]>> {
+    Punctuated::parse_terminated.parse2(tokens)
+}
+
+pub(crate) struct Dependency {
+    pub(crate) key: String,
+    pub(crate) name: String,
+    pub(crate) span: Span,
+}
+
+pub(crate) struct Provider {
+    pub(crate) kind: Kind,
+    pub(crate) scope: Expr,
+    pub(crate) provides: Option<String>,
+    /// Last path segment of the provided type, see [`type_name`]
+    pub(crate) provides_name: Option<String>,
+    pub(crate) dependencies: Vec<Dependency>,
+    pub(crate) span: Span,
+}
+
+impl Provider {
+    pub(crate) fn new(Entry { kind, scope, instantiator }: Entry) -> Self {
+        let mut provides = None;
+        let mut provides_name = None;
+        let mut dependencies = Vec::new();
+        if let Expr::Closure(closure) = &instantiator {
+            if let Some(ty) = provided_type(&instantiator) {
+                provides = Some(type_key(&ty));
+                provides_name = Some(type_name(&ty));
+            }
+            for input in &closure.inputs {
+                if let Pat::Type(pat) = input {
+                    collect_dependencies(&pat.ty, &mut dependencies);
+                }
+            }
+        }
+        Self {
+            kind,
+            scope,
+            provides,
+            provides_name,
+            dependencies,
+            span: instantiator.span(),
+        }
+    }
+}
+
+/// Type provided by a closure instantiator, from its return type or body
+pub(crate) fn provided_type(instantiator: &Expr) -> Option<Type> {
+    let Expr::Closure(closure) = instantiator else {
+        return None;
+    };
+    match &closure.output {
+        ReturnType::Type(_, ty) => first_type_argument(ty),
+        ReturnType::Default => provided_by_body(&closure.body),
+    }
+}
+
+/// `A` of `Result<A, E>`, `InstantiatorResult<A>` and so on
+fn first_type_argument(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => match &path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first()? {
+                GenericArgument::Type(ty) => Some(ty.clone()),
+                _ => None,
+            },
+            _ => None,
+        },
+        Type::Group(group) => first_type_argument(&group.elem),
+        Type::Paren(paren) => first_type_argument(&paren.elem),
+        _ => None,
+    }
+}
+
+fn tail(expr: &Expr) -> Option<&Expr> {
+    match expr {
+        Expr::Block(block) => block_tail(&block.block),
+        Expr::Async(block) => block_tail(&block.block),
+        Expr::Paren(paren) => tail(&paren.expr),
+        Expr::Group(group) => tail(&group.expr),
+        expr => Some(expr),
+    }
+}
+
+fn block_tail(block: &Block) -> Option<&Expr> {
+    match block.stmts.last()? {
+        Stmt::Expr(expr, None) => tail(expr),
+        _ => None,
+    }
+}
+
+/// The type of `Ok::<A, _>(...)`, `Ok(A)`, `Ok(A(...))` or `Ok(A { ... })`
+fn provided_by_body(body: &Expr) -> Option<Type> {
+    let Expr::Call(call) = tail(body)? else {
+        return None;
+    };
+    let Expr::Path(func) = &*call.func else {
+        return None;
+    };
+    let ok = func.path.segments.last()?;
+    if ok.ident != "Ok" {
+        return None;
+    }
+    if let PathArguments::AngleBracketed(args) = &ok.arguments {
+        if let Some(GenericArgument::Type(ty)) = args.args.first() {
+            return Some(ty.clone());
+        }
+    }
+    let [value] = call.args.iter().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let path = match value {
+        Expr::Path(path) if path.qself.is_none() => &path.path,
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(path) if path.qself.is_none() => &path.path,
+            _ => return None,
+        },
+        Expr::Struct(value) if value.qself.is_none() => &value.path,
+        _ => return None,
+    };
+    is_type_name(path).then(|| {
+        Type::Path(TypePath {
+            qself: None,
+            path: path.clone(),
+        })
+    })
+}
+
+/// Whether the path is a single `CamelCase` segment that isn't a constant or a prelude variant
+fn is_type_name(path: &Path) -> bool {
+    let [segment] = path.segments.iter().collect::<Vec<_>>()[..] else {
+        return false;
+    };
+    let name = segment.ident.to_string();
+    let is_constant = name.len() > 1 && !name.chars().any(|char| char.is_ascii_lowercase());
+    name.starts_with(|char: char| char.is_ascii_uppercase())
+        && !is_constant
+        && !matches!(name.as_str(), "Self" | "Some" | "None" | "Ok" | "Err")
+}
+
+fn collect_dependencies(ty: &Type, dependencies: &mut Vec<Dependency>) {
+    match ty {
+        Type::Tuple(tuple) => {
+            for elem in &tuple.elems {
+                collect_dependencies(elem, dependencies);
+            }
+        }
+        Type::Group(group) => collect_dependencies(&group.elem, dependencies),
+        Type::Paren(paren) => collect_dependencies(&paren.elem, dependencies),
+        Type::Path(TypePath { qself: None, path }) => {
+            let Some(segment) = path.segments.last() else {
+                return;
+            };
+            if segment.ident != "Inject" && segment.ident != "InjectTransient" {
+                return;
+            }
+            if let Some(dependency) = first_type_argument(ty) {
+                dependencies.push(Dependency {
+                    key: type_key(&dependency),
+                    name: type_name(&dependency),
+                    span: dependency.span(),
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Types are compared by the full path, so `a::Config` and `b::Config` are different types
+pub(crate) fn type_key(ty: &Type) -> String {
+    match ty {
+        Type::Group(group) => type_key(&group.elem),
+        Type::Paren(paren) => type_key(&paren.elem),
+        ty => {
+            let key = tokens_to_string(ty);
+            let key = key.strip_prefix("::").unwrap_or(&key);
+            key.strip_prefix("self::").unwrap_or(key).to_string()
+        }
+    }
+}
+
+/// Last path segment of the type. The same type may be written as `crate::A` and `A`,
+/// so a type is reported as missing only if no provided type has the same name
+pub(crate) fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => path.segments.last().map(tokens_to_string).unwrap_or_default(),
+        Type::Group(group) => type_name(&group.elem),
+        Type::Paren(paren) => type_name(&paren.elem),
+        ty => tokens_to_string(ty),
+    }
+}
+
+fn tokens_to_string(tokens: impl ToTokens) -> String {
+    tokens.to_token_stream().to_string().replace(' ', "")
+}
+
+/// Name and priority of a [`froodi::DefaultScope`] variant.
+/// Only variants qualified with `DefaultScope::` are known, since a bare `App` may be a variant of another scope,
+/// and the priorities of [`froodi::DefaultScope`] follow its ancestry, which isn't true for scope trees
+fn default_scope(scope: &Expr) -> Option<(String, u8)> {
+    let Expr::Path(path) = scope else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+    let segments: Vec<String> = path.path.segments.iter().map(|segment| segment.ident.to_string()).collect();
+    let [.., qualifier, name] = segments.as_slice() else {
+        return None;
+    };
+    if qualifier != "DefaultScope" {
+        return None;
+    }
+    let priority = DEFAULT_SCOPES.iter().position(|scope| scope == name)?;
+    Some((name.clone(), u8::try_from(priority).ok()?))
+}
+
+fn check(providers: &[Provider], externals: &[Type], opaque: bool) -> Vec<syn::Error> {
+    let scopes: Vec<_> = providers.iter().map(|provider| default_scope(&provider.scope)).collect();
+
+    // The provider used for each type: overrides replace providers, which replace defaults
+    let mut effective: BTreeMap<&str, usize> = BTreeMap::new();
+    for (index, provider) in providers.iter().enumerate() {
+        if let Some(key) = &provider.provides {
+            let current = effective.entry(key).or_insert(index);
+            if providers[*current].kind <= provider.kind {
+                *current = index;
+            }
+        }
+    }
+    let is_used = |index: usize| {
+        providers[index]
+            .provides
+            .as_deref()
+            .map_or(true, |key| effective.get(key) == Some(&index))
+    };
+    let name = |index: usize| providers[index].provides.as_deref().unwrap_or("instantiator");
+
+    let externals: Vec<String> = externals.iter().map(type_name).collect();
+    let is_provided = |name: &str| providers.iter().any(|provider| provider.provides_name.as_deref() == Some(name));
+    let sees_all_providers = !opaque && providers.iter().all(|provider| provider.provides.is_some());
+
+    let mut errors = Vec::new();
+    for (index, provider) in providers.iter().enumerate() {
+        if !is_used(index) {
+            continue;
+        }
+        for dependency in &provider.dependencies {
+            match effective.get(dependency.key.as_str()) {
+                Some(&dependency_index) => {
+                    if let (Some((scope, priority)), Some((dependency_scope, dependency_priority))) =
+                        (&scopes[index], &scopes[dependency_index])
+                    {
+                        if dependency_priority > priority {
+                            errors.push(syn::Error::new(
+                                dependency.span,
+                                format!(
+                                    "`{}` (scope `{scope}`) depends on `{}`, which is provided in the child scope `{dependency_scope}` \
+                                     and isn't accessible from `{scope}`",
+                                    name(index),
+                                    dependency.key,
+                                ),
+                            ));
+                        }
+                    }
+                }
+                None if sees_all_providers
+                    && dependency.name != "Container"
+                    && !externals.contains(&dependency.name)
+                    && !is_provided(&dependency.name) =>
+                {
+                    errors.push(syn::Error::new(
+                        dependency.span,
+                        format!(
+                            "`{}` depends on `{}`, which isn't provided by the registry. \
+                             Provide it or declare it with `external({})`",
+                            name(index),
+                            dependency.key,
+                            dependency.key,
+                        ),
+                    ));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let Some(cycle) = find_cycle(providers, &effective) {
+        let path: Vec<String> = cycle.iter().map(|&index| format!("`{}`", name(index))).collect();
+        errors.push(syn::Error::new(
+            providers[cycle[0]].span,
+            format!("Cyclic dependency: {}", path.join(" -> ")),
+        ));
+    }
+    errors
+}
+
+/// Returns the providers of the first found cycle, the first one is repeated at the end
+pub(crate) fn find_cycle(providers: &[Provider], effective: &BTreeMap<&str, usize>) -> Option<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        New,
+        InStack,
+        Done,
+    }
+
+    fn visit(
+        index: usize,
+        providers: &[Provider],
+        effective: &BTreeMap<&str, usize>,
+        states: &mut [State],
+        stack: &mut Vec<usize>,
+    ) -> Option<Vec<usize>> {
+        states[index] = State::InStack;
+        stack.push(index);
+        for dependency in &providers[index].dependencies {
+            let Some(&next) = effective.get(dependency.key.as_str()) else {
+                continue;
+            };
+            match states[next] {
+                State::InStack => {
+                    let start = stack.iter().position(|&index| index == next)?;
+                    let mut cycle = stack[start..].to_vec();
+                    cycle.push(next);
+                    return Some(cycle);
+                }
+                State::New => {
+                    if let Some(cycle) = visit(next, providers, effective, states, stack) {
+                        return Some(cycle);
+                    }
+                }
+                State::Done => {}
+            }
+        }
+        stack.pop();
+        states[index] = State::Done;
+        None
+    }
+
+    let mut states = alloc::vec![State::New; providers.len()];
+    let mut stack = Vec::new();
+    for &index in effective.values() {
+        if states[index] == State::New {
+            if let Some(cycle) = visit(index, providers, effective, &mut states, &mut stack) {
+                return Some(cycle);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString as _;
+    use quote::quote;
+
+    use super::expand;
+
+    fn errors(input: proc_macro2::TokenStream) -> alloc::string::String {
+        expand(input).to_string()
+    }
+
+    #[test]
+    fn test_forwards_entries() {
+        let output = expand(quote! {
+            scope(App) [
+                provide(|| Ok(A)),
+            ],
+            external(B),
+        });
+        assert_eq!(
+            output.to_string(),
+            quote! { ::froodi::Registry::with_external::<B>(::froodi::registry! { scope(App) [provide(|| Ok(A)),], }) }.to_string(),
+        );
+    }
+
+    #[test]
+    fn test_reports_problems() {
+        let output = errors(quote! {
+            scope(DefaultScope::App) [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+            ],
+            scope(froodi::DefaultScope::Request) [
+                provide(|| -> Result<B, E> { Ok(B(1)) }),
+            ],
+        });
+        assert!(output.contains("compile_error"));
+        assert!(output.contains("`A` (scope `App`) depends on `B`, which is provided in the child scope `Request`"));
+
+        let output = errors(quote! {
+            scope(App) [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+                provide(|InjectTransient(_): InjectTransient<A>| Ok(B { value: 1 })),
+            ],
+        });
+        assert!(output.contains("Cyclic dependency: `A` -> `B` -> `A`"));
+
+        let output = errors(quote! {
+            provide(App, |Inject(_): Inject<B>| Ok(A)),
+        });
+        assert!(output.contains("`A` depends on `B`, which isn't provided by the registry"));
+    }
+
+    #[test]
+    fn test_skips_unseen_providers() {
+        // `inst` may provide `B`, and custom scopes have unknown priorities
+        let output = errors(quote! {
+            scope(App) [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+                provide(inst),
+            ],
+        });
+        assert!(!output.contains("compile_error"));
+
+        // Bare names may be variants of another scope type
+        let output = errors(quote! {
+            scope(App) [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+            ],
+            scope(Request) [
+                provide(|| Ok(B)),
+            ],
+        });
+        assert!(!output.contains("compile_error"));
+
+        let output = errors(quote! {
+            scope(MyScope::First) [
+                provide(|Inject(_): Inject<B>| Ok(A)),
+            ],
+            scope(MyScope::Second) [
+                provide(|| Ok(B)),
+            ],
+        });
+        assert!(!output.contains("compile_error"));
+    }
+
+    #[test]
+    fn test_compares_full_paths() {
+        let output = errors(quote! {
+            scope(App) [
+                provide(|Inject(_): Inject<b::Config>| -> Result<a::Config, E> { Ok(a::Config) }),
+                provide(|| -> Result<b::Config, E> { Ok(b::Config) }),
+            ],
+        });
+        assert!(!output.contains("compile_error"));
+
+        // The same type written with another path isn't reported as missing
+        let output = errors(quote! {
+            scope(App) [
+                provide(|Inject(_): Inject<Config>| Ok(A)),
+                provide(|| -> Result<crate::Config, E> { Ok(crate::Config) }),
+            ],
+        });
+        assert!(!output.contains("compile_error"));
+    }
+}
@@ -1,7 +1,10 @@
 #![allow(dead_code)]
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use froodi::{async_impl::Container, async_registry, utils::thread_safety::RcThreadSafety, DefaultScope::*, Inject, InjectTransient};
+use froodi::{
+    async_impl::Container, async_registry, utils::thread_safety::RcThreadSafety, Config, DefaultScope::*, Inject, InjectTransient,
+};
+use futures::FutureExt as _;
 use tokio::runtime::Builder;
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -18,6 +21,34 @@ fn criterion_benchmark(c: &mut Criterion) {
             async move { container.get::<A>().await.unwrap() }
         });
     })
+    .bench_function("async_get_hit", |b| {
+        struct A;
+
+        // Polls a cache hit once without a runtime, so only the hit path is measured
+        let container = Container::new(async_registry! {
+            scope(App) [
+                provide(async || Ok(A)),
+            ],
+        });
+        futures::executor::block_on(container.get::<A>()).unwrap();
+        b.iter(|| container.get::<A>().now_or_never().unwrap().unwrap());
+    })
+    .bench_function("async_get_uncached_single", |b| {
+        struct A;
+
+        let container = Container::new(async_registry! {
+            scope(App) [
+                provide(
+                    async || Ok(A),
+                    config = Config::default().cache_provides(false),
+                ),
+            ],
+        });
+        b.to_async(Builder::new_current_thread().build().unwrap()).iter(|| {
+            let container = container.clone();
+            async move { container.get::<A>().await.unwrap() }
+        });
+    })
     .bench_function("async_get_many", |b| {
         struct A(RcThreadSafety<B>, RcThreadSafety<C>);
         struct B(i32);
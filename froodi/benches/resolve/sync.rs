@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 
 use criterion::{criterion_group, criterion_main, Criterion};
-use froodi::{registry, utils::thread_safety::RcThreadSafety, Container, DefaultScope::*, Inject, InjectTransient};
+use froodi::{registry, utils::thread_safety::RcThreadSafety, Config, Container, DefaultScope::*, Inject, InjectTransient};
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("sync_get_single", |b| {
@@ -14,6 +14,19 @@ fn criterion_benchmark(c: &mut Criterion) {
         });
         b.iter(|| container.get::<A>().unwrap());
     })
+    .bench_function("sync_get_uncached_single", |b| {
+        struct A([u64; 8]);
+
+        let container = Container::new(registry! {
+            scope(App) [
+                provide(
+                    || Ok(A([0; 8])),
+                    config = Config::default().cache_provides(false),
+                ),
+            ],
+        });
+        b.iter(|| container.get::<A>().unwrap());
+    })
     .bench_function("sync_get_many", |b| {
         struct A(RcThreadSafety<B>, RcThreadSafety<C>);
         struct B(i32);
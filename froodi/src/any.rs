@@ -97,10 +97,16 @@ impl TypeInfo {
     #[inline]
     #[must_use]
     pub(crate) fn short_name(&self) -> &'static str {
-        self.name.rsplit_once("::").map_or(self.name, |(_, name)| name)
+        short_type_name(self.name)
     }
 }
 
+#[inline]
+#[must_use]
+pub(crate) fn short_type_name(name: &str) -> &str {
+    name.rsplit_once("::").map_or(name, |(_, name)| name)
+}
+
 pub(crate) type Map = BTreeMap<TypeInfo, RcAnyThreadSafety>;
 
 #[cfg(test)]
@@ -3,8 +3,14 @@ pub(crate) mod finalizer;
 pub(crate) mod instantiator;
 pub(crate) mod registry;
 pub(crate) mod service;
+pub(crate) mod typed;
+
+#[cfg(feature = "local_async")]
+pub mod local;
+pub mod runtime;
 
 pub use container::Container;
 pub use finalizer::Finalizer;
 pub use instantiator::Instantiator;
 pub use registry::{InstantiatorData, Registry, RegistryWithSync};
+pub use typed::TypedContainer;
@@ -1,25 +1,41 @@
-use alloc::{boxed::Box, vec::Vec};
-use core::future::Future;
-use parking_lot::RwLock;
+#[cfg(feature = "local_async")]
+use alloc::collections::btree_set::BTreeSet;
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use core::{
+    fmt::{self, Debug, Formatter},
+    future::{Future, Ready},
+};
 use tracing::{debug, error, trace, warn};
 
 use super::{
     registry::{InstantiatorData, Registry},
+    runtime,
     service::Service as _,
 };
+#[cfg(feature = "local_async")]
+use crate::dependency::Dependency;
 #[cfg(feature = "thread_safe")]
 use crate::lock::PerTypeLocks;
 use crate::{
     any::TypeInfo,
     async_impl::registry::RegistryWithSync,
-    cache::{Cache, Resolved},
+    cache::{Cache, Resolved, SharedCache},
+    clock,
+    config::{Config, Refresh},
     container::{BoxedContainerInner as BoxedSyncContainerInner, Container as SyncContainer, ContainerInner as SyncContainerInner},
     context::Context,
-    errors::{InstantiatorErrorKind, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind},
+    errors::{InstantiateErrorKind, InstantiatorErrorKind, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind},
+    inject::InjectWeak,
+    instantiator::Instance,
     lock::PerTypeSharedLocks,
+    plan::{PlanProblem, ResolutionPlan},
     registry::Registry as SyncRegistry,
     scope::{Scope, ScopeData, ScopeDataWithChildScopesData},
-    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
+    snapshot::{ContainerSnapshot, LevelSnapshot},
+    utils::{
+        future::BoxFuture,
+        thread_safety::{RcAnyThreadSafety, RcThreadSafety, SendSafety, SyncSafety},
+    },
 };
 
 #[derive(Clone)]
@@ -61,21 +77,21 @@ impl Container {
     }
 
     /// Builds the root container chain (the async inner and the embedded sync inner in lockstep):
-    /// starts at the registry's lowest-priority scope and descends, keeping each level as a parent
-    /// with `close_parent = true`, until `is_target` accepts a scope. Shared by [`Self::new`]
+    /// starts at the registry's lowest-priority scope and descends the branch to the first scope accepted
+    /// by `is_target`, keeping each level as a parent with `close_parent = true`. Shared by [`Self::new`]
     /// (target = first non-skipped scope) and [`Self::new_with_start_scope`] (target = requested priority).
     ///
     /// # Panics
     /// Panics if the scopes are exhausted before `is_target` accepts one.
     fn build_root(RegistryWithSync { registry, sync }: RegistryWithSync, is_target: impl Fn(&ScopeData) -> bool) -> Self {
         let mut scopes = registry.get_scope_with_child_scopes();
-        let registry = RcThreadSafety::new(registry);
-        let sync_registry = RcThreadSafety::new(sync);
+        let registry = RcThreadSafety::new(registry.freeze());
+        let sync_registry = RcThreadSafety::new(sync.freeze());
         let mut sync_container = BoxedSyncContainerInner {
-            cache: Cache::new(),
+            cache: Cache::with_index(sync_registry.slot_index()),
             context: Context::new(),
             registry: sync_registry.clone(),
-            scope_data: scopes.scope_data.expect("scopes len (is 0) should be > 0"),
+            scope_data: scopes.scope_data.clone().expect("scopes len (is 0) should be > 0"),
             child_scopes_data: scopes.child_scopes_data.clone(),
             parent: None,
             close_parent: false,
@@ -83,18 +99,18 @@ impl Container {
             per_type_locks: PerTypeLocks::default(),
         };
         let mut container = BoxedContainerInner {
-            cache: Cache::new(),
+            cache: Cache::with_index(registry.slot_index()),
             context: Context::new(),
             registry: registry.clone(),
-            scope_data: scopes.scope_data.expect("scopes len (is 0) should be > 0"),
+            scope_data: scopes.scope_data.clone().expect("scopes len (is 0) should be > 0"),
             child_scopes_data: scopes.child_scopes_data.clone(),
             parent: None,
             close_parent: false,
         };
         while !is_target(&container.scope_data) {
-            scopes = scopes.child();
-            let scope_data = scopes.scope_data.expect("scope chain ended before reaching a target scope");
-            sync_container = sync_container.init_child(sync_registry.clone(), scope_data, scopes.child_scopes_data.clone(), true);
+            scopes = scopes.child(&is_target).expect("scope tree ended before reaching a target scope");
+            let scope_data = scopes.scope_data.clone().expect("child scope should have scope data");
+            sync_container = sync_container.init_child(sync_registry.clone(), scope_data.clone(), scopes.child_scopes_data.clone(), true);
             container = container.init_child(registry.clone(), scope_data, scopes.child_scopes_data.clone(), true);
         }
         (container, sync_container).into()
@@ -129,122 +145,105 @@ impl Container {
     /// so it should be used for dependencies that are cached or shared,
     /// and with optional finalizer.
     #[inline]
-    #[allow(clippy::missing_errors_doc, clippy::multiple_bound_locations, clippy::missing_panics_doc)]
+    #[allow(
+        clippy::missing_errors_doc,
+        clippy::multiple_bound_locations,
+        clippy::missing_panics_doc,
+        clippy::manual_async_fn
+    )]
     pub fn get<Dep: SendSafety + SyncSafety + 'static>(
         &self,
     ) -> impl Future<Output = Result<RcThreadSafety<Dep>, ResolveErrorKind>> + SendSafety + '_ {
-        let type_info = TypeInfo::of::<Dep>();
-        let dep_name = type_info.name;
-        let scope_name = self.inner.scope_data.name;
-        let fut = async move {
-            if let Some(dependency) = { self.inner.cache.read().get(&type_info) } {
+        async move {
+            let type_info = TypeInfo::of::<Dep>();
+            let now = || clock::now(self.inner.registry.clock.as_ref());
+            if let Some(dependency) = { self.inner.cache.get_fresh(&type_info, now)? } {
                 debug!("Found in cache");
                 return Ok(dependency);
             }
             debug!("Not found in cache");
 
-            let Some(InstantiatorData {
-                instantiator,
-                finalizer,
-                config,
-                scope_data,
-                ..
-            }) = self.inner.registry.get(&type_info)
-            else {
+            self.get_uncached(type_info).await
+        }
+    }
+
+    /// Resolves a scoped dependency that isn't cached in the container.
+    /// Only this path is boxed, because instantiators resolve their dependencies with [`Self::get`] recursively.
+    fn get_uncached<Dep: SendSafety + SyncSafety + 'static>(
+        &self,
+        type_info: TypeInfo,
+    ) -> BoxFuture<'_, Result<RcThreadSafety<Dep>, ResolveErrorKind>> {
+        Box::pin(async move {
+            let Some(data) = self.inner.registry.get(&type_info) else {
                 debug!("No instantiator found, trying sync container");
                 return self.sync.get();
             };
+            self.get_with(type_info, data).await
+        })
+    }
 
-            let current_priority = self.inner.scope_data.priority;
-            let dep_priority = scope_data.priority;
-
-            if current_priority > dep_priority {
-                let mut parent = self
-                    .inner
-                    .parent
-                    .as_ref()
-                    .expect("parent should exist for lower-priority dependency");
-                while parent.scope_data.priority != dep_priority {
-                    parent = parent.parent.as_ref().expect("parent with target priority should exist");
-                }
+    /// Resolves a scoped dependency that isn't cached in the container with its provider,
+    /// in the container of the scope of the provider
+    pub(super) async fn get_with<Dep: SendSafety + SyncSafety + 'static, P: Provider>(
+        &self,
+        type_info: TypeInfo,
+        provider: &P,
+    ) -> Result<RcThreadSafety<Dep>, ResolveErrorKind> {
+        let dep_name = type_info.name;
+        let scope_name = &*self.inner.scope_data.name;
+        let scope_data = provider.scope_data();
 
-                return match (Self {
-                    inner: parent.clone(),
-                    sync: self.sync.clone(),
-                    per_type_locks: self.per_type_locks.clone(),
-                })
-                .get::<Dep>()
-                .await
-                {
-                    Ok(dependency) => {
-                        if config.cache_provides {
-                            self.inner.cache.write().insert_rc(type_info, dependency.clone());
-                        }
-                        Ok(dependency)
-                    }
-                    Err(err) => Err(err),
-                };
-            }
-            if dep_priority > current_priority {
+        if scope_data.priority != self.inner.scope_data.priority {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
                 let err = ResolveErrorKind::NoAccessible {
-                    expected_scope_data: *scope_data,
-                    actual_scope_data: self.inner.scope_data,
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
                 };
                 error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
                 return Err(err);
-            }
-
-            trace!("Lock instantiator call");
-            let inst_call_lock = self.per_type_locks.get(type_info.id);
-            let _guard = inst_call_lock.lock().await;
+            };
 
-            if let Some(dependency) = { self.inner.cache.read().get(&type_info) } {
-                debug!("Found in cache after lock");
-                return Ok(dependency);
+            let now = || clock::now(parent.registry.clock.as_ref());
+            let dependency = if let Some(dependency) = { parent.cache.get_fresh::<Dep>(&type_info, now)? } {
+                debug!("Found in cache of parent");
+                dependency
+            } else {
+                Self {
+                    inner: parent.clone(),
+                    sync: self.sync.clone(),
+                    per_type_locks: self.per_type_locks.clone(),
+                }
+                .get_in_scope(provider)
+                .await?
+            };
+            if provider.config().cache_provides {
+                let expires_at = parent.cache.try_read(&type_info)?.expiration(&type_info);
+                let mut cache = self.inner.cache.try_write(&type_info)?;
+                cache.insert_rc(type_info.clone(), dependency.clone());
+                cache.set_expiration(type_info, expires_at);
             }
+            return Ok(dependency);
+        }
 
-            match instantiator.clone().call(self.clone()).await {
-                Ok(dependency) => match dependency.downcast::<Dep>() {
-                    Ok(dependency) => {
-                        let dependency = RcThreadSafety::new(*dependency);
-                        let cache_provides = config.cache_provides;
-                        let has_finalizer = finalizer.is_some();
-                        if cache_provides || has_finalizer {
-                            let mut cache = self.inner.cache.write();
-                            if cache_provides {
-                                cache.insert_rc(type_info.clone(), dependency.clone());
-                                debug!("Cached");
-                            }
-                            if has_finalizer {
-                                cache.push_resolved(Resolved {
-                                    type_info,
-                                    dependency: dependency.clone(),
-                                });
-                                debug!("Pushed to resolved set");
-                            }
-                        }
-                        Ok(dependency)
-                    }
-                    Err(incorrect_type) => {
-                        let err = ResolveErrorKind::IncorrectType {
-                            expected: type_info,
-                            actual: TypeInfo::of_val(&*incorrect_type),
-                        };
-                        error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
-                        Err(err)
-                    }
-                },
-                Err(InstantiatorErrorKind::Deps(err)) => {
-                    error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
-                    Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err))))
-                }
-                Err(InstantiatorErrorKind::Factory(err)) => {
-                    error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
-                    Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(err)))
+        self.get_in_scope(provider).await
+    }
+
+    /// Resolves a scoped dependency of the current scope that isn't cached in the container
+    async fn get_in_scope<Dep: SendSafety + SyncSafety + 'static, P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<RcThreadSafety<Dep>, ResolveErrorKind> {
+        let type_info = TypeInfo::of::<Dep>();
+        if provider.config().refresh == Refresh::Background && P::SPAWNS {
+            if let Some(dependency) = { self.inner.cache.try_read(&type_info)?.get(&type_info) } {
+                // Stale-while-revalidate: the expired instance is returned while the new one is instantiated
+                if self.refresh_in_background::<Dep>() {
+                    return Ok(dependency);
                 }
             }
-        };
-        Box::pin(fut)
+        }
+
+        self.instantiate_scoped(provider).await
     }
 
     /// Gets a transient dependency from the container
@@ -253,72 +252,245 @@ impl Container {
     /// This method resolves a new instance of the dependency each time it is called,
     /// so it should be used for dependencies that are not cached or shared, and without finalizer.
     #[inline]
-    #[allow(clippy::missing_errors_doc, clippy::multiple_bound_locations, clippy::missing_panics_doc)]
+    #[allow(
+        clippy::missing_errors_doc,
+        clippy::multiple_bound_locations,
+        clippy::missing_panics_doc,
+        clippy::manual_async_fn
+    )]
     pub fn get_transient<Dep: 'static>(&self) -> impl Future<Output = Result<Dep, ResolveErrorKind>> + SendSafety + '_ {
-        let type_info = TypeInfo::of::<Dep>();
-        let dep_name = type_info.name;
-        let scope_name = self.inner.scope_data.name;
-        let fut = async move {
-            let Some(InstantiatorData {
-                instantiator, scope_data, ..
-            }) = self.inner.registry.get(&type_info)
-            else {
+        async move {
+            let type_info = TypeInfo::of::<Dep>();
+            let Some(data) = self.inner.registry.get(&type_info) else {
                 debug!("No instantiator found, trying sync container");
                 return self.sync.get_transient();
             };
+            self.get_transient_with(type_info, data).await
+        }
+    }
 
-            let current_priority = self.inner.scope_data.priority;
-            let dep_priority = scope_data.priority;
-
-            if current_priority > dep_priority {
-                let mut parent = self
-                    .inner
-                    .parent
-                    .as_ref()
-                    .expect("parent should exist for lower-priority dependency");
-                while parent.scope_data.priority != dep_priority {
-                    parent = parent.parent.as_ref().expect("parent with target priority should exist");
+    /// Resolves a transient dependency with its provider, in the container of the scope of the provider
+    pub(super) async fn get_transient_with<Dep: 'static, P: Provider>(
+        &self,
+        type_info: TypeInfo,
+        provider: &P,
+    ) -> Result<Dep, ResolveErrorKind> {
+        let dep_name = type_info.name;
+        let scope_name = &*self.inner.scope_data.name;
+        let scope_data = provider.scope_data();
+
+        let ancestor;
+        let container = if scope_data.priority == self.inner.scope_data.priority {
+            self
+        } else {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
+                let err = ResolveErrorKind::NoAccessible {
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
+                };
+                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
+                return Err(err);
+            };
+            ancestor = Self {
+                inner: parent.clone(),
+                sync: self.sync.clone(),
+                per_type_locks: self.per_type_locks.clone(),
+            };
+            &ancestor
+        };
+
+        match container.instantiate(provider, &type_info, false).await {
+            Ok(dependency) => match dependency.downcast_owned::<Dep>() {
+                Ok(dependency) => Ok(dependency),
+                Err(incorrect_type) => {
+                    let err = ResolveErrorKind::IncorrectType {
+                        expected: type_info,
+                        actual: incorrect_type.type_info(),
+                    };
+                    error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
+                    Err(err)
                 }
-                return (Self {
-                    inner: parent.clone(),
-                    sync: self.sync.clone(),
-                    per_type_locks: self.per_type_locks.clone(),
-                })
-                .get_transient()
-                .await;
+            },
+            Err(err) => {
+                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
+                Err(err)
             }
-            if dep_priority > current_priority {
+        }
+    }
+
+    /// Gets a weak reference to the instance of a dependency cached by the container of its scope, without instantiating it,
+    /// see [`InjectWeak`]
+    pub(crate) fn get_weak<Dep: SendSafety + SyncSafety + 'static>(&self) -> Result<InjectWeak<Dep>, ResolveErrorKind> {
+        let type_info = TypeInfo::of::<Dep>();
+        let dep_name = type_info.name;
+        let scope_name = &*self.inner.scope_data.name;
+
+        let Some(InstantiatorData { scope_data, .. }) = self.inner.registry.get(&type_info) else {
+            debug!("No instantiator found, trying sync container");
+            return self.sync.get_weak();
+        };
+
+        let inner = if scope_data.priority == self.inner.scope_data.priority {
+            &self.inner
+        } else {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
                 let err = ResolveErrorKind::NoAccessible {
-                    expected_scope_data: *scope_data,
-                    actual_scope_data: self.inner.scope_data,
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
                 };
-                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
+                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve weak dependency");
                 return Err(err);
+            };
+            parent
+        };
+        let weak = InjectWeak::new();
+        inner.cache.try_write(&type_info)?.watch_weak(type_info, &weak.0);
+        Ok(weak)
+    }
+
+    /// Instantiates a dependency of the current scope and caches it, unless a fresh instance is cached when the lock is taken.
+    /// A replaced expired instance is finalized.
+    async fn instantiate_scoped<Dep: SendSafety + SyncSafety + 'static, P: Provider>(
+        &self,
+        provider: &P,
+    ) -> Result<RcThreadSafety<Dep>, ResolveErrorKind> {
+        let config = provider.config();
+        let type_info = TypeInfo::of::<Dep>();
+        let dep_name = type_info.name;
+        let scope_name = &*self.inner.scope_data.name;
+        let now = || clock::now(self.inner.registry.clock.as_ref());
+
+        // Same as `Self::instantiate`, but the lock isn't held while waiting before a retry,
+        // so other resolutions of the type aren't blocked by the delay
+        let inst_call_lock = self.per_type_locks.get(type_info.id);
+        let mut attempt = 1;
+        let (dependency, _guard) = loop {
+            trace!("Lock instantiator call");
+            let guard = inst_call_lock.lock().await;
+
+            if let Some(dependency) = { self.inner.cache.try_read(&type_info)?.get_fresh(&type_info, now) } {
+                debug!("Found in cache after lock");
+                return Ok(dependency);
             }
 
-            match instantiator.clone().call(self.clone()).await {
-                Ok(dependency) => match dependency.downcast::<Dep>() {
-                    Ok(dependency) => Ok(*dependency),
+            let err = match self.instantiate_once(provider, &type_info, true, attempt).await {
+                Ok(dependency) => match dependency.downcast_shared::<Dep>() {
+                    Ok(dependency) => break (dependency, guard),
                     Err(incorrect_type) => {
                         let err = ResolveErrorKind::IncorrectType {
                             expected: type_info,
-                            actual: TypeInfo::of_val(&*incorrect_type),
+                            actual: incorrect_type.type_info(),
                         };
-                        error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
-                        Err(err)
+                        error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
+                        return Err(err);
                     }
                 },
-                Err(InstantiatorErrorKind::Deps(err)) => {
-                    error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
-                    Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err))))
+                Err(err) if is_retryable(&err) && attempt < config.retry.max_attempts => err,
+                Err(err) => {
+                    error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
+                    return Err(err);
                 }
-                Err(InstantiatorErrorKind::Factory(err)) => {
-                    error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
-                    Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(err)))
+            };
+            drop(guard);
+            warn!(attempt, error = %err, "Failed to instantiate, retrying");
+            let delay = config.retry.delay(attempt);
+            if !delay.is_zero() {
+                runtime::sleep(delay).await;
+            }
+            attempt += 1;
+        };
+
+        let cache_provides = config.cache_provides;
+        let has_finalizer = provider.has_finalizer();
+        let mut replaced = None;
+        if cache_provides || has_finalizer {
+            let mut cache = self.inner.cache.try_write(&type_info)?;
+            if cache_provides {
+                if let Some(previous) = cache.insert_rc(type_info.clone(), dependency.clone()) {
+                    // The previous instance is expired, it's finalized now instead of on close
+                    replaced = cache.resolved.remove(&previous);
                 }
+                let expires_at = config.ttl.and_then(|ttl| Some(now()?.saturating_add(ttl)));
+                cache.set_expiration(type_info.clone(), expires_at);
+                debug!("Cached");
+            }
+            if has_finalizer {
+                cache.push_resolved(Resolved {
+                    type_info,
+                    dependency: dependency.clone(),
+                });
+                debug!("Pushed to resolved set");
+            }
+        }
+        if let Some(finalize) = replaced.and_then(|Resolved { dependency, .. }| provider.finalize(dependency)) {
+            let _ = finalize.await;
+            debug!("Finalizer of replaced instance called");
+        }
+        Ok(dependency)
+    }
+
+    /// Spawns instantiation of an expired dependency of the current scope, unless it's already being instantiated.
+    /// Returns `false` if the backend can't spawn tasks, see [`runtime`]
+    fn refresh_in_background<Dep: SendSafety + SyncSafety + 'static>(&self) -> bool {
+        let type_info = TypeInfo::of::<Dep>();
+        if self.per_type_locks.get(type_info.id).try_lock().is_none() {
+            return true;
+        }
+
+        let container = self.clone();
+        let refresh = async move {
+            let Some(data) = container.inner.registry.get(&type_info) else {
+                return;
+            };
+            if let Err(err) = container.instantiate_scoped::<Dep, _>(data).await {
+                warn!(dependency = type_info.name, error = %err, "Failed to refresh dependency in background");
+            }
+        };
+        runtime::spawn(Box::pin(refresh)).is_none()
+    }
+
+    /// Calls the instantiator with the timeout of the config, retrying its own failures and timeouts by the retry policy of the config
+    async fn instantiate<P: Provider>(&self, provider: &P, type_info: &TypeInfo, shared: bool) -> Result<Instance, ResolveErrorKind> {
+        let config = provider.config();
+        let mut attempt = 1;
+        loop {
+            let err = match self.instantiate_once(provider, type_info, shared, attempt).await {
+                Err(err) if is_retryable(&err) && attempt < config.retry.max_attempts => err,
+                result => return result,
+            };
+            warn!(attempt, error = %err, "Failed to instantiate, retrying");
+            let delay = config.retry.delay(attempt);
+            if !delay.is_zero() {
+                runtime::sleep(delay).await;
             }
+            attempt += 1;
+        }
+    }
+
+    /// Calls the instantiator with the timeout of the config.
+    /// An error is reported as of the last attempt, see [`is_retryable`]
+    async fn instantiate_once<P: Provider>(
+        &self,
+        provider: &P,
+        type_info: &TypeInfo,
+        shared: bool,
+        attempt: u32,
+    ) -> Result<Instance, ResolveErrorKind> {
+        let call = provider.instantiate(self.clone(), shared);
+        let result = match provider.config().timeout {
+            Some(timeout) => runtime::timeout(timeout, call).await.map_err(|()| timeout),
+            None => Ok(call.await),
         };
-        Box::pin(fut)
+        match result {
+            Ok(Ok(dependency)) => Ok(dependency),
+            Ok(Err(InstantiatorErrorKind::Deps(err))) => Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err)))),
+            Ok(Err(InstantiatorErrorKind::Factory(err))) => Err(ResolveErrorKind::factory(type_info.clone(), err, attempt)),
+            Err(timeout) => Err(ResolveErrorKind::Timeout {
+                type_info: type_info.clone(),
+                timeout,
+                attempts: attempt,
+            }),
+        }
     }
 
     /// Closes the container, calling finalizers for resolved dependencies in LIFO order.
@@ -326,31 +498,126 @@ impl Container {
     /// # Warning
     /// This method can be called multiple times, but it will only call finalizers for dependencies that were resolved since the last call
     pub fn close(&self) -> impl Future<Output = ()> + SendSafety + '_ {
-        let fut = async move {
-            self.inner.close().await;
-            self.sync.inner.close_with_parent_flag(false);
+        Box::pin(self.close_with(|_, _| None::<Ready<()>>))
+    }
 
-            let mut inner_parent = self.inner.parent.as_ref();
-            let mut sync_parent = self.sync.inner.parent.as_ref();
+    /// Like [`Self::close`], `finalize_local` calls the finalizers of dependencies without an async instantiator,
+    /// see [`super::local`]
+    pub(super) async fn close_with<Fut: Future>(&self, finalize_local: impl Fn(&TypeInfo, RcAnyThreadSafety) -> Option<Fut>) {
+        self.inner.close(&finalize_local).await;
+        self.sync.inner.close_with_parent_flag(false);
 
-            let mut close_parent = self.inner.close_parent;
-            while close_parent {
-                match (inner_parent, sync_parent) {
-                    (Some(container), Some(sync_container)) => {
-                        sync_container.inner.close_with_parent_flag(false);
-                        container.close().await;
+        let mut inner_parent = self.inner.parent.as_ref();
+        let mut sync_parent = self.sync.inner.parent.as_ref();
 
-                        close_parent = container.close_parent;
+        let mut close_parent = self.inner.close_parent;
+        while close_parent {
+            match (inner_parent, sync_parent) {
+                (Some(container), Some(sync_container)) => {
+                    sync_container.inner.close_with_parent_flag(false);
+                    container.close(&finalize_local).await;
 
-                        inner_parent = container.parent.as_ref();
-                        sync_parent = sync_container.inner.parent.as_ref();
-                    }
-                    (None, None) => break,
-                    _ => unreachable!(),
+                    close_parent = container.close_parent;
+
+                    inner_parent = container.parent.as_ref();
+                    sync_parent = sync_container.inner.parent.as_ref();
                 }
+                (None, None) => break,
+                _ => unreachable!(),
             }
-        };
-        Box::pin(fut)
+        }
+    }
+
+    /// Gets a scoped dependency cached in the container, `None` if it isn't cached or is expired
+    #[cfg(feature = "local_async")]
+    pub(super) fn get_cached<Dep: SendSafety + SyncSafety + 'static>(&self) -> Result<Option<RcThreadSafety<Dep>>, ResolveErrorKind> {
+        let now = || clock::now(self.inner.registry.clock.as_ref());
+        self.inner.cache.get_fresh(&TypeInfo::of::<Dep>(), now)
+    }
+
+    /// Whether an instance of the type is cached in the container and isn't expired
+    #[cfg(feature = "local_async")]
+    pub(super) fn contains_cached(&self, type_info: &TypeInfo) -> bool {
+        let now = || clock::now(self.inner.registry.clock.as_ref());
+        self.inner.cache.read().contains_fresh(type_info, now)
+    }
+
+    /// Scope and dependencies of the async provider of the type
+    #[cfg(feature = "local_async")]
+    pub(super) fn provider(&self, type_info: &TypeInfo) -> Option<(&ScopeData, &BTreeSet<Dependency>)> {
+        self.inner.registry.get(type_info).map(
+            |InstantiatorData {
+                 scope_data, dependencies, ..
+             }| (scope_data, dependencies),
+        )
+    }
+
+    /// Container of the scope, the current one or an ancestor, `None` if the scope isn't accessible from the current one
+    #[cfg(feature = "local_async")]
+    pub(super) fn in_scope(&self, scope_data: &ScopeData) -> Option<Self> {
+        if scope_data.priority == self.inner.scope_data.priority {
+            return Some(self.clone());
+        }
+        Some(Self {
+            inner: self.inner.ancestor_in_scope(scope_data)?.clone(),
+            sync: self.sync.clone(),
+            per_type_locks: self.per_type_locks.clone(),
+        })
+    }
+
+    /// Takes a snapshot of the container state: its levels from the root scope to the current one,
+    /// types registered in each scope, cached types, pending finalizers and context types.
+    /// Each level combines the async providers with the sync ones.
+    ///
+    /// Meant for debugging, the [`Debug`] impl of the container is built on it.
+    #[must_use]
+    pub fn describe(&self) -> ContainerSnapshot {
+        let mut levels = Vec::new();
+        let mut inner = Some(&self.inner);
+        let mut sync_inner = Some(&self.sync.inner);
+        while let (Some(container), Some(sync_container)) = (inner, sync_inner) {
+            let scope_data = &container.scope_data;
+            let registered = container
+                .registry
+                .entries
+                .iter()
+                .filter(|(_, data)| data.scope_data == *scope_data)
+                .map(|(type_info, _)| type_info)
+                .chain(
+                    sync_container
+                        .registry
+                        .entries
+                        .iter()
+                        .filter(|(_, data)| data.scope_data == *scope_data)
+                        .map(|(type_info, _)| type_info),
+                );
+            // The async resolved set is finalized before the sync one
+            levels.push(LevelSnapshot::new(
+                scope_data.clone(),
+                registered,
+                &[&sync_container.cache.read(), &container.cache.read()],
+                &container.context,
+            ));
+            inner = container.parent.as_ref();
+            sync_inner = sync_container.parent.as_ref().map(|parent| &parent.inner);
+        }
+        levels.reverse();
+        ContainerSnapshot { levels }
+    }
+
+    /// Plans resolving `Dep` with [`Self::get`] without calling any instantiator:
+    /// for `Dep` and each of its dependencies, the scope it would be resolved in, whether it's already cached,
+    /// whether a finalizer would be registered, and problems that would fail the resolution.
+    /// Types without an async instantiator are planned with the sync container, as [`Self::get`] falls back to it.
+    #[must_use]
+    pub fn explain<Dep: 'static>(&self) -> ResolutionPlan {
+        self.inner.explain_type(&self.sync, &TypeInfo::of::<Dep>(), false, &mut Vec::new())
+    }
+}
+
+impl Debug for Container {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Container").field("levels", &self.describe().levels).finish()
     }
 }
 
@@ -376,10 +643,10 @@ impl Container {
 
         Self {
             inner: RcThreadSafety::new(ContainerInner {
-                cache: RwLock::new(cache),
+                cache: SharedCache::new(cache),
                 context,
                 registry,
-                scope_data,
+                scope_data: scope_data.clone(),
                 child_scopes_data: child_scopes_data.clone(),
                 parent: Some(self.inner),
                 close_parent,
@@ -388,7 +655,7 @@ impl Container {
                 #[cfg(feature = "thread_safe")]
                 per_type_locks: self.sync.per_type_locks.clone(),
                 inner: RcThreadSafety::new(SyncContainerInner {
-                    cache: RwLock::new(sync_cache),
+                    cache: SharedCache::new(sync_cache),
                     context: sync_context,
                     registry: sync_registry,
                     scope_data,
@@ -419,10 +686,10 @@ impl Container {
 
         Self {
             inner: RcThreadSafety::new(ContainerInner {
-                cache: RwLock::new(cache),
+                cache: SharedCache::new(cache),
                 context,
                 registry,
-                scope_data,
+                scope_data: scope_data.clone(),
                 child_scopes_data: child_scopes_data.clone(),
                 parent: Some(self.inner),
                 close_parent,
@@ -431,7 +698,7 @@ impl Container {
                 #[cfg(feature = "thread_safe")]
                 per_type_locks: self.sync.per_type_locks.clone(),
                 inner: RcThreadSafety::new(SyncContainerInner {
-                    cache: RwLock::new(sync_cache),
+                    cache: SharedCache::new(sync_cache),
                     context: sync_context,
                     registry: sync_registry,
                     scope_data,
@@ -445,8 +712,8 @@ impl Container {
     }
 
     /// Shared loop behind the four `*::build()` entry points (see the sync container for the full
-    /// rationale): starting from the next child scope, init one child container per scope until
-    /// `is_target` accepts one. The first child keeps its parent open; intermediate skipped levels
+    /// rationale): descend the branch of the scope tree to the first scope accepted by `is_target`,
+    /// initializing one child container per scope. The first child keeps its parent open; intermediate skipped levels
     /// use `close_parent = true`. A `context`, when present, is threaded into every level.
     fn build_descendant<E>(
         self,
@@ -455,20 +722,41 @@ impl Container {
         no_child: E,
         no_target: impl Fn() -> E,
     ) -> Result<Container, E> {
-        let scopes = self.inner.get_scope_with_child_scopes().child();
-        let mut child = self.init_descendant(context.clone(), scopes.scope_data.ok_or(no_child)?, scopes.child_scopes_data, false);
+        let scopes = self.inner.get_scope_with_child_scopes();
+        let Some(mut scopes) = scopes.child(&is_target) else {
+            return Err(if scopes.has_children() { no_target() } else { no_child });
+        };
+        let scope_data = scopes.scope_data.clone().expect("child scope should have scope data");
+        let mut child = self.init_descendant(context.clone(), scope_data, scopes.child_scopes_data.clone(), false);
         while !is_target(&child.inner.scope_data) {
-            let scopes = child.inner.get_scope_with_child_scopes().child();
-            child = child.init_descendant(
-                context.clone(),
-                scopes.scope_data.ok_or_else(&no_target)?,
-                scopes.child_scopes_data,
-                true,
-            );
+            scopes = scopes.child(&is_target).ok_or_else(&no_target)?;
+            let scope_data = scopes.scope_data.clone().expect("child scope should have scope data");
+            child = child.init_descendant(context.clone(), scope_data, scopes.child_scopes_data.clone(), true);
         }
         Ok(child)
     }
 
+    fn build_descendant_by_name(self, context: Option<Context>, name: Cow<'static, str>) -> Result<Container, ScopeWithErrorKind> {
+        use ScopeWithErrorKind::{NoChildRegistries, NoChildRegistriesWithScope};
+
+        let priority = self
+            .inner
+            .registry
+            .scopes_data
+            .iter()
+            .find(|scope_data| scope_data.name == name)
+            .map(|scope_data| scope_data.priority);
+        self.build_descendant(
+            context,
+            |scope_data| scope_data.name == name,
+            NoChildRegistries,
+            || NoChildRegistriesWithScope {
+                name: name.clone(),
+                priority,
+            },
+        )
+    }
+
     fn init_descendant(
         self,
         context: Option<Context>,
@@ -508,6 +796,17 @@ impl ChildContainerBuilder {
         }
     }
 
+    /// Enters a scope by its name, for scopes that are only known at runtime.
+    /// See [`Registry::with_scopes`]
+    #[inline]
+    #[must_use]
+    pub fn with_scope_name(self, name: impl Into<Cow<'static, str>>) -> ChildContainerWithScope<Cow<'static, str>> {
+        ChildContainerWithScope {
+            container: self.container,
+            scope: name.into(),
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn with_context(self, context: Context) -> ChildContainerWithContext {
@@ -562,7 +861,7 @@ where
     ///
     /// # Errors
     /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
-    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
     ///
     /// # Warning
     /// If you want just to use next non-skipped scope, use [`ChildContainerBuilder::with_scope`]
@@ -575,11 +874,35 @@ where
             None,
             move |scope_data| scope_data.priority == priority,
             NoChildRegistries,
-            move || NoChildRegistriesWithScope { name, priority },
+            move || NoChildRegistriesWithScope {
+                name: name.into(),
+                priority: Some(priority),
+            },
         )
     }
 }
 
+impl ChildContainerWithScope<Cow<'static, str>> {
+    #[inline]
+    #[must_use]
+    pub fn with_context(self, context: Context) -> ChildContainerWithScopeAndContext<Cow<'static, str>> {
+        ChildContainerWithScopeAndContext {
+            container: self.container,
+            scope: self.scope,
+            context,
+        }
+    }
+
+    /// Creates child container with scope of specified name.
+    ///
+    /// # Errors
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.container.build_descendant_by_name(None, self.scope)
+    }
+}
+
 pub struct ChildContainerWithContext {
     container: Container,
     context: Context,
@@ -596,6 +919,18 @@ impl ChildContainerWithContext {
         }
     }
 
+    /// Enters a scope by its name, for scopes that are only known at runtime.
+    /// See [`Registry::with_scopes`]
+    #[inline]
+    #[must_use]
+    pub fn with_scope_name(self, name: impl Into<Cow<'static, str>>) -> ChildContainerWithScopeAndContext<Cow<'static, str>> {
+        ChildContainerWithScopeAndContext {
+            container: self.container,
+            scope: name.into(),
+            context: self.context,
+        }
+    }
+
     /// Creates child container with next non-skipped scope and passes context to it.
     ///
     /// # Errors
@@ -630,7 +965,7 @@ where
     ///
     /// # Errors
     /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
-    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
     ///
     /// # Warning
     /// If you want just to use next non-skipped scope, use [`ChildContainerBuilder::with_scope`]
@@ -643,11 +978,25 @@ where
             Some(self.context),
             move |scope_data| scope_data.priority == priority,
             NoChildRegistries,
-            move || NoChildRegistriesWithScope { name, priority },
+            move || NoChildRegistriesWithScope {
+                name: name.into(),
+                priority: Some(priority),
+            },
         )
     }
 }
 
+impl ChildContainerWithScopeAndContext<Cow<'static, str>> {
+    /// Creates child container with scope of specified name and passes context to it.
+    ///
+    /// # Errors
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.container.build_descendant_by_name(Some(self.context), self.scope)
+    }
+}
+
 struct BoxedContainerInner {
     cache: Cache,
     context: Context,
@@ -696,7 +1045,7 @@ impl From<BoxedContainerInner> for ContainerInner {
     ) -> Self {
         Self {
             parent: parent.map(|parent| RcThreadSafety::new((*parent).into())),
-            cache: RwLock::new(cache),
+            cache: SharedCache::new(cache),
             context,
             registry,
             scope_data,
@@ -717,7 +1066,7 @@ impl From<(BoxedContainerInner, BoxedSyncContainerInner)> for Container {
 }
 
 struct ContainerInner {
-    cache: RwLock<Cache>,
+    cache: SharedCache,
     context: Context,
     registry: RcThreadSafety<Registry>,
     scope_data: ScopeData,
@@ -729,31 +1078,102 @@ struct ContainerInner {
 impl ContainerInner {
     #[inline]
     pub(crate) fn get_scope_with_child_scopes(&self) -> ScopeDataWithChildScopesData {
-        ScopeDataWithChildScopesData::new(self.scope_data, self.child_scopes_data.clone())
+        ScopeDataWithChildScopesData::new(self.scope_data.clone(), self.child_scopes_data.clone())
+    }
+
+    /// The ancestor level in `scope_data`, `None` if the scope isn't an ancestor of the current one
+    fn ancestor_in_scope(&self, scope_data: &ScopeData) -> Option<&RcThreadSafety<ContainerInner>> {
+        let mut parent = self.parent.as_ref()?;
+        while parent.scope_data.priority != scope_data.priority {
+            parent = parent.parent.as_ref()?;
+        }
+        Some(parent)
+    }
+
+    /// Mirrors the walk of [`Container::get`] and [`Container::get_transient`]. `path` holds the types being planned
+    fn explain_type(&self, sync: &SyncContainer, type_info: &TypeInfo, is_transient: bool, path: &mut Vec<TypeInfo>) -> ResolutionPlan {
+        let now = || clock::now(self.registry.clock.as_ref());
+        if !is_transient && self.cache.read().contains_fresh(type_info, now) {
+            let mut plan = ResolutionPlan::new(type_info.name, is_transient);
+            plan.scope = Some(
+                self.registry
+                    .get(type_info)
+                    .map_or_else(|| self.scope_data.clone(), |data| data.scope_data.clone()),
+            );
+            plan.cached = true;
+            return plan;
+        }
+
+        let Some(InstantiatorData {
+            finalizer,
+            scope_data,
+            dependencies,
+            ..
+        }) = self.registry.get(type_info)
+        else {
+            return sync.explain_type(type_info, is_transient, path);
+        };
+
+        let mut plan = ResolutionPlan::new(type_info.name, is_transient);
+        plan.scope = Some(scope_data.clone());
+        plan.is_async = true;
+        if scope_data.priority != self.scope_data.priority {
+            let Some(parent) = self.ancestor_in_scope(scope_data) else {
+                plan.problem = Some(PlanProblem::NoAccessible {
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.scope_data.clone(),
+                });
+                return plan;
+            };
+            return parent.explain_type(sync, type_info, is_transient, path);
+        }
+        if path.contains(type_info) {
+            plan.problem = Some(PlanProblem::CyclicDependency);
+            return plan;
+        }
+
+        plan.registers_finalizer = !is_transient && finalizer.is_some();
+        path.push(type_info.clone());
+        plan.dependencies = dependencies
+            .iter()
+            .map(|dependency| self.explain_type(sync, &dependency.type_info, dependency.is_transient, path))
+            .collect();
+        path.pop();
+        plan
     }
 
-    #[allow(clippy::missing_panics_doc)]
-    fn close(&self) -> impl Future<Output = ()> + SendSafety + '_ {
+    /// Calls the finalizers of the resolved dependencies in LIFO order, see [`Container::close_with`]
+    fn close<'a, Fut: Future>(
+        &'a self,
+        finalize_local: &'a impl Fn(&TypeInfo, RcAnyThreadSafety) -> Option<Fut>,
+    ) -> impl Future<Output = ()> + 'a {
         let mut resolved_set = { self.cache.write().take_resolved_set() };
-        Box::pin(async move {
+        async move {
             while let Some(Resolved { type_info, dependency }) = resolved_set.0.pop_back() {
-                let InstantiatorData { finalizer, .. } = self
-                    .registry
-                    .get(&type_info)
-                    .expect("Instantiator should be present for resolved type");
-
-                if let Some(finalizer) = finalizer {
-                    let _ = finalizer.clone().call(dependency).await;
-                    debug!(%type_info, "Finalizer called");
+                match self.registry.get(&type_info) {
+                    Some(InstantiatorData {
+                        finalizer: Some(finalizer),
+                        ..
+                    }) => {
+                        let _ = finalizer.clone().call(dependency).await;
+                        debug!(%type_info, "Finalizer called");
+                    }
+                    Some(InstantiatorData { finalizer: None, .. }) => {}
+                    None => match finalize_local(&type_info, dependency) {
+                        Some(finalize) => {
+                            finalize.await;
+                            debug!(%type_info, "Finalizer of local dependency called");
+                        }
+                        None => {
+                            warn!(%type_info, "Finalizer of local dependency not called, the container should be closed by its local one")
+                        }
+                    },
                 }
             }
 
             // We need to clear cache and fill it with the context as in start of the container usage
-            #[allow(clippy::assigning_clones)]
-            {
-                self.cache.write().map = self.context.map.clone();
-            }
-        })
+            self.cache.reset(&self.context);
+        }
     }
 }
 
@@ -770,6 +1190,70 @@ impl Drop for ContainerInner {
     }
 }
 
+/// Instantiator and finalizer of a type with its config and scope, of the async registry or of a local one, see [`super::local`]
+pub(super) trait Provider {
+    type Instantiate: Future<Output = Result<Instance, InstantiatorErrorKind<ResolveErrorKind, InstantiateErrorKind>>>;
+    type Finalize: Future;
+
+    /// Whether instances can be refreshed in a spawned task, see [`Refresh::Background`]
+    const SPAWNS: bool;
+
+    fn scope_data(&self) -> &ScopeData;
+
+    fn config(&self) -> &Config;
+
+    fn has_finalizer(&self) -> bool;
+
+    /// Calls the instantiator with the container of its scope and whether the instance should be shared
+    fn instantiate(&self, container: Container, shared: bool) -> Self::Instantiate;
+
+    /// Calls the finalizer, `None` if there is none
+    fn finalize(&self, dependency: RcAnyThreadSafety) -> Option<Self::Finalize>;
+}
+
+impl Provider for InstantiatorData {
+    type Instantiate = BoxFuture<'static, Result<Instance, InstantiatorErrorKind<ResolveErrorKind, InstantiateErrorKind>>>;
+    type Finalize = BoxFuture<'static, Result<(), ()>>;
+
+    const SPAWNS: bool = true;
+
+    #[inline]
+    fn scope_data(&self) -> &ScopeData {
+        &self.scope_data
+    }
+
+    #[inline]
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    #[inline]
+    fn has_finalizer(&self) -> bool {
+        self.finalizer.is_some()
+    }
+
+    #[inline]
+    fn instantiate(&self, container: Container, shared: bool) -> Self::Instantiate {
+        self.instantiator.clone().call((container, shared))
+    }
+
+    #[inline]
+    fn finalize(&self, dependency: RcAnyThreadSafety) -> Option<Self::Finalize> {
+        Some(self.finalizer.clone()?.call(dependency))
+    }
+}
+
+/// Whether the error is of the instantiator itself or a timeout, so the attempt is retried by the retry policy of the config.
+/// Errors of the dependencies are returned as is
+fn is_retryable(err: &ResolveErrorKind) -> bool {
+    matches!(
+        err,
+        ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(_))
+            | ResolveErrorKind::RetriesExhausted { .. }
+            | ResolveErrorKind::Timeout { .. }
+    )
+}
+
 #[allow(dead_code)]
 #[cfg(test)]
 mod tests {
@@ -780,14 +1264,17 @@ mod tests {
         async_registry, registry,
         scope::DefaultScope::*,
         utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
-        Inject, InjectTransient, ResolveErrorKind, Scope,
+        Inject, InjectTransient, ResolveErrorKind, Scope, ScopeWithErrorKind,
     };
 
     use alloc::{
         format,
         string::{String, ToString as _},
     };
-    use core::sync::atomic::{AtomicU8, Ordering};
+    use core::{
+        sync::atomic::{AtomicU64, AtomicU8, Ordering},
+        time::Duration,
+    };
     use tracing::debug;
     use tracing_test::traced_test;
 
@@ -1644,7 +2131,7 @@ mod tests {
                         let for_factory = for_factory.clone();
                         async move { Ok::<_, crate::InstantiateErrorKind>(for_factory.fetch_add(1, Ordering::SeqCst)) }
                     },
-                    config = crate::Config { cache_provides: false },
+                    config = crate::Config::default().cache_provides(false),
                 ),
             ],
         });
@@ -1660,4 +2147,511 @@ mod tests {
             "each get must re-instantiate at the owning scope"
         );
     }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_describe() {
+        use core::any::type_name;
+
+        let app = Container::new(async_registry! {
+            scope(App) [
+                provide(async || Ok(Request1), finalizer = |_: RcThreadSafety<Request1>| async {}),
+            ],
+            extend(registry! {
+                scope(App) [
+                    provide(|| Ok(1u8), finalizer = |_: RcThreadSafety<u8>| {}),
+                ],
+            }),
+        });
+        app.get::<u8>().await.unwrap();
+        app.get::<Request1>().await.unwrap();
+
+        let snapshot = app.describe();
+        let scopes: alloc::vec::Vec<_> = snapshot.scope_path().map(|scope| &*scope.name).collect();
+        assert_eq!(scopes, ["runtime", "app"]);
+
+        let app_level = snapshot.current();
+        let mut types = [type_name::<Request1>(), type_name::<u8>()];
+        types.sort_unstable();
+        assert_eq!(app_level.registered, types);
+        assert_eq!(app_level.cached, types);
+        // The async resolved set is finalized first, so it's listed last
+        assert_eq!(app_level.pending_finalizers, [type_name::<u8>(), type_name::<Request1>()]);
+        assert!(format!("{app:?}").contains(type_name::<Request1>()));
+
+        let mut context = crate::Context::new();
+        context.insert(7u32);
+        let request = app.clone().enter().with_scope(Request).with_context(context).build().unwrap();
+        let snapshot = request.describe();
+        assert_eq!(snapshot.levels.len(), 4);
+        assert_eq!(snapshot.current().cached, types);
+        assert!(snapshot.current().pending_finalizers.is_empty());
+        assert_eq!(snapshot.current().context, [type_name::<u32>()]);
+
+        app.close().await;
+        assert!(app.describe().current().pending_finalizers.is_empty());
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_explain() {
+        use crate::PlanProblem;
+        use core::any::type_name;
+
+        let app = Container::new(async_registry! {
+            scope(Request) [
+                provide(
+                    async |Inject(req): Inject<Request1>, Inject(_): Inject<u32>| Ok(Request2(req)),
+                    finalizer = |_: RcThreadSafety<Request2>| async {},
+                ),
+            ],
+            extend(registry! {
+                scope(App) [
+                    provide(|| Ok(Request1), finalizer = |_: RcThreadSafety<Request1>| {}),
+                ],
+            }),
+        });
+        let request = app.enter().with_scope(Request).build().unwrap();
+
+        let plan = request.explain::<Request2>();
+        assert_eq!(plan.scope, Some(Request.into()));
+        assert!(plan.is_async && plan.registers_finalizer && !plan.cached);
+
+        let request1 = plan
+            .dependencies
+            .iter()
+            .find(|dependency| dependency.type_name == type_name::<Request1>())
+            .unwrap();
+        assert_eq!(request1.scope, Some(App.into()));
+        assert!(!request1.is_async && !request1.cached && request1.registers_finalizer);
+
+        let problems = plan.problems();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].type_name, type_name::<u32>());
+        assert_eq!(problems[0].problem, Some(PlanProblem::NoInstantiator));
+
+        request.get::<Request1>().await.unwrap();
+        let plan = request.explain::<Request1>();
+        assert!(plan.cached);
+        assert_eq!(plan.scope, Some(App.into()));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_scope_tree() {
+        use crate::{ScopeData, Scopes};
+
+        /// `Request` and `Job` are siblings under `App`
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        enum TreeScope {
+            App,
+            Request,
+            Job,
+        }
+
+        impl From<TreeScope> for ScopeData {
+            fn from(scope: TreeScope) -> Self {
+                Self {
+                    priority: scope.priority(),
+                    name: scope.name().into(),
+                    is_skipped_by_default: false,
+                    parent: scope.parent().map(|parent| parent.priority()),
+                }
+            }
+        }
+
+        impl Scope for TreeScope {
+            fn name(&self) -> &'static str {
+                match self {
+                    TreeScope::App => "app",
+                    TreeScope::Request => "request",
+                    TreeScope::Job => "job",
+                }
+            }
+
+            fn priority(&self) -> u8 {
+                *self as u8
+            }
+
+            fn parent(&self) -> Option<Self> {
+                (*self != TreeScope::App).then_some(TreeScope::App)
+            }
+        }
+
+        impl Scopes<2> for TreeScope {
+            type Scope = Self;
+
+            fn all() -> (Self::Scope, [Self::Scope; 2]) {
+                (TreeScope::App, [TreeScope::Request, TreeScope::Job])
+            }
+        }
+
+        struct Config;
+        struct RequestData;
+        struct JobData;
+
+        let app = Container::new_with_start_scope(
+            async_registry! {
+                scope(TreeScope::Request) [
+                    provide(async |Inject(_): Inject<Config>| Ok(RequestData)),
+                ],
+                scope(TreeScope::Job) [
+                    provide(async |Inject(_): Inject<Config>| Ok(JobData)),
+                ],
+                extend(registry! {
+                    scope(TreeScope::App) [
+                        provide(|| Ok(Config)),
+                    ],
+                    scope(TreeScope::Job) [
+                        provide(|| Ok(1u8)),
+                    ],
+                }),
+            },
+            TreeScope::App,
+        );
+
+        let request = app.clone().enter_build().unwrap();
+        assert!(request.get::<RequestData>().await.is_ok());
+        assert!(matches!(request.get::<JobData>().await, Err(ResolveErrorKind::NoAccessible { .. })));
+        assert!(matches!(request.get::<u8>().await, Err(ResolveErrorKind::NoAccessible { .. })));
+        assert!(!request.explain::<JobData>().is_resolvable());
+
+        let job = app.enter().with_scope(TreeScope::Job).build().unwrap();
+        assert!(job.get::<JobData>().await.is_ok());
+        assert_eq!(*job.get::<u8>().await.unwrap(), 1);
+        assert!(matches!(job.get::<RequestData>().await, Err(ResolveErrorKind::NoAccessible { .. })));
+        let scopes: alloc::vec::Vec<_> = job.describe().scope_path().map(|scope| scope.name.clone()).collect();
+        assert_eq!(scopes, ["app", "job"]);
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_runtime_scopes() {
+        use crate::ScopeData;
+
+        let scopes: alloc::vec::Vec<_> = ["app", "pipeline", "stage"]
+            .into_iter()
+            .zip(0..)
+            .map(|(name, priority)| ScopeData::new(priority, name.to_string()))
+            .collect();
+
+        let app = Container::new(
+            async_registry! {
+                scope(scopes[2].clone()) [
+                    provide(async |Inject(num): Inject<u8>| Ok(u16::from(*num) + 1)),
+                ],
+                extend(registry! {
+                    scope(scopes[0].clone()) [
+                        provide(|| Ok(1u8)),
+                    ],
+                }),
+            }
+            .with_scopes(scopes),
+        );
+
+        let stage = app.clone().enter().with_scope_name("stage").build().unwrap();
+        assert_eq!(*stage.get::<u16>().await.unwrap(), 2);
+        let path: alloc::vec::Vec<_> = stage.describe().scope_path().map(|scope| scope.name.clone()).collect();
+        assert_eq!(path, ["app", "pipeline", "stage"]);
+
+        assert!(matches!(
+            app.enter().with_scope_name("unknown").build(),
+            Err(ScopeWithErrorKind::NoChildRegistriesWithScope { priority: None, .. })
+        ));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_concurrent_dependencies() {
+        struct Left;
+        struct Right;
+        struct Both;
+
+        // Each factory yields once, so with concurrent resolution the other one starts before it finishes
+        async fn overlapping<T>(in_flight: &AtomicU8, max_in_flight: &AtomicU8, value: T) -> Result<T, crate::InstantiateErrorKind> {
+            in_flight.fetch_add(1, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            max_in_flight.fetch_max(in_flight.load(Ordering::SeqCst), Ordering::SeqCst);
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(value)
+        }
+
+        for resolve_dependencies_concurrently in [true, false] {
+            let in_flight = RcThreadSafety::new(AtomicU8::new(0));
+            let max_in_flight = RcThreadSafety::new(AtomicU8::new(0));
+
+            let container = Container::new(async_registry! {
+                scope(App) [
+                    provide({
+                        let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                        move || {
+                            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                            async move { overlapping(&in_flight, &max_in_flight, Left).await }
+                        }
+                    }),
+                    provide({
+                        let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                        move || {
+                            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                            async move { overlapping(&in_flight, &max_in_flight, Right).await }
+                        }
+                    }),
+                    provide(
+                        async |Inject(_): Inject<Left>, Inject(_): Inject<Right>| Ok(Both),
+                        config = crate::Config::default().resolve_dependencies_concurrently(resolve_dependencies_concurrently),
+                    ),
+                ]
+            });
+
+            assert!(container.get::<Both>().await.is_ok());
+            let expected = if resolve_dependencies_concurrently { 2 } else { 1 };
+            assert_eq!(max_in_flight.load(Ordering::SeqCst), expected);
+        }
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_concurrent_dependencies_first_error() {
+        use core::sync::atomic::AtomicBool;
+
+        struct Left;
+        struct Right;
+        struct Both;
+
+        let left_finished = RcThreadSafety::new(AtomicBool::new(false));
+        let container = Container::new(async_registry! {
+            scope(App) [
+                provide({
+                    let left_finished = left_finished.clone();
+                    move || {
+                        let left_finished = left_finished.clone();
+                        async move {
+                            tokio::task::yield_now().await;
+                            left_finished.store(true, Ordering::SeqCst);
+                            Err::<Left, _>(crate::InstantiateErrorKind::Custom(anyhow::anyhow!("left")))
+                        }
+                    }
+                }),
+                provide(async || Err::<Right, _>(crate::InstantiateErrorKind::Custom(anyhow::anyhow!("right")))),
+                provide(
+                    async |Inject(_): Inject<Left>, Inject(_): Inject<Right>| Ok(Both),
+                    config = crate::Config::default().resolve_dependencies_concurrently(true),
+                ),
+            ]
+        });
+
+        // `Right` fails first, so `Left` isn't polled anymore
+        let err = container.get::<Both>().await.err().unwrap();
+        let message = format!("{err:?}");
+        assert!(message.contains("right") && !message.contains("left"), "{message}");
+        assert!(!left_finished.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_timeout() {
+        use core::time::Duration;
+
+        let container = Container::new(async_registry! {
+            scope(App) [
+                provide(
+                    async || {
+                        tokio::time::sleep(Duration::from_secs(10)).await;
+                        Ok(())
+                    },
+                    config = crate::Config::default().timeout(Some(Duration::from_millis(10))).retry(crate::RetryPolicy::new(2, Duration::from_millis(1))),
+                ),
+            ]
+        });
+
+        assert!(matches!(
+            container.get::<()>().await,
+            Err(ResolveErrorKind::Timeout { attempts: 2, timeout, .. }) if timeout == Duration::from_millis(10)
+        ));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_retry() {
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+
+        for (max_attempts, is_ok) in [(3, true), (2, false)] {
+            calls.store(0, Ordering::SeqCst);
+
+            let container = Container::new(async_registry! {
+                scope(App) [
+                    provide(
+                        {
+                            let calls = calls.clone();
+                            move || {
+                                let attempt = calls.fetch_add(1, Ordering::SeqCst);
+                                async move {
+                                    match attempt {
+                                        0 | 1 => Err(anyhow::anyhow!("transient failure").into()),
+                                        _ => Ok(()),
+                                    }
+                                }
+                            }
+                        },
+                        config = crate::Config::default().retry(crate::RetryPolicy::new(max_attempts, core::time::Duration::from_millis(1))),
+                    ),
+                ]
+            });
+
+            match container.get::<()>().await {
+                Ok(_) => assert!(is_ok),
+                Err(err) => assert!(
+                    !is_ok && matches!(err, ResolveErrorKind::RetriesExhausted { attempts: 2, .. }),
+                    "{err}"
+                ),
+            }
+            assert_eq!(calls.load(Ordering::SeqCst), if is_ok { 3 } else { 2 });
+        }
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_retry_releases_lock_while_waiting() {
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let container = Container::new(async_registry! {
+            scope(App) [
+                provide(
+                    {
+                        let calls = calls.clone();
+                        move || {
+                            let attempt = calls.fetch_add(1, Ordering::SeqCst);
+                            async move {
+                                if attempt == 0 {
+                                    return Err(anyhow::anyhow!("transient failure").into());
+                                }
+                                Ok(attempt)
+                            }
+                        }
+                    },
+                    config = crate::Config::default().retry(crate::RetryPolicy::new(2, Duration::from_millis(200))),
+                ),
+            ]
+        });
+
+        // The first call waits without the lock, so the second one makes the next attempt before the delay is over
+        let (retrying, resolved) = tokio::join!(
+            container.get::<u8>(),
+            tokio::time::timeout(Duration::from_millis(100), container.get::<u8>()),
+        );
+        let resolved = resolved.expect("the lock should be released while waiting").unwrap();
+        assert!(RcThreadSafety::ptr_eq(&resolved, &retrying.unwrap()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    struct ManualClock(RcThreadSafety<AtomicU64>);
+
+    impl crate::Clock for ManualClock {
+        fn now(&self) -> Duration {
+            Duration::from_secs(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    fn ttl_container(secs: &RcThreadSafety<AtomicU64>, calls: &RcThreadSafety<AtomicU8>, refresh: crate::Refresh) -> Container {
+        Container::new(
+            async_registry! {
+                scope(App) [
+                    provide(
+                        {
+                            let calls = calls.clone();
+                            move || {
+                                let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                                async move { Ok(call) }
+                            }
+                        },
+                        config = crate::Config::default().ttl(Some(Duration::from_secs(10))).refresh(refresh),
+                    ),
+                ]
+            }
+            .with_clock(ManualClock(secs.clone())),
+        )
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_ttl_refresh_on_access() {
+        let secs = RcThreadSafety::new(AtomicU64::new(0));
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let app_container = ttl_container(&secs, &calls, crate::Refresh::OnAccess);
+        let request_container = app_container.clone().enter_build().unwrap();
+
+        assert_eq!(*request_container.get::<u8>().await.unwrap(), 1);
+
+        secs.store(9, Ordering::SeqCst);
+        assert_eq!(*app_container.get::<u8>().await.unwrap(), 1);
+        assert!(request_container.explain::<u8>().cached);
+
+        secs.store(10, Ordering::SeqCst);
+        // The expired instance is reported as instantiated again, as `get` would do
+        assert!(!request_container.explain::<u8>().cached);
+        assert_eq!(*request_container.get::<u8>().await.unwrap(), 2);
+        assert_eq!(*app_container.get::<u8>().await.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(all(feature = "thread_safe", feature = "tokio"))]
+    #[tokio::test]
+    #[traced_test]
+    async fn test_ttl_refresh_in_background() {
+        let secs = RcThreadSafety::new(AtomicU64::new(0));
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let container = ttl_container(&secs, &calls, crate::Refresh::Background);
+
+        assert_eq!(*container.get::<u8>().await.unwrap(), 1);
+
+        secs.store(10, Ordering::SeqCst);
+        assert_eq!(*container.get::<u8>().await.unwrap(), 1);
+
+        // Let the spawned refresh run on the current thread runtime
+        while calls.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+        tokio::task::yield_now().await;
+
+        assert_eq!(*container.get::<u8>().await.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    #[traced_test]
+    fn test_tokio_backend_outside_of_runtime() {
+        let secs = RcThreadSafety::new(AtomicU64::new(0));
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let container = ttl_container(&secs, &calls, crate::Refresh::Background);
+
+        futures::executor::block_on(async {
+            assert_eq!(*container.get::<u8>().await.unwrap(), 1);
+
+            // Without a runtime to spawn the refresh on, the expired instance is replaced on access
+            secs.store(10, Ordering::SeqCst);
+            assert_eq!(*container.get::<u8>().await.unwrap(), 2);
+            assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+            // Timers fall back to `futures-timer`
+            super::runtime::timeout(Duration::from_millis(10), core::future::pending::<()>())
+                .await
+                .unwrap_err();
+        });
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    #[tokio::test]
+    #[traced_test]
+    async fn test_ttl_refresh_without_spawning() {
+        let secs = RcThreadSafety::new(AtomicU64::new(0));
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let container = ttl_container(&secs, &calls, crate::Refresh::Background);
+
+        assert_eq!(*container.get::<u8>().await.unwrap(), 1);
+
+        // The executor-agnostic backend can't spawn the refresh, so the expired instance is replaced on access
+        secs.store(10, Ordering::SeqCst);
+        assert_eq!(*container.get::<u8>().await.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
 }
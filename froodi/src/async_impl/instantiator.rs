@@ -1,5 +1,5 @@
 use alloc::{boxed::Box, collections::btree_set::BTreeSet};
-use core::{any::Any, future::Future};
+use core::future::Future;
 use tracing::debug;
 
 use super::{
@@ -10,7 +10,8 @@ use crate::{
     dependency::Dependency,
     dependency_resolver::DependencyResolver,
     errors::{InstantiateErrorKind, InstantiatorErrorKind},
-    utils::thread_safety::{SendSafety, SyncSafety},
+    instantiator::Instance,
+    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
     ResolveErrorKind,
 };
 
@@ -26,23 +27,35 @@ where
     fn dependencies() -> BTreeSet<Dependency>;
 }
 
+/// Instantiator called with the container and whether the instance should be shared
 pub(crate) type BoxedCloneInstantiator<DepsErr, FactoryErr> =
-    BoxCloneService<Container, Box<dyn Any>, InstantiatorErrorKind<DepsErr, FactoryErr>>;
+    BoxCloneService<(Container, bool), Instance, InstantiatorErrorKind<DepsErr, FactoryErr>>;
 
 #[must_use]
-pub(crate) fn boxed_instantiator<Inst, Deps>(instantiator: Inst) -> BoxedCloneInstantiator<Deps::Error, Inst::Error>
+pub(crate) fn boxed_instantiator<Inst, Deps>(
+    instantiator: Inst,
+    resolve_concurrently: bool,
+) -> BoxedCloneInstantiator<Deps::Error, Inst::Error>
 where
     Inst: Instantiator<Deps> + SendSafety + SyncSafety,
+    Inst::Provides: SendSafety + SyncSafety,
     Deps: DependencyResolver,
 {
     BoxCloneService::new(Box::new(service_fn({
-        move |container| {
+        move |(container, shared): (Container, bool)| {
             let mut instantiator = instantiator.clone();
 
             async move {
-                let dependencies = match Deps::resolve_async(&container).await {
-                    Ok(dependencies) => dependencies,
-                    Err(err) => return Err(InstantiatorErrorKind::Deps(err)),
+                let dependencies = if resolve_concurrently {
+                    match Deps::resolve_async_concurrently(&container).await {
+                        Ok(dependencies) => dependencies,
+                        Err(err) => return Err(InstantiatorErrorKind::Deps(err)),
+                    }
+                } else {
+                    match Deps::resolve_async(&container).await {
+                        Ok(dependencies) => dependencies,
+                        Err(err) => return Err(InstantiatorErrorKind::Deps(err)),
+                    }
                 };
                 let dependency = match instantiator.instantiate(dependencies).await {
                     Ok(dependency) => dependency,
@@ -51,7 +64,11 @@ where
 
                 debug!("Resolved");
 
-                Ok(Box::new(dependency) as _)
+                Ok(if shared {
+                    Instance::Shared(RcThreadSafety::new(dependency))
+                } else {
+                    Instance::Owned(Box::new(dependency))
+                })
             }
         }
     })))
@@ -59,7 +76,13 @@ where
 
 #[must_use]
 pub(crate) fn boxed_container_instantiator() -> BoxedCloneInstantiator<ResolveErrorKind, InstantiateErrorKind> {
-    BoxCloneService::new(Box::new(service_fn(async move |container| Ok(Box::new(container) as _))))
+    BoxCloneService::new(Box::new(service_fn(async move |(container, shared): (Container, bool)| {
+        Ok(if shared {
+            Instance::Shared(RcThreadSafety::new(container))
+        } else {
+            Instance::Owned(Box::new(container))
+        })
+    })))
 }
 
 macro_rules! impl_instantiator {
@@ -89,6 +112,8 @@ macro_rules! impl_instantiator {
                     $(
                         Dependency {
                             type_info: $ty::type_info(),
+                            is_transient: $ty::is_transient(),
+                            is_weak: $ty::is_weak(),
                         }
                     ),*
                 ])
@@ -138,21 +163,24 @@ mod tests {
         let instantiator_request_call_count = RcThreadSafety::new(AtomicU8::new(0));
         let instantiator_response_call_count = RcThreadSafety::new(AtomicU8::new(0));
 
-        let mut instantiator_response = boxed_instantiator({
-            let instantiator_response_call_count = instantiator_response_call_count.clone();
-            move |InjectTransient(Request(val_1)), InjectTransient(Request(val_2))| {
+        let mut instantiator_response = boxed_instantiator(
+            {
                 let instantiator_response_call_count = instantiator_response_call_count.clone();
+                move |InjectTransient(Request(val_1)), InjectTransient(Request(val_2))| {
+                    let instantiator_response_call_count = instantiator_response_call_count.clone();
 
-                async move {
-                    assert_eq!(val_1, val_2);
+                    async move {
+                        assert_eq!(val_1, val_2);
 
-                    instantiator_response_call_count.fetch_add(1, Ordering::SeqCst);
+                        instantiator_response_call_count.fetch_add(1, Ordering::SeqCst);
 
-                    debug!("Call instantiator response");
-                    Ok::<_, InstantiateErrorKind>(Response(val_1))
+                        debug!("Call instantiator response");
+                        Ok::<_, InstantiateErrorKind>(Response(val_1))
+                    }
                 }
-            }
-        });
+            },
+            true,
+        );
 
         let container = Container::new(async_registry! {
             scope(App) [
@@ -172,11 +200,11 @@ mod tests {
             ]
         });
 
-        let response_1 = instantiator_response.call(container.clone()).await.unwrap();
-        let response_2 = instantiator_response.call(container).await.unwrap();
+        let response_1 = instantiator_response.call((container.clone(), false)).await.unwrap();
+        let response_2 = instantiator_response.call((container, true)).await.unwrap();
 
-        assert!(response_1.downcast::<Response>().unwrap().0);
-        assert!(response_2.downcast::<Response>().unwrap().0);
+        assert!(response_1.downcast_owned::<Response>().unwrap().0);
+        assert!(response_2.downcast_shared::<Response>().unwrap().0);
         assert_eq!(instantiator_request_call_count.load(Ordering::SeqCst), 4);
         assert_eq!(instantiator_response_call_count.load(Ordering::SeqCst), 2);
     }
@@ -187,21 +215,24 @@ mod tests {
         let instantiator_request_call_count = RcThreadSafety::new(AtomicU8::new(0));
         let instantiator_response_call_count = RcThreadSafety::new(AtomicU8::new(0));
 
-        let mut instantiator_response = boxed_instantiator({
-            let instantiator_response_call_count = instantiator_response_call_count.clone();
-            move |val_1: Inject<Request>, val_2: Inject<Request>| {
+        let mut instantiator_response = boxed_instantiator(
+            {
                 let instantiator_response_call_count = instantiator_response_call_count.clone();
+                move |val_1: Inject<Request>, val_2: Inject<Request>| {
+                    let instantiator_response_call_count = instantiator_response_call_count.clone();
 
-                async move {
-                    assert_eq!(val_1.0 .0, val_2.0 .0);
+                    async move {
+                        assert_eq!(val_1.0 .0, val_2.0 .0);
 
-                    instantiator_response_call_count.fetch_add(1, Ordering::SeqCst);
+                        instantiator_response_call_count.fetch_add(1, Ordering::SeqCst);
 
-                    debug!("Call instantiator response");
-                    Ok::<_, InstantiateErrorKind>(Response(val_1.0 .0))
+                        debug!("Call instantiator response");
+                        Ok::<_, InstantiateErrorKind>(Response(val_1.0 .0))
+                    }
                 }
-            }
-        });
+            },
+            true,
+        );
 
         let container = Container::new(async_registry! {
             scope(App) [
@@ -221,13 +252,13 @@ mod tests {
             ]
         });
 
-        let response_1 = instantiator_response.call(container.clone()).await.unwrap();
-        let response_2 = instantiator_response.call(container.clone()).await.unwrap();
-        let response_3 = instantiator_response.call(container).await.unwrap();
+        let response_1 = instantiator_response.call((container.clone(), true)).await.unwrap();
+        let response_2 = instantiator_response.call((container.clone(), true)).await.unwrap();
+        let response_3 = instantiator_response.call((container, false)).await.unwrap();
 
-        assert!(response_1.downcast::<Response>().unwrap().0);
-        assert!(response_2.downcast::<Response>().unwrap().0);
-        assert!(response_3.downcast::<Response>().unwrap().0);
+        assert!(response_1.downcast_shared::<Response>().unwrap().0);
+        assert!(response_2.downcast_shared::<Response>().unwrap().0);
+        assert!(response_3.downcast_owned::<Response>().unwrap().0);
         assert_eq!(instantiator_request_call_count.load(Ordering::SeqCst), 1);
         // We don't cache instantiator provides of main factory here, we do it in container
         assert_eq!(instantiator_response_call_count.load(Ordering::SeqCst), 3);
@@ -0,0 +1,584 @@
+//! Async factories whose futures aren't `Send`, for single-threaded executors such as `tokio::task::LocalSet`.
+//!
+//! Factories of the local [`Registry`] can hold `Rc` or other `!Send` state across awaits,
+//! while their instances stay `Send` and `Sync` as the ones of the async container.
+//! The local [`Container`] is a mode of the async container it wraps: local instances are cached in its levels,
+//! so the wrapped container gets them from its cache, and local factories are called with the config of their registration,
+//! timed out and retried as async ones, and finalized on [`Container::close`].
+//!
+//! Before resolving a type, the local container resolves the local types it depends on,
+//! so local factories and factories of the async registry can depend on local types with [`crate::Inject`].
+//! Such dependencies can be declared with [`super::RegistryWithSync::with_external`] for validation.
+//! Local types can't be resolved by the wrapped container without being cached,
+//! so they aren't injected with [`crate::InjectTransient`] or with `cache_provides` disabled.
+//! A `!Send` factory can't be spawned, so local instances are replaced [`crate::Refresh::OnAccess`].
+
+use alloc::{
+    borrow::Cow,
+    boxed::Box,
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    rc::Rc,
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug, Formatter},
+    future::Future,
+};
+use tracing::debug;
+
+use super::{
+    container::{
+        ChildContainerBuilder as AsyncChildContainerBuilder, ChildContainerWithContext, ChildContainerWithScope,
+        ChildContainerWithScopeAndContext, Provider,
+    },
+    Container as AsyncContainer,
+};
+use crate::{
+    any::TypeInfo,
+    config::Config,
+    context::Context,
+    dependency::Dependency,
+    dependency_resolver::DependencyResolver,
+    errors::{InstantiateErrorKind, InstantiatorErrorKind, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind},
+    instantiator::Instance,
+    scope::{Scope, ScopeData},
+    utils::{
+        future::LocalBoxFuture,
+        thread_safety::{RcAnyThreadSafety, RcThreadSafety, SendSafety, SyncSafety},
+    },
+};
+
+/// Like [`super::Instantiator`], but the future doesn't have to be `Send`
+pub trait LocalInstantiator<Deps>: Clone + 'static
+where
+    Deps: DependencyResolver,
+{
+    type Provides: 'static;
+    type Error: Into<InstantiateErrorKind>;
+
+    fn instantiate(&mut self, dependencies: Deps) -> impl Future<Output = Result<Self::Provides, Self::Error>>;
+
+    fn dependencies() -> BTreeSet<Dependency>;
+}
+
+/// Like [`super::Finalizer`], but the future doesn't have to be `Send`
+pub trait LocalFinalizer<Dep>: Clone + 'static {
+    fn finalize(&mut self, dependency: RcThreadSafety<Dep>) -> impl Future<Output = ()>;
+}
+
+type LocalInstantiateResult = Result<Instance, InstantiatorErrorKind<ResolveErrorKind, InstantiateErrorKind>>;
+
+/// Instantiator called with the container of its scope and whether the instance should be shared
+type BoxedLocalInstantiator = Rc<dyn Fn(AsyncContainer, bool) -> LocalBoxFuture<'static, LocalInstantiateResult>>;
+
+type BoxedLocalFinalizer = Rc<dyn Fn(RcAnyThreadSafety) -> LocalBoxFuture<'static, ()>>;
+
+fn boxed_local_instantiator<Inst, Deps>(instantiator: Inst, resolve_concurrently: bool) -> BoxedLocalInstantiator
+where
+    Inst: LocalInstantiator<Deps>,
+    Inst::Provides: SendSafety + SyncSafety,
+    Deps: DependencyResolver + 'static,
+{
+    Rc::new(move |container: AsyncContainer, shared: bool| {
+        let mut instantiator = instantiator.clone();
+
+        Box::pin(async move {
+            let dependencies = if resolve_concurrently {
+                Deps::resolve_async_concurrently(&container).await
+            } else {
+                Deps::resolve_async(&container).await
+            };
+            let dependencies = match dependencies {
+                Ok(dependencies) => dependencies,
+                Err(err) => return Err(InstantiatorErrorKind::Deps(err.into())),
+            };
+            let dependency = match instantiator.instantiate(dependencies).await {
+                Ok(dependency) => dependency,
+                Err(err) => return Err(InstantiatorErrorKind::Factory(err.into())),
+            };
+
+            debug!("Resolved");
+
+            Ok(if shared {
+                Instance::Shared(RcThreadSafety::new(dependency))
+            } else {
+                Instance::Owned(Box::new(dependency))
+            })
+        })
+    })
+}
+
+fn boxed_local_finalizer<Dep, Fin>(finalizer: Fin) -> BoxedLocalFinalizer
+where
+    Dep: SendSafety + SyncSafety + 'static,
+    Fin: LocalFinalizer<Dep>,
+{
+    Rc::new(move |dependency: RcAnyThreadSafety| {
+        let mut finalizer = finalizer.clone();
+        let dependency = dependency.downcast::<Dep>().expect("Failed to downcast value in finalizer factory");
+
+        Box::pin(async move { finalizer.finalize(dependency).await })
+    })
+}
+
+#[derive(Clone)]
+struct LocalInstantiatorData {
+    instantiator: BoxedLocalInstantiator,
+    finalizer: Option<BoxedLocalFinalizer>,
+    dependencies: BTreeSet<Dependency>,
+    config: Config,
+    scope_data: ScopeData,
+    /// Resolves the type with [`Container::get_prepared`], once the local types it depends on are resolved
+    resolve: fn(&Container) -> LocalBoxFuture<'_, Result<(), ResolveErrorKind>>,
+}
+
+impl Provider for LocalInstantiatorData {
+    type Instantiate = LocalBoxFuture<'static, LocalInstantiateResult>;
+    type Finalize = LocalBoxFuture<'static, ()>;
+
+    const SPAWNS: bool = false;
+
+    #[inline]
+    fn scope_data(&self) -> &ScopeData {
+        &self.scope_data
+    }
+
+    #[inline]
+    fn config(&self) -> &Config {
+        &self.config
+    }
+
+    #[inline]
+    fn has_finalizer(&self) -> bool {
+        self.finalizer.is_some()
+    }
+
+    #[inline]
+    fn instantiate(&self, container: AsyncContainer, shared: bool) -> Self::Instantiate {
+        (self.instantiator)(container, shared)
+    }
+
+    #[inline]
+    fn finalize(&self, dependency: RcAnyThreadSafety) -> Option<Self::Finalize> {
+        Some((self.finalizer.as_ref()?)(dependency))
+    }
+}
+
+fn resolve_prepared<Dep: SendSafety + SyncSafety + 'static>(container: &Container) -> LocalBoxFuture<'_, Result<(), ResolveErrorKind>> {
+    Box::pin(async move { container.get_prepared::<Dep>().await.map(|_| ()) })
+}
+
+/// Registry of local factories, see the [module docs](self)
+#[derive(Clone, Default)]
+pub struct Registry {
+    entries: BTreeMap<TypeInfo, LocalInstantiatorData>,
+}
+
+impl Registry {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a factory of `scope` with the default config, replacing the previous one of the same type
+    #[inline]
+    #[must_use]
+    pub fn provide<Inst, Deps>(self, scope: impl Scope, instantiator: Inst) -> Self
+    where
+        Inst: LocalInstantiator<Deps>,
+        Inst::Provides: SendSafety + SyncSafety,
+        Deps: DependencyResolver + 'static,
+    {
+        self.provide_with_config(scope, instantiator, Config::default())
+    }
+
+    /// Registers a factory of `scope`, replacing the previous one of the same type
+    #[must_use]
+    pub fn provide_with_config<Inst, Deps>(self, scope: impl Scope, instantiator: Inst, config: Config) -> Self
+    where
+        Inst: LocalInstantiator<Deps>,
+        Inst::Provides: SendSafety + SyncSafety,
+        Deps: DependencyResolver + 'static,
+    {
+        self.insert::<Inst, Deps>(scope.into(), instantiator, config, None)
+    }
+
+    /// Registers a factory of `scope` with a finalizer called on [`Container::close`], replacing the previous one of the same type
+    #[must_use]
+    pub fn provide_with_finalizer<Inst, Deps, Fin>(self, scope: impl Scope, instantiator: Inst, config: Config, finalizer: Fin) -> Self
+    where
+        Inst: LocalInstantiator<Deps>,
+        Inst::Provides: SendSafety + SyncSafety,
+        Deps: DependencyResolver + 'static,
+        Fin: LocalFinalizer<Inst::Provides>,
+    {
+        let finalizer = boxed_local_finalizer(finalizer);
+        self.insert::<Inst, Deps>(scope.into(), instantiator, config, Some(finalizer))
+    }
+
+    fn insert<Inst, Deps>(
+        mut self,
+        scope_data: ScopeData,
+        instantiator: Inst,
+        config: Config,
+        finalizer: Option<BoxedLocalFinalizer>,
+    ) -> Self
+    where
+        Inst: LocalInstantiator<Deps>,
+        Inst::Provides: SendSafety + SyncSafety,
+        Deps: DependencyResolver + 'static,
+    {
+        self.entries.insert(
+            TypeInfo::of::<Inst::Provides>(),
+            LocalInstantiatorData {
+                instantiator: boxed_local_instantiator(instantiator, config.resolve_dependencies_concurrently),
+                finalizer,
+                dependencies: Inst::dependencies(),
+                config,
+                scope_data,
+                resolve: resolve_prepared::<Inst::Provides>,
+            },
+        );
+        self
+    }
+}
+
+/// Container of local factories, see the [module docs](self).
+///
+/// Unlike the async container, it and its futures aren't `Send`
+#[derive(Clone)]
+pub struct Container {
+    container: AsyncContainer,
+    registry: Rc<Registry>,
+}
+
+impl Container {
+    /// Wraps the container, local instances are cached in its levels
+    #[inline]
+    #[must_use]
+    pub fn new(container: AsyncContainer, registry: Registry) -> Self {
+        Self {
+            container,
+            registry: Rc::new(registry),
+        }
+    }
+
+    /// The wrapped container, it gets the local instances resolved by this one from its cache
+    #[inline]
+    #[must_use]
+    pub fn container(&self) -> &AsyncContainer {
+        &self.container
+    }
+
+    /// Creates child container builder, see [`AsyncContainer::enter`]
+    #[inline]
+    #[must_use]
+    pub fn enter(self) -> ChildContainerBuilder<AsyncChildContainerBuilder> {
+        ChildContainerBuilder {
+            builder: self.container.enter(),
+            registry: self.registry,
+        }
+    }
+
+    /// Creates child container and builds it with next non-skipped scope, see [`AsyncContainer::enter_build`]
+    ///
+    /// # Errors
+    /// See [`AsyncContainer::enter_build`]
+    #[inline]
+    pub fn enter_build(self) -> Result<Self, ScopeErrorKind> {
+        self.enter().build()
+    }
+
+    /// Gets a scoped dependency from the container, see [`AsyncContainer::get`]
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get<Dep: SendSafety + SyncSafety + 'static>(&self) -> Result<RcThreadSafety<Dep>, ResolveErrorKind> {
+        if let Some(dependency) = self.container.get_cached::<Dep>()? {
+            debug!("Found in cache");
+            return Ok(dependency);
+        }
+        self.prepare(&TypeInfo::of::<Dep>(), false).await?;
+        self.get_prepared().await
+    }
+
+    /// Gets a transient dependency from the container, see [`AsyncContainer::get_transient`]
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn get_transient<Dep: 'static>(&self) -> Result<Dep, ResolveErrorKind> {
+        let type_info = TypeInfo::of::<Dep>();
+        self.prepare(&type_info, true).await?;
+        match self.registry.entries.get(&type_info) {
+            Some(data) => self.container.get_transient_with(type_info, data).await,
+            None => {
+                debug!("No local instantiator found, trying async container");
+                self.container.get_transient().await
+            }
+        }
+    }
+
+    /// Gets a scoped dependency whose local dependencies are resolved, see [`Self::prepare`]
+    async fn get_prepared<Dep: SendSafety + SyncSafety + 'static>(&self) -> Result<RcThreadSafety<Dep>, ResolveErrorKind> {
+        let type_info = TypeInfo::of::<Dep>();
+        let Some(data) = self.registry.entries.get(&type_info) else {
+            debug!("No local instantiator found, trying async container");
+            return self.container.get().await;
+        };
+        if let Some(dependency) = self.container.get_cached::<Dep>()? {
+            debug!("Found in cache");
+            return Ok(dependency);
+        }
+        self.container.get_with(type_info, data).await
+    }
+
+    /// Resolves the local types the type depends on, so the wrapped container gets them from its cache
+    async fn prepare(&self, type_info: &TypeInfo, is_transient: bool) -> Result<(), ResolveErrorKind> {
+        let mut visited = BTreeSet::new();
+        let mut pending = Vec::new();
+        self.local_dependencies(&self.container, type_info, is_transient, &mut visited, &mut pending);
+        for (container, data) in pending {
+            let container = Self {
+                container,
+                registry: self.registry.clone(),
+            };
+            (data.resolve)(&container).await?;
+        }
+        Ok(())
+    }
+
+    /// Collects the local types the type depends on through the types that aren't cached,
+    /// each after the local types it depends on and with the container its dependent is resolved in.
+    /// Inaccessible types are skipped, their resolution returns the error
+    fn local_dependencies<'a>(
+        &'a self,
+        container: &AsyncContainer,
+        type_info: &TypeInfo,
+        is_transient: bool,
+        visited: &mut BTreeSet<(u8, TypeInfo)>,
+        pending: &mut Vec<(AsyncContainer, &'a LocalInstantiatorData)>,
+    ) {
+        let (scope_data, dependencies) = match self.registry.entries.get(type_info) {
+            Some(data) => (&data.scope_data, &data.dependencies),
+            None => match container.provider(type_info) {
+                Some(provider) => provider,
+                None => return,
+            },
+        };
+        let Some(container) = container.in_scope(scope_data) else {
+            return;
+        };
+        if !is_transient && container.contains_cached(type_info) {
+            return;
+        }
+
+        for dependency in dependencies {
+            if dependency.is_weak || !visited.insert((scope_data.priority, dependency.type_info.clone())) {
+                continue;
+            }
+            self.local_dependencies(&container, &dependency.type_info, dependency.is_transient, visited, pending);
+            if let (Some(data), false) = (self.registry.entries.get(&dependency.type_info), dependency.is_transient) {
+                pending.push((container.clone(), data));
+            }
+        }
+    }
+
+    /// Closes the wrapped container, calling finalizers of local instances as well, see [`AsyncContainer::close`]
+    pub async fn close(&self) {
+        self.container
+            .close_with(|type_info, dependency| self.registry.entries.get(type_info)?.finalize(dependency))
+            .await;
+    }
+}
+
+impl Debug for Container {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Container")
+            .field("container", &self.container)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builder of a child container, wrapping the one of the async container, see [`AsyncContainer::enter`]
+pub struct ChildContainerBuilder<B> {
+    builder: B,
+    registry: Rc<Registry>,
+}
+
+impl<B> ChildContainerBuilder<B> {
+    #[inline]
+    fn map<T>(self, f: impl FnOnce(B) -> T) -> ChildContainerBuilder<T> {
+        ChildContainerBuilder {
+            builder: f(self.builder),
+            registry: self.registry,
+        }
+    }
+
+    #[inline]
+    fn build_with<E>(self, build: impl FnOnce(B) -> Result<AsyncContainer, E>) -> Result<Container, E> {
+        Ok(Container {
+            container: build(self.builder)?,
+            registry: self.registry,
+        })
+    }
+}
+
+impl ChildContainerBuilder<AsyncChildContainerBuilder> {
+    #[inline]
+    #[must_use]
+    pub fn with_scope<S: Scope>(self, scope: S) -> ChildContainerBuilder<ChildContainerWithScope<S>> {
+        self.map(|builder| builder.with_scope(scope))
+    }
+
+    /// Enters a scope by its name, for scopes that are only known at runtime
+    #[inline]
+    #[must_use]
+    pub fn with_scope_name(self, name: impl Into<Cow<'static, str>>) -> ChildContainerBuilder<ChildContainerWithScope<Cow<'static, str>>> {
+        self.map(|builder| builder.with_scope_name(name))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn with_context(self, context: Context) -> ChildContainerBuilder<ChildContainerWithContext> {
+        self.map(|builder| builder.with_context(context))
+    }
+
+    /// Builds the child container, see [`AsyncContainer::enter`]
+    ///
+    /// # Errors
+    /// Returns the error of the builder of the wrapped container
+    #[inline]
+    pub fn build(self) -> Result<Container, ScopeErrorKind> {
+        self.build_with(AsyncChildContainerBuilder::build)
+    }
+}
+
+impl<S: Scope> ChildContainerBuilder<ChildContainerWithScope<S>> {
+    #[inline]
+    #[must_use]
+    pub fn with_context(self, context: Context) -> ChildContainerBuilder<ChildContainerWithScopeAndContext<S>> {
+        self.map(|builder| builder.with_context(context))
+    }
+
+    /// Builds the child container, see [`AsyncContainer::enter`]
+    ///
+    /// # Errors
+    /// Returns the error of the builder of the wrapped container
+    #[inline]
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.build_with(|builder| builder.build())
+    }
+}
+
+impl ChildContainerBuilder<ChildContainerWithScope<Cow<'static, str>>> {
+    #[inline]
+    #[must_use]
+    pub fn with_context(self, context: Context) -> ChildContainerBuilder<ChildContainerWithScopeAndContext<Cow<'static, str>>> {
+        self.map(|builder| builder.with_context(context))
+    }
+
+    /// Builds the child container, see [`AsyncContainer::enter`]
+    ///
+    /// # Errors
+    /// Returns the error of the builder of the wrapped container
+    #[inline]
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.build_with(|builder| builder.build())
+    }
+}
+
+impl ChildContainerBuilder<ChildContainerWithContext> {
+    #[inline]
+    #[must_use]
+    pub fn with_scope<S: Scope>(self, scope: S) -> ChildContainerBuilder<ChildContainerWithScopeAndContext<S>> {
+        self.map(|builder| builder.with_scope(scope))
+    }
+
+    /// Enters a scope by its name, for scopes that are only known at runtime
+    #[inline]
+    #[must_use]
+    pub fn with_scope_name(
+        self,
+        name: impl Into<Cow<'static, str>>,
+    ) -> ChildContainerBuilder<ChildContainerWithScopeAndContext<Cow<'static, str>>> {
+        self.map(|builder| builder.with_scope_name(name))
+    }
+
+    /// Builds the child container, see [`AsyncContainer::enter`]
+    ///
+    /// # Errors
+    /// Returns the error of the builder of the wrapped container
+    #[inline]
+    pub fn build(self) -> Result<Container, ScopeErrorKind> {
+        self.build_with(ChildContainerWithContext::build)
+    }
+}
+
+impl<S: Scope> ChildContainerBuilder<ChildContainerWithScopeAndContext<S>> {
+    /// Builds the child container, see [`AsyncContainer::enter`]
+    ///
+    /// # Errors
+    /// Returns the error of the builder of the wrapped container
+    #[inline]
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.build_with(|builder| builder.build())
+    }
+}
+
+impl ChildContainerBuilder<ChildContainerWithScopeAndContext<Cow<'static, str>>> {
+    /// Builds the child container, see [`AsyncContainer::enter`]
+    ///
+    /// # Errors
+    /// Returns the error of the builder of the wrapped container
+    #[inline]
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.build_with(|builder| builder.build())
+    }
+}
+
+macro_rules! impl_local_instantiator {
+    (
+        [$($ty:ident),*]
+    ) => {
+        #[allow(non_snake_case)]
+        impl<F, Fut, Response, Err, $($ty,)*> LocalInstantiator<($($ty,)*)> for F
+        where
+            F: FnMut($($ty,)*) -> Fut + Clone + 'static,
+            Fut: Future<Output = Result<Response, Err>>,
+            Response: 'static,
+            Err: Into<InstantiateErrorKind>,
+            $( $ty: DependencyResolver + SendSafety + 'static, )*
+        {
+            type Provides = Response;
+            type Error = Err;
+
+            #[inline]
+            fn instantiate(&mut self, ($($ty,)*): ($($ty,)*)) -> impl Future<Output = Result<Self::Provides, Self::Error>> {
+                self($($ty,)*)
+            }
+
+            #[inline]
+            fn dependencies() -> BTreeSet<Dependency> {
+                BTreeSet::from_iter([
+                    $(
+                        Dependency {
+                            type_info: $ty::type_info(),
+                            is_transient: $ty::is_transient(),
+                            is_weak: $ty::is_weak(),
+                        }
+                    ),*
+                ])
+            }
+        }
+    };
+}
+
+all_the_tuples!(impl_local_instantiator);
+
+impl<F, Fut, Dep> LocalFinalizer<Dep> for F
+where
+    F: FnMut(RcThreadSafety<Dep>) -> Fut + Clone + 'static,
+    Fut: Future<Output = ()>,
+{
+    #[inline]
+    fn finalize(&mut self, dependency: RcThreadSafety<Dep>) -> impl Future<Output = ()> {
+        self(dependency)
+    }
+}
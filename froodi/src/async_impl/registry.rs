@@ -1,5 +1,7 @@
 use alloc::{
+    boxed::Box,
     collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    string::String,
     vec::Vec,
 };
 
@@ -10,9 +12,18 @@ use crate::{
         instantiator::{boxed_container_instantiator, BoxedCloneInstantiator},
         Container,
     },
+    clock::{Clock, RcClock},
+    conflict::{
+        detect_duplicate_providers, insert_entry, ConflictPolicy, DuplicateProvider, ProvideKind, Registered, Registration,
+        RegistrationSite,
+    },
     dependency::{Dependency, EMPTY_DEPENDENCIES},
     errors::ValidationErrorKind,
-    scope::{ScopeData, ScopeDataWithChildScopesData},
+    graph::{ProviderInfo, RegistryManifest},
+    registry::detect_missing_dependencies,
+    scope::{extend_scopes_data, ScopeData, ScopeDataWithChildScopesData},
+    slot::{Frozen, SlotIndex},
+    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
     Config, DefaultScope, InstantiateErrorKind, Registry as SyncRegistry, ResolveErrorKind, Scope, Scopes,
 };
 
@@ -23,17 +34,30 @@ pub struct InstantiatorData {
     pub(crate) finalizer: Option<BoxedCloneFinalizer>,
     pub(crate) config: Config,
     pub(crate) scope_data: ScopeData,
+    pub(crate) registration: Registration,
+}
+
+impl Registered for InstantiatorData {
+    #[inline]
+    fn registration(&self) -> &Registration {
+        &self.registration
+    }
 }
 
 #[derive(Clone, Default)]
 pub struct Registry {
     pub(crate) entries: BTreeMap<TypeInfo, InstantiatorData>,
     pub(crate) scopes_data: Vec<ScopeData>,
+    /// Set explicitly with [`Self::with_conflict_policy`], or taken over from a merged registry
+    pub(crate) conflict_policy: Option<ConflictPolicy>,
+    pub(crate) duplicates: Vec<DuplicateProvider>,
+    pub(crate) clock: Option<RcClock>,
+    /// Slot order of the entries, dropped on changes, see [`Self::freeze`]
+    pub(crate) frozen: Option<Frozen<InstantiatorData>>,
 }
 
 impl Registry {
-    #[allow(clippy::similar_names)]
-    pub(crate) fn new<T, S, const N: usize>(mut entries: BTreeMap<TypeInfo, InstantiatorData>) -> Self
+    pub(crate) fn new<T, S, const N: usize>(entries: BTreeMap<TypeInfo, InstantiatorData>) -> Self
     where
         S: Scope,
         T: Scopes<N, Scope = S>,
@@ -41,36 +65,71 @@ impl Registry {
         let (scope, child_scopes) = T::all();
 
         let mut scopes_data = Vec::with_capacity(N + 1);
-        let scope_data = scope.into();
+        scopes_data.push(scope.into());
+        for scope in child_scopes {
+            scopes_data.push(scope.into());
+        }
+        Self::with_scopes_data(entries, scopes_data)
+    }
 
-        entries.insert(
-            {
-                #[cfg(const_type_id)]
-                const {
-                    TypeInfo::new::<Container>("async_impl::Container")
-                }
-                #[cfg(not(const_type_id))]
+    /// Creates a registry with the scopes, registering the built-in [`Container`] entry in the root (lowest-priority) scope
+    pub(crate) fn with_scopes_data(mut entries: BTreeMap<TypeInfo, InstantiatorData>, mut scopes_data: Vec<ScopeData>) -> Self {
+        scopes_data.sort_by_key(|scope| scope.priority);
+        if let Some(scope_data) = scopes_data.first() {
+            entries.insert(
                 {
-                    TypeInfo::new::<Container>("async_impl::Container")
-                }
-            },
-            InstantiatorData {
-                instantiator: boxed_container_instantiator(),
-                dependencies: EMPTY_DEPENDENCIES,
-                finalizer: None,
-                // Caching the container in its own cache creates an cycle
-                // that prevents `Drop`/`close` from ever running
-                config: Config { cache_provides: false },
-                scope_data,
-            },
-        );
+                    #[cfg(const_type_id)]
+                    const {
+                        TypeInfo::new::<Container>("async_impl::Container")
+                    }
+                    #[cfg(not(const_type_id))]
+                    {
+                        TypeInfo::new::<Container>("async_impl::Container")
+                    }
+                },
+                InstantiatorData {
+                    instantiator: boxed_container_instantiator(),
+                    dependencies: EMPTY_DEPENDENCIES,
+                    finalizer: None,
+                    // Caching the container in its own cache creates an cycle
+                    // that prevents `Drop`/`close` from ever running
+                    config: Config::default().cache_provides(false),
+                    scope_data: scope_data.clone(),
+                    registration: Registration {
+                        kind: ProvideKind::Default,
+                        site: RegistrationSite::of::<Container>(),
+                    },
+                },
+            );
+        }
 
-        scopes_data.push(scope_data);
-        for scope in child_scopes {
-            scopes_data.push(scope.into());
+        Self {
+            entries,
+            scopes_data,
+            conflict_policy: None,
+            duplicates: Vec::new(),
+            clock: None,
+            frozen: None,
         }
+    }
 
-        Self { entries, scopes_data }
+    /// Replaces the scopes of the registry, see [`SyncRegistry::with_scopes`]
+    #[must_use]
+    pub fn with_scopes(self, scopes: impl IntoIterator<Item = ScopeData>) -> Self {
+        let Self {
+            mut entries,
+            conflict_policy,
+            duplicates,
+            clock,
+            ..
+        } = self;
+        entries.remove(&TypeInfo::of::<Container>());
+        Self {
+            clock,
+            conflict_policy,
+            duplicates,
+            ..Self::with_scopes_data(entries, scopes.into_iter().collect())
+        }
     }
 
     #[inline]
@@ -78,6 +137,77 @@ impl Registry {
     pub fn new_with_default_entries() -> Self {
         Self::new::<DefaultScope, DefaultScope, 5>(BTreeMap::new())
     }
+
+    /// Sets the policy for registrations of already registered types in registries merged into this one afterwards.
+    /// See [`ConflictPolicy`].
+    #[inline]
+    #[must_use]
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = Some(policy);
+        self
+    }
+
+    /// Sets the clock used to expire cached values with a TTL, see [`Clock`]
+    #[inline]
+    #[must_use]
+    pub fn with_clock<C: Clock + SendSafety + SyncSafety + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(RcThreadSafety::new(clock));
+        self
+    }
+
+    /// Assigns each registered type a dense slot, see [`SyncRegistry::freeze`]
+    #[must_use]
+    pub fn freeze(mut self) -> Self {
+        if self.frozen.is_none() {
+            self.frozen = Some(Frozen::new(&self.entries));
+        }
+        self
+    }
+
+    /// Slot index of the frozen registry, empty if it isn't frozen
+    #[inline]
+    #[must_use]
+    pub(crate) fn slot_index(&self) -> RcThreadSafety<SlotIndex> {
+        self.frozen.as_ref().map(|frozen| frozen.index.clone()).unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn insert_entry(&mut self, key: TypeInfo, value: InstantiatorData) {
+        self.frozen = None;
+        let unknown_scope_data =
+            (!self.scopes_data.iter().any(|known| known.priority == value.scope_data.priority)).then(|| value.scope_data.clone());
+        insert_entry(
+            &mut self.entries,
+            &mut self.duplicates,
+            self.conflict_policy.unwrap_or_default(),
+            key,
+            value,
+        );
+        self.extend_scopes_data(unknown_scope_data);
+    }
+
+    /// Adds the scopes unknown to the registry, keeping the built-in container entry in the root scope
+    fn extend_scopes_data(&mut self, scopes_data: impl IntoIterator<Item = ScopeData>) {
+        self.frozen = None;
+        extend_scopes_data(&mut self.scopes_data, scopes_data);
+        // The root scope may have changed, and the built-in container entry of a merged registry may have replaced ours
+        if let (Some(container), Some(root)) = (self.entries.get_mut(&TypeInfo::of::<Container>()), self.scopes_data.first()) {
+            if container.scope_data.priority != root.priority {
+                container.scope_data = root.clone();
+            }
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: Registry) {
+        // The policy is adopted first, so it also applies to the conflicts of the merged entries
+        self.conflict_policy = self.conflict_policy.or(other.conflict_policy);
+        self.duplicates.extend(other.duplicates);
+        for (key, value) in other.entries {
+            self.insert_entry(key, value);
+        }
+        self.extend_scopes_data(other.scopes_data);
+        self.clock = self.clock.take().or(other.clock);
+    }
 }
 
 #[derive(Clone, Default)]
@@ -87,10 +217,115 @@ pub struct RegistryWithSync {
 }
 
 impl RegistryWithSync {
-    /// Validates both the async and the embedded sync registry (cycles + scope reachability).
+    /// Declares `T` as supplied from outside the registry, for example through a [`crate::Context`],
+    /// so [`Self::validate`] doesn't report dependencies on it as missing.
+    #[inline]
+    #[must_use]
+    pub fn with_external<T: ?Sized + 'static>(mut self) -> Self {
+        self.sync = self.sync.with_external::<T>();
+        self
+    }
+
+    /// Replaces the scopes of both the async and the embedded sync registry, see [`SyncRegistry::with_scopes`]
+    #[must_use]
+    pub fn with_scopes(self, scopes: impl IntoIterator<Item = ScopeData>) -> Self {
+        let scopes: Vec<_> = scopes.into_iter().collect();
+        Self {
+            registry: self.registry.with_scopes(scopes.clone()),
+            sync: self.sync.with_scopes(scopes),
+        }
+    }
+
+    /// Freezes both the async and the embedded sync registry, see [`SyncRegistry::freeze`]
+    #[inline]
+    #[must_use]
+    pub fn freeze(self) -> Self {
+        Self {
+            registry: self.registry.freeze(),
+            sync: self.sync.freeze(),
+        }
+    }
+
+    /// Sets the clock used to expire cached values with a TTL, for both the async and the embedded sync registry.
+    /// See [`Clock`]
+    #[inline]
+    #[must_use]
+    pub fn with_clock<C: Clock + SendSafety + SyncSafety + 'static>(mut self, clock: C) -> Self {
+        let clock: RcClock = RcThreadSafety::new(clock);
+        self.registry.clock = Some(clock.clone());
+        self.sync.clock = Some(clock);
+        self
+    }
+
+    /// Sets the policy for registrations of already registered types in registries merged into this one afterwards,
+    /// for both the async and the embedded sync registry. See [`ConflictPolicy`].
+    #[inline]
+    #[must_use]
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.registry = self.registry.with_conflict_policy(policy);
+        self.sync = self.sync.with_conflict_policy(policy);
+        self
+    }
+
+    /// Validates both the async and the embedded sync registry (conflicting registrations, cycles, scope reachability and
+    /// dependencies without a provider).
+    /// Async instantiators may depend on sync ones, because the async container falls back to its embedded sync container.
+    ///
+    /// There's no lazy injector, a dependency is instantiated before its dependent,
+    /// so only [`crate::InjectWeak`] edges are exempt from the cycle check.
+    ///
+    /// # Errors
+    /// See [`SyncRegistry::validate`].
     pub fn validate(&self) -> Result<(), ValidationErrorKind> {
-        self.registry.validate()?;
-        self.sync.validate()
+        self.validate_partial()?;
+        self.registry
+            .detect_missing_dependencies(|type_info| self.registry.entries.contains_key(type_info) || self.sync.provides(type_info))?;
+        self.sync.detect_missing_dependencies(|type_info| self.sync.provides(type_info))
+    }
+
+    /// Describes the wiring of both registries, highlighting the error reported by [`Self::validate`]
+    #[must_use]
+    pub fn manifest(&self) -> RegistryManifest {
+        let mut scopes_data = self.registry.scopes_data.clone();
+        scopes_data.extend_from_slice(&self.sync.scopes_data);
+        RegistryManifest::new(
+            scopes_data,
+            self.registry.provider_infos().chain(self.sync.provider_infos()),
+            |type_info| self.registry.entries.contains_key(type_info) || self.sync.provides(type_info),
+            self.validate(),
+        )
+    }
+
+    /// Renders both registries in the Graphviz DOT format, see [`RegistryManifest::to_dot`]
+    #[inline]
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        self.manifest().to_dot()
+    }
+
+    /// Renders both registries as a Mermaid flowchart, see [`RegistryManifest::to_mermaid`]
+    #[inline]
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        self.manifest().to_mermaid()
+    }
+
+    /// Serializes the manifest of both registries to JSON
+    #[cfg(feature = "serde")]
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        self.manifest().to_json()
+    }
+
+    /// Validates conflicting registrations, cycles and scope reachability only.
+    ///
+    /// The `async_registry!` macro uses this, because its registries may be merged with the ones providing
+    /// the missing dependencies later.
+    #[doc(hidden)]
+    pub fn validate_partial(&self) -> Result<(), ValidationErrorKind> {
+        self.registry.validate_partial()?;
+        self.sync.validate_partial()
     }
 }
 
@@ -107,7 +342,10 @@ impl From<Registry> for RegistryWithSync {
 impl Registry {
     #[inline]
     pub(crate) fn get(&self, type_info: &TypeInfo) -> Option<&InstantiatorData> {
-        self.entries.get(type_info)
+        match &self.frozen {
+            Some(frozen) => frozen.get(type_info),
+            None => self.entries.get(type_info),
+        }
     }
 
     #[inline]
@@ -115,11 +353,35 @@ impl Registry {
         ScopeDataWithChildScopesData::new_with_sort(self.scopes_data.clone())
     }
 
-    pub(crate) fn validate(&self) -> Result<(), ValidationErrorKind> {
+    fn provider_infos(&self) -> impl Iterator<Item = ProviderInfo<'_>> {
+        // The built-in container entry isn't part of the wiring, as in the lints
+        let container_type_info = TypeInfo::of::<Container>();
+        self.entries
+            .iter()
+            .filter(move |(type_info, _)| **type_info != container_type_info)
+            .map(|(type_info, data)| ProviderInfo {
+                type_info,
+                dependencies: &data.dependencies,
+                scope_data: &data.scope_data,
+                is_async: true,
+                has_finalizer: data.finalizer.is_some(),
+                cache_provides: data.config.cache_provides,
+            })
+    }
+
+    pub(crate) fn validate_partial(&self) -> Result<(), ValidationErrorKind> {
+        detect_duplicate_providers(&self.duplicates)?;
         self.detect_cyclic_dependencies()?;
         self.detect_unreachable_scopes()
     }
 
+    fn detect_missing_dependencies(&self, is_provided: impl Fn(&TypeInfo) -> bool) -> Result<(), ValidationErrorKind> {
+        detect_missing_dependencies(
+            self.entries.iter().map(|(type_info, data)| (type_info, &data.dependencies)),
+            is_provided,
+        )
+    }
+
     fn detect_cyclic_dependencies(&self) -> Result<(), ValidationErrorKind> {
         let mut visited = BTreeSet::new();
         let mut stack = Vec::new();
@@ -135,6 +397,7 @@ impl Registry {
     }
 
     fn detect_unreachable_scopes(&self) -> Result<(), ValidationErrorKind> {
+        let scopes = self.get_scope_with_child_scopes();
         for (
             type_info,
             InstantiatorData {
@@ -142,18 +405,18 @@ impl Registry {
             },
         ) in &self.entries
         {
-            for Dependency { type_info: dependency } in dependencies {
+            for Dependency { type_info: dependency, .. } in dependencies {
                 if let Some(InstantiatorData {
                     scope_data: dependency_scope,
                     ..
                 }) = self.entries.get(dependency)
                 {
-                    if dependency_scope.priority > scope_data.priority {
+                    if !scopes.is_ancestor_or_self(dependency_scope, scope_data) {
                         return Err(ValidationErrorKind::UnreachableDependency {
                             dependent: type_info.clone(),
-                            dependent_scope: *scope_data,
+                            dependent_scope: Box::new(scope_data.clone()),
                             dependency: dependency.clone(),
-                            dependency_scope: *dependency_scope,
+                            dependency_scope: Box::new(dependency_scope.clone()),
                         });
                     }
                 }
@@ -177,7 +440,8 @@ impl Registry {
         }
         stack.push(type_info.clone());
 
-        for Dependency { type_info } in dependencies {
+        // Weak dependencies aren't instantiated, so cycles through them are fine
+        for Dependency { type_info, .. } in dependencies.iter().filter(|dependency| !dependency.is_weak) {
             if let Some(InstantiatorData { dependencies, .. }) = self.entries.get(type_info) {
                 if self.dfs_visit(type_info, dependencies, visited, stack) {
                     return true;
@@ -207,6 +471,9 @@ impl Registry {
 /// provide(inst, finalizer = fin, config = Config::default()) // order doesn’t matter
 /// ```
 ///
+/// `provide_default(...)` and `override(...)` accept the same forms and mark the registration
+/// as a replaceable default or as an intentional replacement, see [`crate::ConflictPolicy`].
+///
 /// Parameters:
 /// - `config` *(optional)* — configuration object.
 /// - `finalizer` *(optional)* — asynchronous function called when the dependency is finalized.
@@ -338,12 +605,12 @@ macro_rules! async_registry {
             $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { scope($scope) [ $($entries)+ ] })),
             $crate::async_registry_internal! { $($rest)+ }
         );
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
     (scope($scope:expr $(,)?) [ $($entries:tt)+ ] $(,)?) => {{
         let registry = $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { scope($scope) [ $($entries)+ ] }));
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
     (provide($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
@@ -351,12 +618,38 @@ macro_rules! async_registry {
             $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { provide($scope, $($entry)+) })),
             $crate::async_registry_internal! { $($rest)+ }
         );
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
     (provide($scope:expr, $($entry:tt)+) $(,)?) => {{
         let registry = $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { provide($scope, $($entry)+) }));
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (provide_default($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        let registry = $crate::utils::Merge::merge(
+            $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { provide_default($scope, $($entry)+) })),
+            $crate::async_registry_internal! { $($rest)+ }
+        );
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (provide_default($scope:expr, $($entry:tt)+) $(,)?) => {{
+        let registry = $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { provide_default($scope, $($entry)+) }));
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (override($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        let registry = $crate::utils::Merge::merge(
+            $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { override($scope, $($entry)+) })),
+            $crate::async_registry_internal! { $($rest)+ }
+        );
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (override($scope:expr, $($entry:tt)+) $(,)?) => {{
+        let registry = $crate::macros_utils::async_impl::build_registry(($scope, $crate::async_registry_internal! { override($scope, $($entry)+) }));
+        registry.validate_partial().unwrap();
         registry
     }};
     (extend($($registries:expr),+ $(,)?) $(,)?) => {{
@@ -367,7 +660,7 @@ macro_rules! async_registry {
         $(
             registry = $crate::utils::Merge::merge(registry, $registries);
         )+
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
 
@@ -447,6 +740,24 @@ macro_rules! async_registry_internal {
     (provide($scope:expr, $($entry:tt)+) $(,)?) => {{
         $crate::async_registry_internal! { @entries_with_scope provide($scope, $($entry)*) }
     }};
+    (provide_default($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        $crate::macros_utils::aliases::hlist![
+            $crate::async_registry_internal! { @entries_with_scope provide_default($scope, $($entry)+) },
+            $crate::async_registry_internal! { $($rest)+ }
+        ]
+    }};
+    (provide_default($scope:expr, $($entry:tt)+) $(,)?) => {{
+        $crate::async_registry_internal! { @entries_with_scope provide_default($scope, $($entry)+) }
+    }};
+    (override($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        $crate::macros_utils::aliases::hlist![
+            $crate::async_registry_internal! { @entries_with_scope override($scope, $($entry)+) },
+            $crate::async_registry_internal! { $($rest)+ }
+        ]
+    }};
+    (override($scope:expr, $($entry:tt)+) $(,)?) => {{
+        $crate::async_registry_internal! { @entries_with_scope override($scope, $($entry)+) }
+    }};
     (extend($($registries:expr),+ $(,)?) $(,)?) => {{
         let mut registry_kind = $crate::macros_utils::types::RegistryKind::AsyncWithSync(Default::default());
         $(
@@ -455,12 +766,32 @@ macro_rules! async_registry_internal {
         $crate::macros_utils::types::RegistryKindOrEntry::Kind(registry_kind)
     }};
 
-    (@entries_with_scope $( provide($scope:expr, $($entry:tt)+) ),+ $(,)?) => {{
-        $crate::macros_utils::aliases::hlist![$( $crate::async_registry_internal! { @entry scope($scope), $($entry)+ } ),+]
+    (@entries_with_scope $( $kind:ident($scope:expr, $($entry:tt)+) ),+ $(,)?) => {{
+        $crate::macros_utils::aliases::hlist![$( $crate::async_registry_internal! { @marked $kind scope($scope), $($entry)+ } ),+]
     }};
-    (@entries_in_scope scope($scope:expr) [ $( provide($($entry:tt)+) ),+ $(,)? ]) => {{
-        $crate::macros_utils::aliases::hlist![$( $crate::async_registry_internal! { @entry scope($scope), $($entry)+ } ),+]
+    (@entries_in_scope scope($scope:expr) [ $( $kind:ident($($entry:tt)+) ),+ $(,)? ]) => {{
+        $crate::macros_utils::aliases::hlist![$( $crate::async_registry_internal! { @marked $kind scope($scope), $($entry)+ } ),+]
+    }};
+    (@marked provide scope($scope:expr), $($entry:tt)+) => {{
+        $crate::async_registry_internal! { @entry scope($scope), $($entry)+ }
+    }};
+    (@marked provide_default scope($scope:expr), $($entry:tt)+) => {{
+        $crate::macros_utils::async_impl::with_kind(
+            $crate::async_registry_internal! { @entry scope($scope), $($entry)+ },
+            $crate::macros_utils::types::ProvideKind::Default,
+        )
+    }};
+    (@marked override scope($scope:expr), $($entry:tt)+) => {{
+        $crate::macros_utils::async_impl::with_kind(
+            $crate::async_registry_internal! { @entry scope($scope), $($entry)+ },
+            $crate::macros_utils::types::ProvideKind::Override,
+        )
     }};
+    (@marked $kind:ident scope($scope:expr), $($entry:tt)+) => {
+        compile_error!(concat!(
+            "Unknown entry `", stringify!($kind), "(...)`. Expected `provide(...)`, `provide_default(...)` or `override(...)`"
+        ))
+    };
     (@entry scope($scope:expr), $inst:expr $(,)?) => {{
         $crate::macros_utils::types::RegistryKindOrEntry::Entry(
             $crate::macros_utils::async_impl::make_entry($scope, $inst, None, None::<$crate::macros_utils::async_impl::FinDummy<_>>)
@@ -575,9 +906,10 @@ mod tests {
     use crate::{
         any::TypeInfo,
         async_impl::{registry::RegistryWithSync, Container},
+        errors::ValidationErrorKind,
         registry,
-        utils::thread_safety::RcThreadSafety,
-        Config, DefaultScope, Inject, InjectTransient, InstantiateErrorKind,
+        utils::{thread_safety::RcThreadSafety, Merge as _},
+        Config, ConflictPolicy, DefaultScope, Inject, InjectTransient, InstantiateErrorKind,
     };
 
     async fn inst_a() -> Result<(), InstantiateErrorKind> {
@@ -875,7 +1207,7 @@ mod tests {
                 provide(async |InjectTransient(b): InjectTransient<B>, InjectTransient(a): InjectTransient<A>| Ok(C(b, a))),
             ],
         };
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
 
         assert_eq!(registry.entries.len(), 4);
         assert_eq!(sync.entries.len(), 1);
@@ -928,6 +1260,86 @@ mod tests {
         };
     }
 
+    #[test]
+    #[traced_test]
+    fn test_registry_validate_detects_missing_dependency() {
+        struct Registered;
+        struct Unregistered;
+
+        let registry = async_registry! {
+            scope(DefaultScope::App) [
+                provide(async |Inject(_): Inject<Unregistered>| Ok(Registered)),
+            ],
+        };
+
+        match registry.validate() {
+            Err(ValidationErrorKind::MissingDependency { dependent, dependency }) => {
+                assert_eq!(dependent, TypeInfo::of::<Registered>());
+                assert_eq!(dependency, TypeInfo::of::<Unregistered>());
+            }
+            other => panic!("expected MissingDependency, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_validate_detects_duplicate_provider() {
+        let registry = async_registry! { provide(DefaultScope::App, inst_a) }
+            .with_conflict_policy(ConflictPolicy::Error)
+            .merge(async_registry! { provide(DefaultScope::Session, inst_a) });
+
+        assert!(matches!(registry.validate(), Err(ValidationErrorKind::DuplicateProvider { .. })));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_merge_adopts_conflict_policy_before_entries() {
+        let registry = async_registry! { provide(DefaultScope::App, inst_a) }
+            .merge(async_registry! { provide(DefaultScope::Session, inst_a) }.with_conflict_policy(ConflictPolicy::Error));
+
+        assert!(matches!(registry.validate(), Err(ValidationErrorKind::DuplicateProvider { .. })));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_conflict_policy_keep_first() {
+        let RegistryWithSync { registry, .. } = async_registry!()
+            .with_conflict_policy(ConflictPolicy::KeepFirst)
+            .merge(async_registry! { provide(DefaultScope::App, inst_a) })
+            .merge(async_registry! { provide(DefaultScope::Session, inst_a) });
+
+        assert_eq!(registry.get(&TypeInfo::of::<()>()).unwrap().scope_data, DefaultScope::App.into());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_validate_allows_sync_external_and_container_dependencies() {
+        struct FromSync;
+        struct FromContext;
+        struct Registered;
+
+        let registry = async_registry! {
+            scope(DefaultScope::App) [
+                provide(async |Inject(_): Inject<FromSync>, Inject(_): Inject<FromContext>, InjectTransient(_): InjectTransient<Container>| {
+                    Ok(Registered)
+                }),
+            ],
+            extend(registry! {
+                provide(DefaultScope::App, || Ok(FromSync)),
+            }),
+        };
+        match registry.validate() {
+            Err(ValidationErrorKind::MissingDependency { dependent, dependency }) => {
+                assert_eq!(dependent, TypeInfo::of::<Registered>());
+                assert_eq!(dependency, TypeInfo::of::<FromContext>());
+                assert!(dependency.name.ends_with("FromContext"));
+            }
+            other => panic!("expected MissingDependency, got: {other:?}"),
+        }
+
+        registry.with_external::<FromContext>().validate().unwrap();
+    }
+
     #[test]
     #[traced_test]
     fn test_registry_extend_entries() {
@@ -1233,7 +1645,7 @@ mod tests {
                     provide(
                         DefaultScope::App,
                         inst_a,
-                        config = Config { cache_provides: false },
+                        config = Config::default().cache_provides(false),
                     ),
                 },
                 async_registry! {
@@ -1259,7 +1671,7 @@ mod tests {
                     provide(
                         DefaultScope::App,
                         inst_a,
-                        config = Config { cache_provides: false },
+                        config = Config::default().cache_provides(false),
                     ),
                 },
                 async_registry! {
@@ -1269,7 +1681,7 @@ mod tests {
                     provide(
                         DefaultScope::Request,
                         inst_a,
-                        config = Config { cache_provides: false },
+                        config = Config::default().cache_provides(false),
                     ),
                 },
             ),
@@ -1290,7 +1702,7 @@ mod tests {
             provide(
                 DefaultScope::App,
                 inst_a,
-                config = Config { cache_provides: false },
+                config = Config::default().cache_provides(false),
             ),
             extend(
                 async_registry! {
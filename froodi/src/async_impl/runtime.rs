@@ -0,0 +1,216 @@
+//! Async primitives of the async container: locks serializing instantiation of a type,
+//! timers of timeouts and retry delays, and tasks refreshing expired instances in the background.
+//!
+//! The backend is chosen by the cargo features:
+//! - `tokio`, falling back to the timers of `futures-timer` outside of a Tokio runtime.
+//!   Tasks are spawned on the current runtime, or refreshed on access as with `async-lock`
+//!   outside of a runtime or if the `thread_safe` feature is disabled.
+//! - `async-lock`, used if `tokio` isn't enabled, so it's the backend of the `async` feature alone.
+//!   It works with any executor: locks of `async-lock` and timers of `futures-timer`.
+//!   Tasks can't be spawned without knowing the executor, so [`crate::Refresh::Background`] instances are refreshed on access
+
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+    time::Duration,
+};
+
+use crate::utils::{future::BoxFuture, thread_safety::SendSafety};
+
+/// Async mutual exclusion lock of a backend
+pub trait AsyncMutexBackend<T> {
+    type Guard<'a>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self;
+    fn lock(&self) -> BoxFuture<'_, Self::Guard<'_>>;
+    /// Returns `None` if the lock is held
+    fn try_lock(&self) -> Option<Self::Guard<'_>>;
+}
+
+/// Timers and tasks of a backend
+pub trait RuntimeBackend {
+    fn sleep(duration: Duration) -> BoxFuture<'static, ()>;
+    /// Runs the task in the background, or returns it back if the backend can't spawn tasks
+    fn spawn(task: BoxFuture<'static, ()>) -> Option<BoxFuture<'static, ()>>;
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_backend {
+    use alloc::boxed::Box;
+    use core::time::Duration;
+
+    use super::{AsyncMutexBackend, RuntimeBackend};
+    use crate::utils::{future::BoxFuture, thread_safety::SendSafety};
+
+    impl<T: SendSafety> AsyncMutexBackend<T> for tokio::sync::Mutex<T> {
+        type Guard<'a>
+            = tokio::sync::MutexGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn lock(&self) -> BoxFuture<'_, Self::Guard<'_>> {
+            Box::pin(Self::lock(self))
+        }
+
+        #[inline]
+        fn try_lock(&self) -> Option<Self::Guard<'_>> {
+            Self::try_lock(self).ok()
+        }
+    }
+
+    /// Runtime of Tokio
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Tokio;
+
+    impl RuntimeBackend for Tokio {
+        /// Sleeps with the timer of the current Tokio runtime, or with `futures-timer` outside of one.
+        /// The runtime must have its timer enabled.
+        #[inline]
+        fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+            if tokio::runtime::Handle::try_current().is_ok() {
+                Box::pin(tokio::time::sleep(duration))
+            } else {
+                Box::pin(futures_timer::Delay::new(duration))
+            }
+        }
+
+        /// Spawns on the current Tokio runtime, or returns the task back outside of one.
+        ///
+        /// If the `thread_safe` feature is disabled, the task isn't `Send` and could only be spawned on a `LocalSet`,
+        /// which can't be detected, so it's always returned back.
+        #[inline]
+        fn spawn(task: BoxFuture<'static, ()>) -> Option<BoxFuture<'static, ()>> {
+            #[cfg(feature = "thread_safe")]
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.spawn(task);
+                return None;
+            }
+            Some(task)
+        }
+    }
+}
+
+mod async_lock_backend {
+    use alloc::boxed::Box;
+    use core::time::Duration;
+
+    use super::{AsyncMutexBackend, RuntimeBackend};
+    use crate::utils::{future::BoxFuture, thread_safety::SendSafety};
+
+    impl<T: SendSafety> AsyncMutexBackend<T> for async_lock::Mutex<T> {
+        type Guard<'a>
+            = async_lock::MutexGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn lock(&self) -> BoxFuture<'_, Self::Guard<'_>> {
+            Box::pin(Self::lock(self))
+        }
+
+        #[inline]
+        fn try_lock(&self) -> Option<Self::Guard<'_>> {
+            Self::try_lock(self)
+        }
+    }
+
+    /// Runtime of any executor, which can't spawn tasks
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Agnostic;
+
+    impl RuntimeBackend for Agnostic {
+        #[inline]
+        fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+            Box::pin(futures_timer::Delay::new(duration))
+        }
+
+        #[inline]
+        fn spawn(task: BoxFuture<'static, ()>) -> Option<BoxFuture<'static, ()>> {
+            Some(task)
+        }
+    }
+}
+
+pub use async_lock_backend::Agnostic;
+#[cfg(feature = "tokio")]
+pub use tokio_backend::Tokio;
+
+#[cfg(feature = "tokio")]
+mod selected {
+    pub(crate) type Mutex<T> = tokio::sync::Mutex<T>;
+    pub(crate) type Runtime = super::Tokio;
+}
+
+#[cfg(not(feature = "tokio"))]
+mod selected {
+    pub(crate) type Mutex<T> = async_lock::Mutex<T>;
+    pub(crate) type Runtime = super::Agnostic;
+}
+
+pub(crate) type AsyncMutexGuard<'a, T> = <selected::Mutex<T> as AsyncMutexBackend<T>>::Guard<'a>;
+
+/// Async mutual exclusion lock of the selected backend
+pub(crate) struct AsyncMutex<T>(selected::Mutex<T>);
+
+impl<T: SendSafety> AsyncMutex<T> {
+    #[inline]
+    #[must_use]
+    pub(crate) fn new(value: T) -> Self {
+        Self(AsyncMutexBackend::new(value))
+    }
+
+    #[inline]
+    pub(crate) async fn lock(&self) -> AsyncMutexGuard<'_, T> {
+        AsyncMutexBackend::lock(&self.0).await
+    }
+
+    #[inline]
+    pub(crate) fn try_lock(&self) -> Option<AsyncMutexGuard<'_, T>> {
+        AsyncMutexBackend::try_lock(&self.0)
+    }
+}
+
+impl<T: SendSafety + Default> Default for AsyncMutex<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[inline]
+pub(crate) fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    <selected::Runtime as RuntimeBackend>::sleep(duration)
+}
+
+/// See [`RuntimeBackend::spawn`]
+#[inline]
+pub(crate) fn spawn(task: BoxFuture<'static, ()>) -> Option<BoxFuture<'static, ()>> {
+    <selected::Runtime as RuntimeBackend>::spawn(task)
+}
+
+/// Waits for the future, or returns `Err(())` if it isn't ready after the duration
+pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, ()> {
+    let mut future = pin!(future);
+    let mut sleep = sleep(duration);
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        sleep.as_mut().poll(cx).map(Err)
+    })
+    .await
+}
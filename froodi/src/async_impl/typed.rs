@@ -0,0 +1,185 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    marker::PhantomData,
+};
+
+use super::{Container, RegistryWithSync};
+use crate::{
+    typed::{Below, NextScope, Scoped, TypedScope, Within},
+    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
+    Context, ResolveErrorKind, ScopeWithErrorKind,
+};
+
+/// Wrapper of an async [`Container`] of the scope `S`, see [`crate::TypedContainer`]
+pub struct TypedContainer<S> {
+    container: Container,
+    scope: PhantomData<fn() -> S>,
+}
+
+impl<S: TypedScope> TypedContainer<S> {
+    /// Creates a container starting at the scope `S`, see [`Container::new_with_start_scope`]
+    ///
+    /// # Panics
+    /// See [`Container::new_with_start_scope`].
+    #[inline]
+    #[must_use]
+    pub fn new(registry: RegistryWithSync) -> Self
+    where
+        S::Scope: Clone,
+    {
+        Self::from_container(Container::new_with_start_scope(registry, S::scope()))
+    }
+
+    #[inline]
+    #[must_use]
+    fn from_container(container: Container) -> Self {
+        Self {
+            container,
+            scope: PhantomData,
+        }
+    }
+
+    /// Enters the scope `C`, a descendant of `S`
+    ///
+    /// # Errors
+    /// Returns [`ScopeWithErrorKind`] if the scope isn't in the registry.
+    #[inline]
+    pub fn enter<C: Below<S>>(self) -> Result<TypedContainer<C>, ScopeWithErrorKind> {
+        self.container
+            .enter()
+            .with_scope(C::scope())
+            .build()
+            .map(TypedContainer::from_container)
+    }
+
+    /// Enters the scope `C`, a descendant of `S`, with the context
+    ///
+    /// # Errors
+    /// Returns [`ScopeWithErrorKind`] if the scope isn't in the registry.
+    #[inline]
+    pub fn enter_with_context<C: Below<S>>(self, context: Context) -> Result<TypedContainer<C>, ScopeWithErrorKind> {
+        self.container
+            .enter()
+            .with_scope(C::scope())
+            .with_context(context)
+            .build()
+            .map(TypedContainer::from_container)
+    }
+
+    /// Enters the next scope not skipped by default, like [`Container::enter_build`]
+    ///
+    /// # Errors
+    /// Returns [`ScopeWithErrorKind`] if the scope isn't in the registry.
+    #[inline]
+    pub fn enter_build(self) -> Result<TypedContainer<S::Next>, ScopeWithErrorKind>
+    where
+        S: NextScope,
+    {
+        self.enter()
+    }
+
+    /// Gets a scoped dependency, see [`Container::get`]
+    #[inline]
+    #[allow(clippy::missing_errors_doc)]
+    pub fn get<Dep>(&self) -> impl Future<Output = Result<RcThreadSafety<Dep>, ResolveErrorKind>> + SendSafety + '_
+    where
+        Dep: Scoped + SendSafety + SyncSafety + 'static,
+        S: Within<Dep::Scope>,
+    {
+        self.container.get()
+    }
+
+    /// Creates a fresh dependency, see [`Container::get_transient`]
+    #[inline]
+    #[allow(clippy::missing_errors_doc)]
+    pub fn get_transient<Dep>(&self) -> impl Future<Output = Result<Dep, ResolveErrorKind>> + SendSafety + '_
+    where
+        Dep: Scoped + 'static,
+        S: Within<Dep::Scope>,
+    {
+        self.container.get_transient()
+    }
+
+    /// See [`Container::close`]
+    #[inline]
+    pub fn close(&self) -> impl Future<Output = ()> + SendSafety + '_ {
+        self.container.close()
+    }
+
+    /// Dynamic container, for dependencies that don't implement [`Scoped`]
+    #[inline]
+    #[must_use]
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Container {
+        self.container
+    }
+}
+
+impl<S> Clone for TypedContainer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            container: self.container.clone(),
+            scope: PhantomData,
+        }
+    }
+}
+
+impl<S> Debug for TypedContainer<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.container, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::TypedContainer;
+    use crate::{
+        async_registry,
+        typed::{App, Request, Scoped},
+        DefaultScope, Inject,
+    };
+
+    use alloc::{
+        format,
+        string::{String, ToString as _},
+    };
+    use tracing_test::traced_test;
+
+    struct AppValue;
+    struct RequestValue;
+
+    impl Scoped for AppValue {
+        type Scope = App;
+    }
+    impl Scoped for RequestValue {
+        type Scope = Request;
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_typed_container() {
+        let app = TypedContainer::<App>::new(async_registry! {
+            scope(DefaultScope::App) [
+                provide(async || Ok(AppValue)),
+            ],
+            scope(DefaultScope::Request) [
+                provide(async |Inject(_): Inject<AppValue>| Ok(RequestValue)),
+            ],
+        });
+        app.get::<AppValue>().await.unwrap();
+
+        let request: TypedContainer<Request> = app.enter_build().unwrap();
+        request.get::<RequestValue>().await.unwrap();
+        request.get_transient::<RequestValue>().await.unwrap();
+        request.get::<AppValue>().await.unwrap();
+        request.close().await;
+    }
+}
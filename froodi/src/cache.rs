@@ -1,25 +1,115 @@
-use alloc::collections::vec_deque::VecDeque;
-use core::mem;
+use alloc::{
+    collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+    vec::Vec,
+};
+use core::{mem, time::Duration};
 
 use crate::{
-    any::{Map, TypeInfo},
-    utils::thread_safety::{RcAnyThreadSafety, RcThreadSafety, SendSafety, SyncSafety},
-    Context,
+    any::TypeInfo,
+    slot::{SlotIndex, SlotMap},
+    utils::{
+        lock::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
+        thread_safety::{RcAnyThreadSafety, RcThreadSafety, SendSafety, SyncSafety, WeakAnyThreadSafety, WeakThreadSafety},
+    },
+    Context, ResolveErrorKind,
 };
 
+/// Cache of a container behind its lock
+pub(crate) struct SharedCache {
+    cache: RwLock<Cache>,
+}
+
+impl SharedCache {
+    #[inline]
+    #[must_use]
+    pub(crate) fn new(cache: Cache) -> Self {
+        Self { cache: RwLock::new(cache) }
+    }
+
+    #[inline]
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Cache> {
+        self.cache.read()
+    }
+
+    /// Locks the cache for writing
+    #[inline]
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, Cache> {
+        self.cache.write()
+    }
+
+    /// Locks the cache for reading while resolving the type.
+    ///
+    /// # Errors
+    /// Returns [`ResolveErrorKind::CacheBorrowed`] if the single-threaded cache is already borrowed for writing,
+    /// see [`crate::utils::lock::RwLockBackend::read_checked`]
+    #[inline]
+    pub(crate) fn try_read(&self, type_info: &TypeInfo) -> Result<RwLockReadGuard<'_, Cache>, ResolveErrorKind> {
+        self.cache.read_checked().ok_or_else(|| ResolveErrorKind::CacheBorrowed {
+            type_info: type_info.clone(),
+        })
+    }
+
+    /// Like [`Self::write`], but see [`Self::try_read`]
+    #[inline]
+    pub(crate) fn try_write(&self, type_info: &TypeInfo) -> Result<RwLockWriteGuard<'_, Cache>, ResolveErrorKind> {
+        self.cache.write_checked().ok_or_else(|| ResolveErrorKind::CacheBorrowed {
+            type_info: type_info.clone(),
+        })
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut Cache {
+        self.cache.get_mut()
+    }
+
+    /// Like [`Cache::get_fresh`], but locks the cache for reading
+    ///
+    /// # Errors
+    /// See [`Self::try_read`]
+    #[inline]
+    pub(crate) fn get_fresh<T: SendSafety + SyncSafety + 'static>(
+        &self,
+        type_info: &TypeInfo,
+        now: impl FnOnce() -> Option<Duration>,
+    ) -> Result<Option<RcThreadSafety<T>>, ResolveErrorKind> {
+        Ok(self.try_read(type_info)?.get_fresh(type_info, now))
+    }
+
+    /// See [`Cache::reset`]
+    #[inline]
+    pub(crate) fn reset(&self, context: &Context) {
+        self.cache.write().reset(context);
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct Cache {
-    pub(crate) map: Map,
+    pub(crate) map: SlotMap,
+    /// Times the cached values with a TTL expire at, see [`crate::Clock`]
+    pub(crate) expirations: BTreeMap<TypeInfo, Duration>,
     pub(crate) resolved: ResolvedSet,
+    /// Weak references waiting for the values of the types to be cached, see [`crate::InjectWeak`]
+    pending_weak: BTreeMap<TypeInfo, Vec<WeakAnyThreadSafety>>,
 }
 
 impl Cache {
+    #[cfg(test)]
     #[inline]
     #[must_use]
     pub fn new() -> Self {
+        Self::with_index(RcThreadSafety::default())
+    }
+
+    /// Creates a cache storing the values of the types of `index` by slot, see [`crate::Registry::freeze`]
+    #[inline]
+    #[must_use]
+    pub(crate) fn with_index(index: RcThreadSafety<SlotIndex>) -> Self {
         Self {
-            map: Map::new(),
+            map: SlotMap::new(index),
+            expirations: BTreeMap::new(),
             resolved: ResolvedSet::new(),
+            pending_weak: BTreeMap::new(),
         }
     }
 
@@ -29,9 +119,35 @@ impl Cache {
         type_info: TypeInfo,
         value: RcThreadSafety<T>,
     ) -> Option<RcThreadSafety<T>> {
+        for weak in self.pending_weak.remove(&type_info).into_iter().flatten() {
+            if let Some(weak) = weak.upgrade() {
+                if let Some(weak) = weak.downcast_ref::<Mutex<WeakThreadSafety<T>>>() {
+                    *weak.lock() = RcThreadSafety::downgrade(&value);
+                }
+            }
+        }
         self.map.insert(type_info, value).and_then(|boxed| boxed.downcast().ok())
     }
 
+    /// Points the weak reference at the cached value, or at the value cached next if there is none.
+    /// Returns whether the value is cached
+    pub(crate) fn watch_weak<T: SendSafety + SyncSafety + 'static>(
+        &mut self,
+        type_info: TypeInfo,
+        weak: &RcThreadSafety<Mutex<WeakThreadSafety<T>>>,
+    ) -> bool {
+        if let Some(value) = self.get::<T>(&type_info) {
+            *weak.lock() = RcThreadSafety::downgrade(&value);
+            return true;
+        }
+        let weak: RcAnyThreadSafety = weak.clone();
+        let pending = self.pending_weak.entry(type_info).or_default();
+        // References dropped before the value is cached aren't kept
+        pending.retain(|weak| weak.strong_count() > 0);
+        pending.push(RcThreadSafety::downgrade(&weak));
+        false
+    }
+
     #[inline]
     pub(crate) fn extend_context(&mut self, context: &Context) {
         self.map
@@ -43,7 +159,9 @@ impl Cache {
     pub(crate) fn child(&self) -> Self {
         Self {
             map: self.map.clone(),
+            expirations: self.expirations.clone(),
             resolved: ResolvedSet::new(),
+            pending_weak: BTreeMap::new(),
         }
     }
 
@@ -52,6 +170,53 @@ impl Cache {
         self.map.get(type_info).and_then(|boxed| boxed.clone().downcast().ok())
     }
 
+    /// Like [`Self::get`], but `None` if the value is expired at the time returned by `now`
+    #[must_use]
+    pub(crate) fn get_fresh<T: SendSafety + SyncSafety + 'static>(
+        &self,
+        type_info: &TypeInfo,
+        now: impl FnOnce() -> Option<Duration>,
+    ) -> Option<RcThreadSafety<T>> {
+        if self.is_expired(type_info, now) {
+            return None;
+        }
+        self.get(type_info)
+    }
+
+    /// Whether a value is cached and not expired at the time returned by `now`, see [`Self::get_fresh`]
+    #[must_use]
+    pub(crate) fn contains_fresh(&self, type_info: &TypeInfo, now: impl FnOnce() -> Option<Duration>) -> bool {
+        self.map.contains_key(type_info) && !self.is_expired(type_info, now)
+    }
+
+    fn is_expired(&self, type_info: &TypeInfo, now: impl FnOnce() -> Option<Duration>) -> bool {
+        self.expirations
+            .get(type_info)
+            .is_some_and(|expires_at| now().is_some_and(|now| now >= *expires_at))
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn expiration(&self, type_info: &TypeInfo) -> Option<Duration> {
+        self.expirations.get(type_info).copied()
+    }
+
+    #[inline]
+    pub(crate) fn set_expiration(&mut self, type_info: TypeInfo, expires_at: Option<Duration>) {
+        match expires_at {
+            Some(expires_at) => self.expirations.insert(type_info, expires_at),
+            None => self.expirations.remove(&type_info),
+        };
+    }
+
+    /// Clears the cache, leaving only the values of the context as in a new container
+    #[inline]
+    pub(crate) fn reset(&mut self, context: &Context) {
+        self.map.reset(&context.map);
+        self.expirations.clear();
+        self.pending_weak.clear();
+    }
+
     #[inline]
     pub(crate) fn push_resolved(&mut self, resolved: Resolved) {
         self.resolved.push(resolved);
@@ -81,12 +246,22 @@ impl ResolvedSet {
     pub(crate) fn push(&mut self, resolved: Resolved) {
         self.0.push_back(resolved);
     }
+
+    /// Removes the entry of the `dependency` instance, so its finalizer isn't called on close
+    pub(crate) fn remove<T: ?Sized>(&mut self, dependency: &RcThreadSafety<T>) -> Option<Resolved> {
+        let ptr = RcThreadSafety::as_ptr(dependency).cast::<()>();
+        let index = self
+            .0
+            .iter()
+            .position(|resolved| RcThreadSafety::as_ptr(&resolved.dependency).cast::<()>() == ptr)?;
+        self.0.remove(index)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     extern crate std;
-    use super::{Cache, Resolved};
+    use super::{Cache, Resolved, SharedCache};
     use crate::{any::TypeInfo, utils::thread_safety::RcThreadSafety, Context};
 
     #[derive(Debug, PartialEq, Eq)]
@@ -180,4 +355,25 @@ mod tests {
         let taken_again = cache.take_resolved_set();
         assert_eq!(taken_again.0.len(), 0);
     }
+
+    #[test]
+    fn shared_cache_get_fresh() {
+        use core::time::Duration;
+
+        let foo = TypeInfo::of::<Foo>();
+        let bar = TypeInfo::of::<Bar>();
+        let mut cache = Cache::new();
+        cache.insert_rc(foo.clone(), RcThreadSafety::new(Foo(1)));
+        cache.insert_rc(bar.clone(), RcThreadSafety::new(Bar(2)));
+        cache.set_expiration(bar.clone(), Some(Duration::from_secs(10)));
+        let shared = SharedCache::new(cache);
+        let now = || Some(Duration::ZERO);
+
+        assert_eq!(*shared.get_fresh::<Foo>(&foo, now).unwrap().unwrap(), Foo(1));
+        assert_eq!(*shared.get_fresh::<Bar>(&bar, now).unwrap().unwrap(), Bar(2));
+        assert!(shared.get_fresh::<Bar>(&bar, || Some(Duration::from_secs(10))).unwrap().is_none());
+
+        shared.reset(&Context::new());
+        assert!(shared.get_fresh::<Foo>(&foo, now).unwrap().is_none());
+    }
 }
@@ -0,0 +1,82 @@
+use core::time::Duration;
+
+use crate::utils::thread_safety::RcThreadSafety;
+
+/// Source of the current time for cached values with a TTL, see [`crate::Config`].
+///
+/// Only differences between values are used, so a clock may count from any point.
+/// Registries use [`SystemClock`] by default with the `std` feature, and values never expire without it unless a clock is set.
+/// A custom clock is useful in tests, to expire values without sleeping.
+pub trait Clock {
+    /// Current time, counted from any fixed point
+    fn now(&self) -> Duration;
+
+    /// Blocks the current thread for the duration, used by sync instantiators to wait before a retry without the `std` feature.
+    ///
+    /// Spins until [`Self::now`] passes the duration by default, so clocks that don't advance by themselves should override it
+    fn sleep(&self, duration: Duration) {
+        let until = self.now().saturating_add(duration);
+        while self.now() < until {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Clock of the system time, counted from the Unix epoch
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> Duration {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+#[cfg(feature = "thread_safe")]
+pub(crate) type RcClock = RcThreadSafety<dyn Clock + Send + Sync>;
+
+#[cfg(not(feature = "thread_safe"))]
+pub(crate) type RcClock = RcThreadSafety<dyn Clock>;
+
+/// Current time of the clock, or of [`SystemClock`] if it isn't set.
+/// `None` if there is no clock, so nothing expires.
+#[inline]
+#[must_use]
+pub(crate) fn now(clock: Option<&RcClock>) -> Option<Duration> {
+    match clock {
+        Some(clock) => Some(clock.now()),
+        #[cfg(feature = "std")]
+        None => Some(SystemClock.now()),
+        #[cfg(not(feature = "std"))]
+        None => None,
+    }
+}
+
+/// Blocks the current thread before a retry of a sync instantiator.
+/// Sleeps with the `std` feature, otherwise waits with [`Clock::sleep`] of the clock, and doesn't wait if there's no clock,
+/// see [`crate::LintWarning::RetryDelayWithoutClock`]
+#[inline]
+pub(crate) fn sleep(clock: Option<&RcClock>, duration: Duration) {
+    if duration.is_zero() {
+        return;
+    }
+    #[cfg(feature = "std")]
+    {
+        let _ = clock;
+        std::thread::sleep(duration);
+    }
+    #[cfg(not(feature = "std"))]
+    if let Some(clock) = clock {
+        clock.sleep(duration);
+    }
+}
@@ -1,3 +1,5 @@
+use core::time::Duration;
+
 /// Config for an instantiator
 /// ## Fields
 /// - `cache_provides`:
@@ -5,13 +7,169 @@
 ///
 ///   This does **not** affect the dependencies of the instance.
 ///   Only the final result is cached if caching is applicable.
+/// - `resolve_dependencies_concurrently`:
+///   If `true`, the dependencies of an async instantiator are resolved concurrently instead of one by one.
+///   Off by default.
+///
+///   On failure, the error of the first dependency to fail is returned, which isn't necessarily the first in argument order.
+///   The other dependencies aren't polled anymore and are dropped mid-instantiation, but the ones that were resolved
+///   before the failure keep their effects: their instances stay cached and their finalizers registered,
+///   while resolving one by one stops before instantiating the dependencies after the failed one.
+///   Dependencies that depend on each other are still resolved consistently, because instantiation of a type is serialized per type.
+///   Sync instantiators always resolve their dependencies one by one.
+/// - `timeout`:
+///   Limit of a single attempt to instantiate with an async instantiator, including resolving its dependencies.
+///   If all attempts time out, [`crate::ResolveErrorKind::Timeout`] is returned.
+///
+///   Sync instantiators ignore it. With the `tokio` feature, a Tokio runtime must have the time driver enabled.
+/// - `retry`:
+///   How many times to call the instantiator when it fails, see [`RetryPolicy`].
+///
+///   Only errors of the instantiator itself and timeouts are retried, errors of its dependencies are returned as is.
+///   Concurrent resolutions of the type wait for the retries, but aren't blocked while a sync instantiator waits
+///   before the next attempt, so one of them may make the next attempt instead.
+///   Without the `std` feature, sync instantiators wait with [`crate::Clock::sleep`] of the registry clock.
+///   If all attempts fail, [`crate::ResolveErrorKind::RetriesExhausted`] is returned with the error of the last one.
+/// - `ttl`:
+///   How long the cached instance stays valid. After it expires, the instantiator is called again and
+///   the finalizer, if any, is called for the replaced instance.
+///
+///   Time comes from the [`crate::Clock`] of the registry. Only used if `cache_provides` is `true`.
+/// - `refresh`:
+///   How an expired instance is replaced, see [`Refresh`].
+///
+/// The config is built from [`Config::default`] with a method per field, so new fields aren't breaking changes:
+/// ```rust
+/// use froodi::Config;
+///
+/// let config = Config::default().cache_provides(false);
+/// assert!(!config.cache_provides);
+/// ```
 #[derive(Clone, Copy)]
+#[non_exhaustive]
 pub struct Config {
     pub cache_provides: bool,
+    pub resolve_dependencies_concurrently: bool,
+    pub timeout: Option<Duration>,
+    pub retry: RetryPolicy,
+    pub ttl: Option<Duration>,
+    pub refresh: Refresh,
 }
 
 impl Default for Config {
     fn default() -> Self {
-        Self { cache_provides: true }
+        Self {
+            cache_provides: true,
+            resolve_dependencies_concurrently: false,
+            timeout: None,
+            retry: RetryPolicy::default(),
+            ttl: None,
+            refresh: Refresh::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Sets `cache_provides`, see [`Config`]
+    #[inline]
+    #[must_use]
+    pub const fn cache_provides(mut self, cache_provides: bool) -> Self {
+        self.cache_provides = cache_provides;
+        self
+    }
+
+    /// Sets `resolve_dependencies_concurrently`, see [`Config`]
+    #[inline]
+    #[must_use]
+    pub const fn resolve_dependencies_concurrently(mut self, resolve_dependencies_concurrently: bool) -> Self {
+        self.resolve_dependencies_concurrently = resolve_dependencies_concurrently;
+        self
+    }
+
+    /// Sets `timeout`, see [`Config`]
+    #[inline]
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets `retry`, see [`Config`]
+    #[inline]
+    #[must_use]
+    pub const fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets `ttl`, see [`Config`]
+    #[inline]
+    #[must_use]
+    pub const fn ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
     }
+
+    /// Sets `refresh`, see [`Config`]
+    #[inline]
+    #[must_use]
+    pub const fn refresh(mut self, refresh: Refresh) -> Self {
+        self.refresh = refresh;
+        self
+    }
+}
+
+/// Retry policy of an instantiator
+/// ## Fields
+/// - `max_attempts`:
+///   Maximum number of attempts, including the first one. `0` is treated as `1`.
+/// - `backoff`:
+///   Delay before the second attempt, doubled before each next one.
+///
+///   Sync instantiators sleep with the `std` feature and wait with [`crate::Clock::sleep`] of the registry clock otherwise,
+///   without a clock they don't wait, see [`crate::LintWarning::RetryDelayWithoutClock`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    #[inline]
+    #[must_use]
+    pub const fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self { max_attempts, backoff }
+    }
+
+    /// Delay before the attempt after `attempt`, numbered from 1
+    #[inline]
+    #[must_use]
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << (attempt - 1).min(31))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Duration::ZERO,
+        }
+    }
+}
+
+/// How an instance with an expired TTL is replaced
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Refresh {
+    /// The first access after expiration calls the instantiator and waits for it
+    #[default]
+    OnAccess,
+    /// Stale-while-revalidate: the first access after expiration returns the expired instance
+    /// and calls the instantiator in a spawned task, accesses get the new instance once it's ready.
+    ///
+    /// Only async containers refresh in the background, they spawn on the current Tokio runtime with the `tokio` feature
+    /// and the `thread_safe` feature. Sync containers, async containers of the executor-agnostic `async-lock` backend,
+    /// async containers outside of a Tokio runtime, and local factories of the `local_async` feature replace instances [`Refresh::OnAccess`].
+    Background,
 }
@@ -0,0 +1,125 @@
+use alloc::{collections::btree_map::BTreeMap, vec::Vec};
+use core::{
+    any::type_name,
+    fmt::{self, Display, Formatter},
+    panic::Location,
+};
+
+use crate::{any::TypeInfo, errors::ValidationErrorKind};
+
+/// Policy applied when a registry being merged registers a type that's already registered.
+///
+/// Registrations marked with `provide_default(...)` always yield to the other registration,
+/// and ones marked with `override(...)` always replace it, so the policy only applies
+/// to two plain `provide(...)` (or two `override(...)`) registrations of the same type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the last registration and report the conflict as [`crate::ValidationErrorKind::DuplicateProvider`],
+    /// so validation of the registry fails
+    Error,
+    /// Keep the first registration
+    KeepFirst,
+    /// Replace the registration with the last one
+    #[default]
+    Override,
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvideKind {
+    Provide,
+    Default,
+    Override,
+}
+
+/// Where a type was registered: the instantiator and the source location of its registration
+#[derive(Debug, Clone, Copy)]
+pub struct RegistrationSite {
+    pub instantiator: &'static str,
+    pub location: &'static Location<'static>,
+}
+
+impl RegistrationSite {
+    #[inline]
+    #[must_use]
+    #[track_caller]
+    pub(crate) fn of<Inst>() -> Self {
+        Self {
+            instantiator: type_name::<Inst>(),
+            location: Location::caller(),
+        }
+    }
+}
+
+impl Display for RegistrationSite {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` at {}", self.instantiator, self.location)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Registration {
+    pub(crate) kind: ProvideKind,
+    pub(crate) site: RegistrationSite,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DuplicateProvider {
+    pub(crate) type_info: TypeInfo,
+    pub(crate) first: RegistrationSite,
+    pub(crate) second: RegistrationSite,
+}
+
+pub(crate) trait Registered {
+    fn registration(&self) -> &Registration;
+}
+
+/// Inserts `value`, resolving a conflict with an already registered entry by the markers of both registrations
+/// and by `policy`. Conflicts the policy doesn't resolve are pushed to `duplicates`.
+pub(crate) fn insert_entry<D: Registered>(
+    entries: &mut BTreeMap<TypeInfo, D>,
+    duplicates: &mut Vec<DuplicateProvider>,
+    policy: ConflictPolicy,
+    key: TypeInfo,
+    value: D,
+) {
+    let Some(existing) = entries.get(&key) else {
+        entries.insert(key, value);
+        return;
+    };
+    let first = *existing.registration();
+    let second = *value.registration();
+
+    let replace = match (first.kind, second.kind) {
+        (ProvideKind::Default, _) => true,
+        (_, ProvideKind::Default) => false,
+        (ProvideKind::Provide, ProvideKind::Override) => true,
+        (ProvideKind::Override, ProvideKind::Provide) => false,
+        (ProvideKind::Provide, ProvideKind::Provide) | (ProvideKind::Override, ProvideKind::Override) => match policy {
+            ConflictPolicy::Error => {
+                duplicates.push(DuplicateProvider {
+                    type_info: key.clone(),
+                    first: first.site,
+                    second: second.site,
+                });
+                true
+            }
+            ConflictPolicy::KeepFirst => false,
+            ConflictPolicy::Override => true,
+        },
+    };
+    if replace {
+        entries.insert(key, value);
+    }
+}
+
+pub(crate) fn detect_duplicate_providers(duplicates: &[DuplicateProvider]) -> Result<(), ValidationErrorKind> {
+    match duplicates.first() {
+        Some(DuplicateProvider { type_info, first, second }) => Err(ValidationErrorKind::DuplicateProvider {
+            type_info: type_info.clone(),
+            first: *first,
+            second: *second,
+        }),
+        None => Ok(()),
+    }
+}
@@ -1,20 +1,26 @@
-use alloc::{boxed::Box, vec::Vec};
-use parking_lot::RwLock;
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+use core::fmt::{self, Debug, Formatter};
 #[cfg(feature = "thread_safe")]
 use tracing::trace;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
-use super::cache::Cache;
+use super::cache::{Cache, SharedCache};
 #[cfg(feature = "thread_safe")]
 use crate::lock::PerTypeLocks;
 use crate::{
     any::TypeInfo,
     cache::Resolved,
+    clock,
+    config::Config,
     context::Context,
-    errors::{InstantiatorErrorKind, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind},
+    errors::{InstantiateErrorKind, InstantiatorErrorKind, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind},
+    inject::InjectWeak,
+    instantiator::{BoxedCloneInstantiator, Instance},
+    plan::{PlanProblem, ResolutionPlan},
     registry::{InstantiatorData, Registry},
     scope::{Scope, ScopeData, ScopeDataWithChildScopesData},
     service::Service as _,
+    snapshot::{ContainerSnapshot, LevelSnapshot},
     utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
 };
 
@@ -57,7 +63,7 @@ impl Container {
     }
 
     /// Builds the root container chain: starts at the registry's lowest-priority scope and descends
-    /// (keeping each level as a parent with `close_parent = true`) until `is_target` accepts a scope.
+    /// the branch to the first scope accepted by `is_target` (keeping each level as a parent with `close_parent = true`).
     /// Shared by [`Self::new`] (target = first non-skipped scope) and [`Self::new_with_start_scope`]
     /// (target = the scope with the requested priority).
     ///
@@ -66,12 +72,12 @@ impl Container {
     /// every scope is skipped / none matches the requested priority).
     fn build_root(registry: Registry, is_target: impl Fn(&ScopeData) -> bool) -> Self {
         let mut scopes = registry.get_scope_with_child_scopes();
-        let registry = RcThreadSafety::new(registry);
+        let registry = RcThreadSafety::new(registry.freeze());
         let mut container = BoxedContainerInner {
-            cache: Cache::new(),
+            cache: Cache::with_index(registry.slot_index()),
             context: Context::new(),
             registry: registry.clone(),
-            scope_data: scopes.scope_data.expect("scopes len (is 0) should be > 0"),
+            scope_data: scopes.scope_data.clone().expect("scopes len (is 0) should be > 0"),
             child_scopes_data: scopes.child_scopes_data.clone(),
             parent: None,
             close_parent: false,
@@ -79,8 +85,8 @@ impl Container {
             per_type_locks: PerTypeLocks::default(),
         };
         while !is_target(&container.scope_data) {
-            scopes = scopes.child();
-            let scope_data = scopes.scope_data.expect("scope chain ended before reaching a target scope");
+            scopes = scopes.child(&is_target).expect("scope tree ended before reaching a target scope");
+            let scope_data = scopes.scope_data.clone().expect("child scope should have scope data");
             container = container.init_child(registry.clone(), scope_data, scopes.child_scopes_data.clone(), true);
         }
         container.into()
@@ -118,9 +124,11 @@ impl Container {
     pub fn get<Dep: SendSafety + SyncSafety + 'static>(&self) -> Result<RcThreadSafety<Dep>, ResolveErrorKind> {
         let type_info = TypeInfo::of::<Dep>();
         let dep_name = type_info.name;
-        let scope_name = self.inner.scope_data.name;
+        let scope_name = &*self.inner.scope_data.name;
 
-        if let Some(dependency) = { self.inner.cache.read().get(&type_info) } {
+        let now = || clock::now(self.inner.registry.clock.as_ref());
+
+        if let Some(dependency) = { self.inner.cache.get_fresh(&type_info, now)? } {
             debug!("Found in cache");
             return Ok(dependency);
         }
@@ -139,61 +147,79 @@ impl Container {
             return Err(err);
         };
 
-        let current_priority = self.inner.scope_data.priority;
-        let dep_priority = scope_data.priority;
-
-        if current_priority > dep_priority {
-            let mut parent = self
-                .inner
-                .parent
-                .as_ref()
-                .expect("parent should exist for lower-priority dependency");
-            while parent.inner.scope_data.priority != dep_priority {
-                parent = parent.inner.parent.as_ref().expect("parent with target priority should exist");
-            }
+        if scope_data.priority != self.inner.scope_data.priority {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
+                let err = ResolveErrorKind::NoAccessible {
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
+                };
+                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
+                return Err(err);
+            };
 
             return match parent.get::<Dep>() {
                 Ok(dependency) => {
                     if config.cache_provides {
-                        self.inner.cache.write().insert_rc(type_info, dependency.clone());
+                        let expires_at = parent.inner.cache.try_read(&type_info)?.expiration(&type_info);
+                        let mut cache = self.inner.cache.try_write(&type_info)?;
+                        cache.insert_rc(type_info.clone(), dependency.clone());
+                        cache.set_expiration(type_info, expires_at);
                     }
                     Ok(dependency)
                 }
                 Err(err) => Err(err),
             };
         }
-        if dep_priority > current_priority {
-            let err = ResolveErrorKind::NoAccessible {
-                expected_scope_data: *scope_data,
-                actual_scope_data: self.inner.scope_data,
-            };
-            error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
-            return Err(err);
-        }
 
-        #[cfg(feature = "thread_safe")]
-        trace!("Lock instantiator call");
         #[cfg(feature = "thread_safe")]
         let inst_call_lock = self.per_type_locks.get(type_info.id);
         #[cfg(feature = "thread_safe")]
-        let _guard = inst_call_lock.lock();
+        let mut _guard = None;
 
-        #[cfg(feature = "thread_safe")]
-        if let Some(dependency) = { self.inner.cache.read().get(&type_info) } {
-            debug!("Found in cache after lock");
-            return Ok(dependency);
-        }
+        // Same as `Self::instantiate`, but the lock isn't held while waiting before a retry,
+        // so resolutions of the type from other threads aren't blocked by the delay
+        let mut attempt = 1;
+        let result = loop {
+            #[cfg(feature = "thread_safe")]
+            {
+                trace!("Lock instantiator call");
+                _guard = Some(inst_call_lock.lock());
 
-        match instantiator.clone().call(self.clone()) {
-            Ok(dependency) => match dependency.downcast::<Dep>() {
+                if let Some(dependency) = { self.inner.cache.read().get_fresh(&type_info, now) } {
+                    debug!("Found in cache after lock");
+                    return Ok(dependency);
+                }
+            }
+
+            match instantiator.clone().call((self.clone(), true)) {
+                Err(InstantiatorErrorKind::Factory(err)) if attempt < config.retry.max_attempts => {
+                    warn!(attempt, error = %err, "Failed to instantiate, retrying");
+                    #[cfg(feature = "thread_safe")]
+                    {
+                        _guard = None;
+                    }
+                    clock::sleep(self.inner.registry.clock.as_ref(), config.retry.delay(attempt));
+                    attempt += 1;
+                }
+                result => break result,
+            }
+        };
+
+        match result {
+            Ok(dependency) => match dependency.downcast_shared::<Dep>() {
                 Ok(dependency) => {
-                    let dependency = RcThreadSafety::new(*dependency);
                     let cache_provides = config.cache_provides;
                     let has_finalizer = finalizer.is_some();
+                    let mut replaced = None;
                     if cache_provides || has_finalizer {
-                        let mut cache = self.inner.cache.write();
+                        let mut cache = self.inner.cache.try_write(&type_info)?;
                         if cache_provides {
-                            cache.insert_rc(type_info.clone(), dependency.clone());
+                            if let Some(previous) = cache.insert_rc(type_info.clone(), dependency.clone()) {
+                                // The previous instance is expired, it's finalized now instead of on close
+                                replaced = cache.resolved.remove(&previous);
+                            }
+                            let expires_at = config.ttl.and_then(|ttl| Some(now()?.saturating_add(ttl)));
+                            cache.set_expiration(type_info.clone(), expires_at);
                             debug!("Cached");
                         }
                         if has_finalizer {
@@ -204,12 +230,16 @@ impl Container {
                             debug!("Pushed to resolved set");
                         }
                     }
+                    if let (Some(Resolved { dependency, .. }), Some(finalizer)) = (replaced, finalizer) {
+                        let _ = finalizer.clone().call(dependency);
+                        debug!("Finalizer of replaced instance called");
+                    }
                     Ok(dependency)
                 }
                 Err(incorrect_type) => {
                     let err = ResolveErrorKind::IncorrectType {
                         expected: type_info,
-                        actual: TypeInfo::of_val(&*incorrect_type),
+                        actual: incorrect_type.type_info(),
                     };
                     error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
                     Err(err)
@@ -220,8 +250,9 @@ impl Container {
                 Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err))))
             }
             Err(InstantiatorErrorKind::Factory(err)) => {
+                let err = ResolveErrorKind::factory(type_info, err, attempt);
                 error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve dependency");
-                Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(err)))
+                Err(err)
             }
         }
     }
@@ -238,10 +269,13 @@ impl Container {
     pub fn get_transient<Dep: 'static>(&self) -> Result<Dep, ResolveErrorKind> {
         let type_info = TypeInfo::of::<Dep>();
         let dep_name = type_info.name;
-        let scope_name = self.inner.scope_data.name;
+        let scope_name = &*self.inner.scope_data.name;
 
         let Some(InstantiatorData {
-            instantiator, scope_data, ..
+            instantiator,
+            config,
+            scope_data,
+            ..
         }) = self.inner.registry.get(&type_info)
         else {
             let err = ResolveErrorKind::NoInstantiator { type_info };
@@ -249,48 +283,92 @@ impl Container {
             return Err(err);
         };
 
-        let current_priority = self.inner.scope_data.priority;
-        let dep_priority = scope_data.priority;
-
-        if current_priority > dep_priority {
-            let mut parent = self
-                .inner
-                .parent
-                .as_ref()
-                .expect("parent should exist for lower-priority dependency");
-            while parent.inner.scope_data.priority != dep_priority {
-                parent = parent.inner.parent.as_ref().expect("parent with target priority should exist");
-            }
-            return parent.get_transient();
-        }
-        if dep_priority > current_priority {
-            let err = ResolveErrorKind::NoAccessible {
-                expected_scope_data: *scope_data,
-                actual_scope_data: self.inner.scope_data,
+        if scope_data.priority != self.inner.scope_data.priority {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
+                let err = ResolveErrorKind::NoAccessible {
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
+                };
+                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
+                return Err(err);
             };
-            error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
-            return Err(err);
+            return parent.get_transient();
         }
 
-        match instantiator.clone().call(self.clone()) {
-            Ok(dependency) => match dependency.downcast::<Dep>() {
-                Ok(dependency) => Ok(*dependency),
+        match self.instantiate(instantiator, &type_info, config) {
+            Ok(dependency) => match dependency.downcast_owned::<Dep>() {
+                Ok(dependency) => Ok(dependency),
                 Err(incorrect_type) => {
                     let err = ResolveErrorKind::IncorrectType {
                         expected: type_info,
-                        actual: TypeInfo::of_val(&*incorrect_type),
+                        actual: incorrect_type.type_info(),
                     };
                     error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
                     Err(err)
                 }
             },
-            Err(InstantiatorErrorKind::Deps(err)) => {
+            Err(err) => {
                 error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
-                Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err))))
+                Err(err)
             }
-            Err(InstantiatorErrorKind::Factory(err)) => {
-                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve transient dependency");
-                Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(err)))
+        }
+    }
+
+    /// Gets a weak reference to the instance of a dependency cached by the container of its scope, without instantiating it,
+    /// see [`InjectWeak`]
+    pub(crate) fn get_weak<Dep: SendSafety + SyncSafety + 'static>(&self) -> Result<InjectWeak<Dep>, ResolveErrorKind> {
+        let type_info = TypeInfo::of::<Dep>();
+        let dep_name = type_info.name;
+        let scope_name = &*self.inner.scope_data.name;
+        let weak = InjectWeak::new();
+
+        let Some(InstantiatorData { scope_data, .. }) = self.inner.registry.get(&type_info) else {
+            // Values of the context are cached without a provider
+            if self.inner.cache.try_write(&type_info)?.watch_weak(type_info.clone(), &weak.0) {
+                return Ok(weak);
+            }
+            let err = ResolveErrorKind::NoInstantiator { type_info };
+            error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve weak dependency");
+            return Err(err);
+        };
+
+        let container = if scope_data.priority == self.inner.scope_data.priority {
+            self
+        } else {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
+                let err = ResolveErrorKind::NoAccessible {
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
+                };
+                error!(dependency = dep_name, scope = scope_name, error = %err, "Failed to resolve weak dependency");
+                return Err(err);
+            };
+            parent
+        };
+        container.inner.cache.try_write(&type_info)?.watch_weak(type_info, &weak.0);
+        Ok(weak)
+    }
+
+    /// Calls the instantiator of a transient dependency, retrying its own failures by the retry policy of the config
+    fn instantiate(
+        &self,
+        instantiator: &BoxedCloneInstantiator<ResolveErrorKind, InstantiateErrorKind>,
+        type_info: &TypeInfo,
+        config: &Config,
+    ) -> Result<Instance, ResolveErrorKind> {
+        let mut attempt = 1;
+        loop {
+            match instantiator.clone().call((self.clone(), false)) {
+                Ok(dependency) => return Ok(dependency),
+                Err(InstantiatorErrorKind::Deps(err)) => {
+                    return Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err))));
+                }
+                Err(InstantiatorErrorKind::Factory(err)) if attempt < config.retry.max_attempts => {
+                    warn!(attempt, error = %err, "Failed to instantiate, retrying");
+                    clock::sleep(self.inner.registry.clock.as_ref(), config.retry.delay(attempt));
+                    attempt += 1;
+                }
+                Err(InstantiatorErrorKind::Factory(err)) => return Err(ResolveErrorKind::factory(type_info.clone(), err, attempt)),
             }
         }
     }
@@ -303,9 +381,92 @@ impl Container {
     pub fn close(&self) {
         self.inner.close();
     }
+
+    /// Takes a snapshot of the container state: its levels from the root scope to the current one,
+    /// types registered in each scope, cached types, pending finalizers and context types.
+    ///
+    /// Meant for debugging, the [`Debug`] impl of the container is built on it.
+    #[must_use]
+    pub fn describe(&self) -> ContainerSnapshot {
+        let mut levels = Vec::new();
+        let mut container = Some(self);
+        while let Some(Container { inner, .. }) = container {
+            levels.push(inner.describe_level());
+            container = inner.parent.as_ref();
+        }
+        levels.reverse();
+        ContainerSnapshot { levels }
+    }
+
+    /// Plans resolving `Dep` with [`Self::get`] without calling any instantiator:
+    /// for `Dep` and each of its dependencies, the scope it would be resolved in, whether it's already cached,
+    /// whether a finalizer would be registered, and problems that would fail the resolution.
+    #[must_use]
+    pub fn explain<Dep: 'static>(&self) -> ResolutionPlan {
+        self.explain_type(&TypeInfo::of::<Dep>(), false, &mut Vec::new())
+    }
+}
+
+impl Debug for Container {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Container").field("levels", &self.describe().levels).finish()
+    }
 }
 
 impl Container {
+    /// Mirrors the walk of [`Self::get`] and [`Self::get_transient`]. `path` holds the types being planned
+    pub(crate) fn explain_type(&self, type_info: &TypeInfo, is_transient: bool, path: &mut Vec<TypeInfo>) -> ResolutionPlan {
+        let mut plan = ResolutionPlan::new(type_info.name, is_transient);
+        let now = || clock::now(self.inner.registry.clock.as_ref());
+        if !is_transient && self.inner.cache.read().contains_fresh(type_info, now) {
+            // Values of parent scopes are cached in child levels too, so report the scope they're provided in
+            plan.scope = Some(
+                self.inner
+                    .registry
+                    .get(type_info)
+                    .map_or_else(|| self.inner.scope_data.clone(), |data| data.scope_data.clone()),
+            );
+            plan.cached = true;
+            return plan;
+        }
+
+        let Some(InstantiatorData {
+            finalizer,
+            scope_data,
+            dependencies,
+            ..
+        }) = self.inner.registry.get(type_info)
+        else {
+            plan.problem = Some(PlanProblem::NoInstantiator);
+            return plan;
+        };
+
+        plan.scope = Some(scope_data.clone());
+        if scope_data.priority != self.inner.scope_data.priority {
+            let Some(parent) = self.inner.ancestor_in_scope(scope_data) else {
+                plan.problem = Some(PlanProblem::NoAccessible {
+                    expected_scope_data: scope_data.clone(),
+                    actual_scope_data: self.inner.scope_data.clone(),
+                });
+                return plan;
+            };
+            return parent.explain_type(type_info, is_transient, path);
+        }
+        if path.contains(type_info) {
+            plan.problem = Some(PlanProblem::CyclicDependency);
+            return plan;
+        }
+
+        plan.registers_finalizer = !is_transient && finalizer.is_some();
+        path.push(type_info.clone());
+        plan.dependencies = dependencies
+            .iter()
+            .map(|dependency| self.explain_type(&dependency.type_info, dependency.is_transient, path))
+            .collect();
+        path.pop();
+        plan
+    }
+
     #[must_use]
     fn init_child_with_context(
         self,
@@ -322,7 +483,7 @@ impl Container {
             #[cfg(feature = "thread_safe")]
             per_type_locks: self.per_type_locks.clone(),
             inner: RcThreadSafety::new(ContainerInner {
-                cache: RwLock::new(cache),
+                cache: SharedCache::new(cache),
                 context,
                 registry,
                 scope_data,
@@ -348,7 +509,7 @@ impl Container {
             #[cfg(feature = "thread_safe")]
             per_type_locks: self.per_type_locks.clone(),
             inner: RcThreadSafety::new(ContainerInner {
-                cache: RwLock::new(cache),
+                cache: SharedCache::new(cache),
                 context,
                 registry,
                 scope_data,
@@ -359,8 +520,8 @@ impl Container {
         }
     }
 
-    /// Shared loop behind the four `*::build()` entry points. Starting from the next child scope, it
-    /// initializes one child container per scope until `is_target` accepts one. The first child keeps
+    /// Shared loop behind the four `*::build()` entry points. It descends the branch of the scope tree
+    /// to the first scope accepted by `is_target`, initializing one child container per scope. The first child keeps
     /// its parent open (`close_parent = false`); intermediate skipped levels are created with
     /// `close_parent = true`. A `context`, when present, is threaded into every level.
     fn build_descendant<E>(
@@ -370,20 +531,41 @@ impl Container {
         no_child: E,
         no_target: impl Fn() -> E,
     ) -> Result<Container, E> {
-        let scopes = self.inner.get_scope_with_child_scopes().child();
-        let mut child = self.init_descendant(context.clone(), scopes.scope_data.ok_or(no_child)?, scopes.child_scopes_data, false);
+        let scopes = self.inner.get_scope_with_child_scopes();
+        let Some(mut scopes) = scopes.child(&is_target) else {
+            return Err(if scopes.has_children() { no_target() } else { no_child });
+        };
+        let scope_data = scopes.scope_data.clone().expect("child scope should have scope data");
+        let mut child = self.init_descendant(context.clone(), scope_data, scopes.child_scopes_data.clone(), false);
         while !is_target(&child.inner.scope_data) {
-            let scopes = child.inner.get_scope_with_child_scopes().child();
-            child = child.init_descendant(
-                context.clone(),
-                scopes.scope_data.ok_or_else(&no_target)?,
-                scopes.child_scopes_data,
-                true,
-            );
+            scopes = scopes.child(&is_target).ok_or_else(&no_target)?;
+            let scope_data = scopes.scope_data.clone().expect("child scope should have scope data");
+            child = child.init_descendant(context.clone(), scope_data, scopes.child_scopes_data.clone(), true);
         }
         Ok(child)
     }
 
+    fn build_descendant_by_name(self, context: Option<Context>, name: Cow<'static, str>) -> Result<Container, ScopeWithErrorKind> {
+        use ScopeWithErrorKind::{NoChildRegistries, NoChildRegistriesWithScope};
+
+        let priority = self
+            .inner
+            .registry
+            .scopes_data
+            .iter()
+            .find(|scope_data| scope_data.name == name)
+            .map(|scope_data| scope_data.priority);
+        self.build_descendant(
+            context,
+            |scope_data| scope_data.name == name,
+            NoChildRegistries,
+            || NoChildRegistriesWithScope {
+                name: name.clone(),
+                priority,
+            },
+        )
+    }
+
     fn init_descendant(
         self,
         context: Option<Context>,
@@ -413,6 +595,17 @@ impl ChildContainerBuilder {
         }
     }
 
+    /// Enters a scope by its name, for scopes that are only known at runtime.
+    /// See [`Registry::with_scopes`]
+    #[inline]
+    #[must_use]
+    pub fn with_scope_name(self, name: impl Into<Cow<'static, str>>) -> ChildContainerWithScope<Cow<'static, str>> {
+        ChildContainerWithScope {
+            container: self.container,
+            scope: name.into(),
+        }
+    }
+
     #[inline]
     #[must_use]
     pub fn with_context(self, context: Context) -> ChildContainerWithContext {
@@ -467,7 +660,7 @@ where
     ///
     /// # Errors
     /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
-    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
     ///
     /// # Warning
     /// If you want just to use next non-skipped scope, use [`ChildContainerBuilder::with_scope`]
@@ -480,11 +673,35 @@ where
             None,
             move |scope_data| scope_data.priority == priority,
             NoChildRegistries,
-            move || NoChildRegistriesWithScope { name, priority },
+            move || NoChildRegistriesWithScope {
+                name: name.into(),
+                priority: Some(priority),
+            },
         )
     }
 }
 
+impl ChildContainerWithScope<Cow<'static, str>> {
+    #[inline]
+    #[must_use]
+    pub fn with_context(self, context: Context) -> ChildContainerWithScopeAndContext<Cow<'static, str>> {
+        ChildContainerWithScopeAndContext {
+            container: self.container,
+            scope: self.scope,
+            context,
+        }
+    }
+
+    /// Creates child container with scope of specified name.
+    ///
+    /// # Errors
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.container.build_descendant_by_name(None, self.scope)
+    }
+}
+
 pub struct ChildContainerWithContext {
     container: Container,
     context: Context,
@@ -501,6 +718,18 @@ impl ChildContainerWithContext {
         }
     }
 
+    /// Enters a scope by its name, for scopes that are only known at runtime.
+    /// See [`Registry::with_scopes`]
+    #[inline]
+    #[must_use]
+    pub fn with_scope_name(self, name: impl Into<Cow<'static, str>>) -> ChildContainerWithScopeAndContext<Cow<'static, str>> {
+        ChildContainerWithScopeAndContext {
+            container: self.container,
+            scope: name.into(),
+            context: self.context,
+        }
+    }
+
     /// Creates child container with next non-skipped scope and passes context to it.
     ///
     /// # Errors
@@ -535,7 +764,7 @@ where
     ///
     /// # Errors
     /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
-    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
     ///
     /// # Warning
     /// If you want just to use next non-skipped scope, use [`ChildContainerBuilder::with_scope`]
@@ -548,11 +777,25 @@ where
             Some(self.context),
             move |scope_data| scope_data.priority == priority,
             NoChildRegistries,
-            move || NoChildRegistriesWithScope { name, priority },
+            move || NoChildRegistriesWithScope {
+                name: name.into(),
+                priority: Some(priority),
+            },
         )
     }
 }
 
+impl ChildContainerWithScopeAndContext<Cow<'static, str>> {
+    /// Creates child container with scope of specified name and passes context to it.
+    ///
+    /// # Errors
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistries`] if there are no registries
+    /// - Returns [`ScopeWithErrorKind::NoChildRegistriesWithScope`] if there are no registries with specified scope among the descendants of the current one
+    pub fn build(self) -> Result<Container, ScopeWithErrorKind> {
+        self.container.build_descendant_by_name(Some(self.context), self.scope)
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct BoxedContainerInner {
     pub(crate) cache: Cache,
@@ -608,7 +851,7 @@ impl From<BoxedContainerInner> for Container {
     ) -> Self {
         Self {
             inner: RcThreadSafety::new(ContainerInner {
-                cache: RwLock::new(cache),
+                cache: SharedCache::new(cache),
                 context,
                 registry,
                 scope_data,
@@ -623,7 +866,7 @@ impl From<BoxedContainerInner> for Container {
 }
 
 pub(crate) struct ContainerInner {
-    pub(crate) cache: RwLock<Cache>,
+    pub(crate) cache: SharedCache,
     pub(crate) context: Context,
     pub(crate) registry: RcThreadSafety<Registry>,
     pub(crate) scope_data: ScopeData,
@@ -635,7 +878,26 @@ pub(crate) struct ContainerInner {
 impl ContainerInner {
     #[inline]
     pub(crate) fn get_scope_with_child_scopes(&self) -> ScopeDataWithChildScopesData {
-        ScopeDataWithChildScopesData::new(self.scope_data, self.child_scopes_data.clone())
+        ScopeDataWithChildScopesData::new(self.scope_data.clone(), self.child_scopes_data.clone())
+    }
+
+    /// The ancestor level in `scope_data`, `None` if the scope isn't an ancestor of the current one
+    fn ancestor_in_scope(&self, scope_data: &ScopeData) -> Option<&Container> {
+        let mut parent = self.parent.as_ref()?;
+        while parent.inner.scope_data.priority != scope_data.priority {
+            parent = parent.inner.parent.as_ref()?;
+        }
+        Some(parent)
+    }
+
+    pub(crate) fn describe_level(&self) -> LevelSnapshot {
+        let registered = self
+            .registry
+            .entries
+            .iter()
+            .filter(|(_, data)| data.scope_data == self.scope_data)
+            .map(|(type_info, _)| type_info);
+        LevelSnapshot::new(self.scope_data.clone(), registered, &[&self.cache.read()], &self.context)
     }
 
     #[inline]
@@ -659,10 +921,7 @@ impl ContainerInner {
         }
 
         // We need to clear cache and fill it with the context as in start of the container usage
-        #[allow(clippy::assigning_clones)]
-        {
-            self.cache.write().map = self.context.map.clone();
-        }
+        self.cache.reset(&self.context);
 
         if close_parent {
             if let Some(parent) = &self.parent {
@@ -688,7 +947,7 @@ mod tests {
     use super::Container;
     use crate::{
         container::ContainerInner,
-        inject::{Inject, InjectTransient},
+        inject::{Inject, InjectTransient, InjectWeak},
         registry,
         scope::DefaultScope::*,
         utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
@@ -699,7 +958,10 @@ mod tests {
         format,
         string::{String, ToString as _},
     };
-    use core::sync::atomic::{AtomicU8, Ordering};
+    use core::{
+        sync::atomic::{AtomicU64, AtomicU8, Ordering},
+        time::Duration,
+    };
     use tracing::debug;
     use tracing_test::traced_test;
 
@@ -1275,7 +1537,7 @@ mod tests {
             scope(App) [
                 provide(
                     move || Ok::<_, crate::InstantiateErrorKind>(for_factory.fetch_add(1, Ordering::SeqCst)),
-                    config = crate::Config { cache_provides: false },
+                    config = crate::Config::default().cache_provides(false),
                 ),
             ],
         });
@@ -1291,4 +1553,440 @@ mod tests {
             "each get must re-instantiate at the owning scope"
         );
     }
+
+    #[test]
+    #[traced_test]
+    fn test_describe() {
+        use core::any::type_name;
+
+        let app = Container::new(registry! {
+            scope(App) [
+                provide(|| Ok(Request1), finalizer = |_: RcThreadSafety<Request1>| {}),
+            ],
+            scope(Request) [
+                provide(|Inject(req): Inject<Request1>| Ok(Request2(req)), finalizer = |_: RcThreadSafety<Request2>| {}),
+                provide(|| Ok(RequestTransient1)),
+            ],
+        });
+        let mut context = crate::Context::new();
+        context.insert(7u32);
+        let request = app.clone().enter().with_scope(Request).with_context(context).build().unwrap();
+        request.get::<Request2>().unwrap();
+
+        let snapshot = request.describe();
+        let scopes: alloc::vec::Vec<_> = snapshot.scope_path().map(|scope| &*scope.name).collect();
+        assert_eq!(scopes, ["runtime", "app", "session", "request"]);
+
+        let [runtime, app_level, _, request_level] = &snapshot.levels[..] else {
+            panic!("unexpected levels: {snapshot:?}");
+        };
+        assert_eq!(runtime.registered, ["Container"]);
+        assert_eq!(app_level.registered, [type_name::<Request1>()]);
+        assert_eq!(app_level.cached, [type_name::<Request1>()]);
+        assert_eq!(app_level.pending_finalizers, [type_name::<Request1>()]);
+        assert!(app_level.context.is_empty());
+
+        let mut registered = [type_name::<Request2>(), type_name::<RequestTransient1>()];
+        registered.sort_unstable();
+        assert_eq!(request_level.registered, registered);
+        let mut cached = [type_name::<Request1>(), type_name::<Request2>()];
+        cached.sort_unstable();
+        assert_eq!(request_level.cached, cached);
+        assert_eq!(request_level.pending_finalizers, [type_name::<Request2>()]);
+        assert_eq!(request_level.context, [type_name::<u32>()]);
+        assert_eq!(snapshot.current(), request_level);
+
+        let debug = format!("{request:?}");
+        assert!(debug.starts_with("Container { levels: ["));
+        assert!(debug.contains(type_name::<Request2>()));
+
+        request.close();
+        let snapshot = request.describe();
+        assert!(snapshot.current().pending_finalizers.is_empty());
+        assert!(snapshot.current().cached.is_empty());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_explain() {
+        use crate::{PlanProblem, ResolutionPlan};
+        use core::any::type_name;
+
+        fn dependency<T>(plan: &ResolutionPlan) -> &ResolutionPlan {
+            plan.dependencies
+                .iter()
+                .find(|dependency| dependency.type_name == type_name::<T>())
+                .expect("dependency should be planned")
+        }
+
+        let app = Container::new(registry! {
+            scope(App) [
+                provide(|| Ok(Request1), finalizer = |_: RcThreadSafety<Request1>| {}),
+            ],
+            scope(Request) [
+                provide(
+                    |Inject(req): Inject<Request1>, InjectTransient(_): InjectTransient<RequestTransient2>| Ok(Request2(req)),
+                    finalizer = |_: RcThreadSafety<Request2>| {},
+                ),
+                provide(|InjectTransient(req): InjectTransient<RequestTransient1>| Ok(RequestTransient2(req))),
+                provide(|Inject(_): Inject<u32>| Ok(RequestTransient1)),
+            ],
+            scope(Action) [
+                provide(|Inject(req): Inject<Request2>| Ok(Request3(req.0.clone(), req))),
+            ],
+        });
+        app.get::<Request1>().unwrap();
+        let request = app.enter().with_scope(Request).build().unwrap();
+
+        let plan = request.explain::<Request2>();
+        assert_eq!(plan.type_name, type_name::<Request2>());
+        assert_eq!(plan.scope, Some(Request.into()));
+        assert!(!plan.cached && plan.registers_finalizer && !plan.is_transient);
+        assert_eq!(plan.dependencies.len(), 2);
+
+        let request1 = dependency::<Request1>(&plan);
+        assert!(request1.cached);
+        assert_eq!(request1.scope, Some(App.into()));
+        assert!(request1.dependencies.is_empty());
+
+        let transient2 = dependency::<RequestTransient2>(&plan);
+        assert!(transient2.is_transient && !transient2.cached && !transient2.registers_finalizer);
+        let transient1 = dependency::<RequestTransient1>(transient2);
+        let missing = dependency::<u32>(transient1);
+        assert_eq!(missing.problem, Some(PlanProblem::NoInstantiator));
+        assert_eq!(missing.scope, None);
+
+        assert!(!plan.is_resolvable());
+        let problems = plan.problems();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].type_name, type_name::<u32>());
+
+        let rendered = plan.to_string();
+        assert!(rendered.starts_with("Request2 [instantiated in `request`, finalizer]"));
+        assert!(rendered.contains("\n  Request1 [cached in `app`]"));
+        assert!(rendered.contains("\n      u32 [no instantiator]"));
+
+        let plan = request.explain::<Request3>();
+        assert_eq!(
+            plan.problem,
+            Some(PlanProblem::NoAccessible {
+                expected_scope_data: Action.into(),
+                actual_scope_data: Request.into(),
+            })
+        );
+        assert!(plan.dependencies.is_empty());
+
+        // Nothing was instantiated while planning
+        assert!(request.describe().current().pending_finalizers.is_empty());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_scope_tree() {
+        use crate::{errors::ScopeWithErrorKind, ScopeData, Scopes};
+
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        enum TreeScope {
+            App,
+            Request,
+            Action,
+            Job,
+            JobStep,
+        }
+
+        impl From<TreeScope> for ScopeData {
+            fn from(scope: TreeScope) -> Self {
+                Self {
+                    priority: scope.priority(),
+                    name: scope.name().into(),
+                    is_skipped_by_default: scope.is_skipped_by_default(),
+                    parent: scope.parent().map(|parent| parent.priority()),
+                }
+            }
+        }
+
+        impl Scope for TreeScope {
+            fn name(&self) -> &'static str {
+                match self {
+                    TreeScope::App => "app",
+                    TreeScope::Request => "request",
+                    TreeScope::Action => "action",
+                    TreeScope::Job => "job",
+                    TreeScope::JobStep => "job_step",
+                }
+            }
+
+            fn priority(&self) -> u8 {
+                *self as u8
+            }
+
+            fn parent(&self) -> Option<Self> {
+                match self {
+                    TreeScope::Job => Some(TreeScope::App),
+                    _ => None,
+                }
+            }
+        }
+
+        impl Scopes<4> for TreeScope {
+            type Scope = Self;
+
+            fn all() -> (Self::Scope, [Self::Scope; 4]) {
+                use TreeScope::{Action, App, Job, JobStep, Request};
+
+                (App, [Request, Action, Job, JobStep])
+            }
+        }
+
+        struct Config;
+        struct RequestData;
+        struct JobData;
+
+        let app = Container::new_with_start_scope(
+            registry! {
+                scope(TreeScope::App) [
+                    provide(|| Ok(Config)),
+                ],
+                scope(TreeScope::Request) [
+                    provide(|Inject(_): Inject<Config>| Ok(RequestData)),
+                ],
+                scope(TreeScope::Job) [
+                    provide(|Inject(_): Inject<Config>| Ok(JobData)),
+                ],
+                scope(TreeScope::JobStep) [
+                    provide(|Inject(_): Inject<JobData>| Ok(())),
+                ],
+            },
+            TreeScope::App,
+        );
+
+        // The default branch is the first child
+        let request = app.clone().enter_build().unwrap();
+        assert_eq!(request.inner.scope_data.name, "request");
+        assert!(request.get::<RequestData>().is_ok());
+        assert!(matches!(
+            request.get::<JobData>(),
+            Err(ResolveErrorKind::NoAccessible { expected_scope_data, actual_scope_data })
+                if expected_scope_data.name == "job" && actual_scope_data.name == "request"
+        ));
+        assert!(matches!(
+            request.clone().enter().with_scope(TreeScope::Job).build(),
+            Err(ScopeWithErrorKind::NoChildRegistriesWithScope { name, .. }) if name == "job"
+        ));
+
+        let job = app.clone().enter().with_scope(TreeScope::Job).build().unwrap();
+        assert!(job.get::<JobData>().is_ok());
+        assert!(matches!(job.get::<RequestData>(), Err(ResolveErrorKind::NoAccessible { .. })));
+        assert!(!job.explain::<RequestData>().is_resolvable());
+
+        // Entering a scope of the branch creates the levels on the way to it
+        let job_step = app.enter().with_scope(TreeScope::JobStep).build().unwrap();
+        let scopes: alloc::vec::Vec<_> = job_step.describe().scope_path().map(|scope| scope.name.clone()).collect();
+        assert_eq!(scopes, ["app", "job", "job_step"]);
+        assert!(job_step.get::<()>().is_ok());
+        assert!(job_step.explain::<()>().is_resolvable());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_retry() {
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+
+        for (max_attempts, is_ok) in [(3, true), (2, false)] {
+            calls.store(0, Ordering::SeqCst);
+
+            let container = Container::new(registry! {
+                scope(App) [
+                    provide(
+                        {
+                            let calls = calls.clone();
+                            move || match calls.fetch_add(1, Ordering::SeqCst) {
+                                0 | 1 => Err(anyhow::anyhow!("transient failure").into()),
+                                _ => Ok(()),
+                            }
+                        },
+                        config = crate::Config::default().retry(crate::RetryPolicy::new(max_attempts, core::time::Duration::from_millis(1))),
+                    ),
+                ]
+            });
+
+            match container.get::<()>() {
+                Ok(_) => assert!(is_ok),
+                Err(err) => assert!(
+                    !is_ok && matches!(err, ResolveErrorKind::RetriesExhausted { attempts: 2, .. }),
+                    "{err}"
+                ),
+            }
+            assert_eq!(calls.load(Ordering::SeqCst), if is_ok { 3 } else { 2 });
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    #[cfg(all(feature = "thread_safe", feature = "std"))]
+    fn test_retry_releases_lock_while_waiting() {
+        use std::{sync::mpsc, thread};
+
+        let (failed_tx, failed_rx) = mpsc::sync_channel(1);
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let container = Container::new(registry! {
+            scope(App) [
+                provide(
+                    {
+                        let calls = calls.clone();
+                        move || {
+                            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                                failed_tx.send(()).unwrap();
+                                return Err(anyhow::anyhow!("transient failure").into());
+                            }
+                            Ok(thread::current().id())
+                        }
+                    },
+                    config = crate::Config::default().retry(crate::RetryPolicy::new(2, Duration::from_millis(200))),
+                ),
+            ]
+        });
+
+        let retrying = thread::spawn({
+            let container = container.clone();
+            move || container.get::<thread::ThreadId>().unwrap()
+        });
+        failed_rx.recv().unwrap();
+
+        // The retrying thread waits without the lock, so this call makes the next attempt
+        let resolved = container.get::<thread::ThreadId>().unwrap();
+        assert_eq!(*resolved, thread::current().id());
+        assert!(RcThreadSafety::ptr_eq(&resolved, &retrying.join().unwrap()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_clock_sleep_waits_for_clock() {
+        use crate::Clock as _;
+
+        // Advances by a second on each read
+        struct TickingClock(AtomicU64);
+
+        impl crate::Clock for TickingClock {
+            fn now(&self) -> Duration {
+                Duration::from_secs(self.0.fetch_add(1, Ordering::SeqCst))
+            }
+        }
+
+        let clock = TickingClock(AtomicU64::new(0));
+        clock.sleep(Duration::from_secs(3));
+        assert!(clock.0.load(Ordering::SeqCst) > 3);
+    }
+
+    struct ManualClock(RcThreadSafety<AtomicU64>);
+
+    impl crate::Clock for ManualClock {
+        fn now(&self) -> Duration {
+            Duration::from_secs(self.0.load(Ordering::SeqCst))
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_ttl() {
+        let secs = RcThreadSafety::new(AtomicU64::new(0));
+        let calls = RcThreadSafety::new(AtomicU8::new(0));
+        let finalized = RcThreadSafety::new(AtomicU8::new(0));
+
+        let app_container = Container::new(
+            registry! {
+                scope(App) [
+                    provide(
+                        {
+                            let calls = calls.clone();
+                            move || Ok(calls.fetch_add(1, Ordering::SeqCst) + 1)
+                        },
+                        config = crate::Config::default().ttl(Some(Duration::from_secs(10))),
+                        finalizer = {
+                            let finalized = finalized.clone();
+                            move |_: RcThreadSafety<u8>| {
+                                finalized.fetch_add(1, Ordering::SeqCst);
+                            }
+                        },
+                    ),
+                ]
+            }
+            .with_clock(ManualClock(secs.clone())),
+        );
+        let request_container = app_container.clone().enter_build().unwrap();
+
+        assert_eq!(*app_container.get::<u8>().unwrap(), 1);
+        assert_eq!(*request_container.get::<u8>().unwrap(), 1);
+
+        secs.store(5, Ordering::SeqCst);
+        assert_eq!(*app_container.get::<u8>().unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(app_container.explain::<u8>().cached);
+
+        secs.store(10, Ordering::SeqCst);
+        // The expired instance is reported as instantiated again, as `get` would do
+        assert!(!app_container.explain::<u8>().cached);
+        assert_eq!(*app_container.get::<u8>().unwrap(), 2);
+        assert_eq!(finalized.load(Ordering::SeqCst), 1);
+        assert_eq!(*request_container.get::<u8>().unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        request_container.close();
+        app_container.close();
+        assert_eq!(finalized.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    #[cfg(not(feature = "thread_safe"))]
+    #[traced_test]
+    fn test_reentrant_resolution() {
+        let app_container = Container::new(registry! {
+            scope(App) [
+                provide(|| Ok(1u8)),
+                provide(|Inject(value): Inject<u8>| Ok(u16::from(*value))),
+            ],
+        });
+
+        // The cache is borrowed by the caller, as by a `Drop` implementation of a value dropped while it's updated
+        let cache = app_container.inner.cache.write();
+        assert!(matches!(
+            app_container.get::<u16>(),
+            Err(ResolveErrorKind::CacheBorrowed { type_info }) if type_info == crate::TypeInfo::of::<u16>()
+        ));
+        drop(cache);
+        assert_eq!(*app_container.get::<u16>().unwrap(), 1);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_weak_back_pointer() {
+        struct Parent(RcThreadSafety<Child>);
+        struct Child(InjectWeak<Parent>);
+
+        let app_container = Container::new(registry! {
+            scope(App) [
+                provide(|Inject(child): Inject<Child>| Ok(Parent(child))),
+                provide(|weak: InjectWeak<Parent>| Ok(Child(weak))),
+            ],
+        });
+        let request_container = app_container.clone().enter_build().unwrap();
+
+        // The parent isn't instantiated by the weak reference, it points at the parent once it's cached
+        let child = request_container.get::<Child>().unwrap();
+        assert!(child.0.upgrade().is_none());
+        let parent = request_container.get::<Parent>().unwrap();
+        assert!(RcThreadSafety::ptr_eq(&child.0.upgrade().unwrap(), &parent));
+        assert!(RcThreadSafety::ptr_eq(&parent.0, &child));
+
+        assert!(matches!(
+            request_container.get_weak::<u8>(),
+            Err(ResolveErrorKind::NoInstantiator { .. })
+        ));
+
+        // The weak reference doesn't keep the parent alive
+        drop((parent, request_container));
+        app_container.close();
+        assert!(child.0.upgrade().is_none());
+    }
 }
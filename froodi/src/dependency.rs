@@ -5,6 +5,10 @@ use crate::any::TypeInfo;
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Dependency {
     pub type_info: TypeInfo,
+    /// `true` if the dependency is resolved via [`crate::InjectTransient`], so it's never cached
+    pub is_transient: bool,
+    /// `true` if the dependency is resolved via [`crate::InjectWeak`], so it isn't instantiated and cycles through it are allowed
+    pub is_weak: bool,
 }
 
 pub(crate) const EMPTY_DEPENDENCIES: BTreeSet<Dependency> = BTreeSet::new();
@@ -1,5 +1,9 @@
 #[cfg(feature = "async")]
-use core::future::Future;
+use core::{
+    future::{poll_fn, Future},
+    pin::pin,
+    task::Poll,
+};
 
 use super::errors::ResolveErrorKind;
 #[cfg(feature = "async")]
@@ -14,6 +18,16 @@ pub trait DependencyResolver: Sized {
     #[cfg(feature = "async")]
     fn resolve_async(container: &AsyncContainer) -> impl Future<Output = Result<Self, Self::Error>> + SendSafety;
 
+    /// Like [`Self::resolve_async`], but the parts of the dependency are resolved concurrently if it has several of them.
+    /// On failure, the error of the first failed part in order is returned.
+    ///
+    /// Parts that depend on each other stay consistent, because instantiation of a type is serialized per type.
+    #[cfg(feature = "async")]
+    #[inline]
+    fn resolve_async_concurrently(container: &AsyncContainer) -> impl Future<Output = Result<Self, Self::Error>> + SendSafety {
+        Self::resolve_async(container)
+    }
+
     #[inline]
     #[must_use]
     fn type_info() -> TypeInfo
@@ -22,6 +36,20 @@ pub trait DependencyResolver: Sized {
     {
         TypeInfo::of::<Self>()
     }
+
+    /// Whether the dependency is resolved without caching, see [`crate::InjectTransient`]
+    #[inline]
+    #[must_use]
+    fn is_transient() -> bool {
+        false
+    }
+
+    /// Whether the dependency is referenced without being instantiated, see [`crate::InjectWeak`]
+    #[inline]
+    #[must_use]
+    fn is_weak() -> bool {
+        false
+    }
 }
 
 macro_rules! impl_dependency_resolver {
@@ -47,6 +75,34 @@ macro_rules! impl_dependency_resolver {
             async fn resolve_async(container: &AsyncContainer) -> Result<Self, Self::Error> {
                 Ok(($($ty::resolve_async(container).await.map_err(Into::into)?,)*))
             }
+
+            #[inline]
+            #[allow(unused_variables)]
+            #[cfg(feature = "async")]
+            async fn resolve_async_concurrently(container: &AsyncContainer) -> Result<Self, Self::Error> {
+                // Each part is a pinned future with a slot for its result, a part isn't polled after it's ready.
+                // The first error ends the resolution, so the parts still pending aren't polled again and are dropped
+                $( let mut $ty = (pin!($ty::resolve_async(container)), None::<$ty>); )*
+                poll_fn(|cx| {
+                    let mut is_ready = true;
+                    $(
+                        if $ty.1.is_none() {
+                            match $ty.0.as_mut().poll(cx) {
+                                Poll::Ready(Ok(value)) => $ty.1 = Some(value),
+                                Poll::Ready(Err(err)) => return Poll::Ready(Err::<(), ResolveErrorKind>(err.into())),
+                                Poll::Pending => is_ready = false,
+                            }
+                        }
+                    )*
+                    if is_ready {
+                        Poll::Ready(Ok(()))
+                    } else {
+                        Poll::Pending
+                    }
+                })
+                .await?;
+                Ok(($($ty.1.expect("part should be resolved"),)*))
+            }
         }
     };
 }
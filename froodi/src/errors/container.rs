@@ -1,3 +1,5 @@
+use alloc::borrow::Cow;
+
 #[derive(thiserror::Error, Debug)]
 pub enum ScopeErrorKind {
     #[error("Child registries not found in container")]
@@ -10,6 +12,7 @@ pub enum ScopeErrorKind {
 pub enum ScopeWithErrorKind {
     #[error("Child registries not found in container")]
     NoChildRegistries,
-    #[error("Registry with name {name} and priority {priority} not found in container")]
-    NoChildRegistriesWithScope { name: &'static str, priority: u8 },
+    /// `priority` is `None` when the registry doesn't know a scope with this name
+    #[error("Registry with name {name} not found in child scopes of container")]
+    NoChildRegistriesWithScope { name: Cow<'static, str>, priority: Option<u8> },
 }
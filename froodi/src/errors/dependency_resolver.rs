@@ -1,4 +1,5 @@
 use alloc::boxed::Box;
+use core::time::Duration;
 
 use super::{instantiate::InstantiateErrorKind, instantiator::InstantiatorErrorKind};
 use crate::{any::TypeInfo, scope::ScopeData};
@@ -22,6 +23,41 @@ pub enum ResolveErrorKind {
     },
     #[error("Incorrect instantiator provides type. Actual: {actual:?}, expected: {expected:?}")]
     IncorrectType { expected: TypeInfo, actual: TypeInfo },
+    #[error("Instantiator for {type_info:?} timed out after {attempts} attempt(s) of {timeout:?}")]
+    Timeout {
+        type_info: TypeInfo,
+        timeout: Duration,
+        attempts: u32,
+    },
+    #[error("Instantiator for {type_info:?} failed after {attempts} attempt(s): {source}")]
+    RetriesExhausted {
+        type_info: TypeInfo,
+        attempts: u32,
+        source: InstantiateErrorKind,
+    },
+    #[error(
+        "Cache is already borrowed to resolve {type_info:?}. \
+        The single-threaded container is used re-entrantly while its cache is locked, for example from a clock or a `Drop` implementation"
+    )]
+    CacheBorrowed { type_info: TypeInfo },
     #[error(transparent)]
     Instantiator(InstantiatorErrorKind<Box<ResolveErrorKind>, InstantiateErrorKind>),
 }
+
+impl ResolveErrorKind {
+    /// Error of the instantiator itself after its last attempt.
+    /// [`Self::RetriesExhausted`] if it was retried, so the number of attempts is reported
+    #[inline]
+    #[must_use]
+    pub(crate) fn factory(type_info: TypeInfo, err: InstantiateErrorKind, attempts: u32) -> Self {
+        if attempts > 1 {
+            Self::RetriesExhausted {
+                type_info,
+                attempts,
+                source: err,
+            }
+        } else {
+            Self::Instantiator(InstantiatorErrorKind::Factory(err))
+        }
+    }
+}
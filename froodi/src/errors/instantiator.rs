@@ -1,7 +1,7 @@
 use alloc::{boxed::Box, fmt};
 use core::fmt::{Debug, Display, Formatter};
 
-use crate::{any::TypeInfo, scope::ScopeData};
+use crate::{any::TypeInfo, conflict::RegistrationSite, scope::ScopeData};
 
 #[derive(thiserror::Error, Debug)]
 pub enum InstantiatorErrorKind<DepsErr, FactoryErr> {
@@ -18,9 +18,30 @@ pub enum ValidationErrorKind {
     },
     UnreachableDependency {
         dependent: TypeInfo,
-        dependent_scope: ScopeData,
+        dependent_scope: Box<ScopeData>,
         dependency: TypeInfo,
-        dependency_scope: ScopeData,
+        dependency_scope: Box<ScopeData>,
+    },
+    MissingDependency {
+        dependent: TypeInfo,
+        dependency: TypeInfo,
+    },
+    DuplicateProvider {
+        type_info: TypeInfo,
+        first: RegistrationSite,
+        second: RegistrationSite,
+    },
+    MissingFacadeDependency {
+        facade: &'static str,
+        method: &'static str,
+        dependency: TypeInfo,
+    },
+    UnreachableFacadeDependency {
+        facade: &'static str,
+        method: &'static str,
+        facade_scope: Box<ScopeData>,
+        dependency: TypeInfo,
+        dependency_scope: Box<ScopeData>,
     },
 }
 
@@ -50,7 +71,7 @@ impl Display for ValidationErrorKind {
             } => write!(
                 f,
                 "Unreachable dependency: `{}` (scope `{}`, priority {}) depends on `{}` (scope `{}`, priority {}), \
-                 which is a narrower scope and can never be resolved from it. A dependency must live in an equal or wider scope.",
+                 which is a narrower or sibling scope and can never be resolved from it. A dependency must live in the same scope or an ancestor one.",
                 dependent.short_name(),
                 dependent_scope.name,
                 dependent_scope.priority,
@@ -58,6 +79,45 @@ impl Display for ValidationErrorKind {
                 dependency_scope.name,
                 dependency_scope.priority,
             ),
+            ValidationErrorKind::MissingDependency { dependent, dependency } => write!(
+                f,
+                "Missing dependency: `{}` depends on `{}` ({}), which has no registered instantiator. \
+                 Register a provider for it or declare it as external if it's supplied through the context.",
+                dependent.short_name(),
+                dependency.short_name(),
+                dependency.name,
+            ),
+            ValidationErrorKind::DuplicateProvider { type_info, first, second } => write!(
+                f,
+                "Duplicate provider: `{}` ({}) is registered by {first} and again by {second}. \
+                 Mark the registration meant to be replaced with `provide_default(...)`, the replacing one with `override(...)`, \
+                 or merge the registries with a different conflict policy.",
+                type_info.short_name(),
+                type_info.name,
+            ),
+            ValidationErrorKind::MissingFacadeDependency { facade, method, dependency } => write!(
+                f,
+                "Missing facade dependency: `{facade}::{method}` returns `{}` ({}), which has no registered instantiator. \
+                 Register a provider for it or declare it as external if it's supplied through the context.",
+                dependency.short_name(),
+                dependency.name,
+            ),
+            ValidationErrorKind::UnreachableFacadeDependency {
+                facade,
+                method,
+                facade_scope,
+                dependency,
+                dependency_scope,
+            } => write!(
+                f,
+                "Unreachable facade dependency: `{facade}::{method}` (scope `{}`, priority {}) returns `{}` (scope `{}`, priority {}), \
+                 which is a narrower or sibling scope and can never be resolved from it.",
+                facade_scope.name,
+                facade_scope.priority,
+                dependency.short_name(),
+                dependency_scope.name,
+                dependency_scope.priority,
+            ),
         }
     }
 }
@@ -0,0 +1,120 @@
+//! Checks of facade traits generated by [`crate::facade`] against registries.
+
+use alloc::boxed::Box;
+
+#[cfg(feature = "async")]
+use crate::async_impl;
+use crate::{
+    any::TypeInfo,
+    scope::{ScopeData, ScopeDataWithChildScopesData},
+    Registry, ValidationErrorKind,
+};
+
+/// Method of a facade trait and the type it resolves
+#[derive(Debug, Clone)]
+pub struct FacadeMethod {
+    pub name: &'static str,
+    pub type_info: TypeInfo,
+}
+
+impl FacadeMethod {
+    #[inline]
+    #[must_use]
+    pub fn of<Dep: ?Sized + 'static>(name: &'static str) -> Self {
+        Self {
+            name,
+            type_info: TypeInfo::of::<Dep>(),
+        }
+    }
+}
+
+/// Registry that facade traits can be checked against
+pub trait FacadeRegistry {
+    /// Checks that each method of the facade has a provider, in `scope` or one of its ancestors if it's set.
+    /// Types declared as external are accepted in any scope.
+    ///
+    /// # Errors
+    /// - Returns [`ValidationErrorKind::MissingFacadeDependency`] if a type is neither registered nor declared as external
+    /// - Returns [`ValidationErrorKind::UnreachableFacadeDependency`] if a type is provided in a scope that isn't accessible from `scope`
+    fn check_facade(&self, facade: &'static str, scope: Option<ScopeData>, methods: &[FacadeMethod]) -> Result<(), ValidationErrorKind>;
+}
+
+impl FacadeRegistry for Registry {
+    fn check_facade(&self, facade: &'static str, scope: Option<ScopeData>, methods: &[FacadeMethod]) -> Result<(), ValidationErrorKind> {
+        check(facade, scope, methods, &self.get_scope_with_child_scopes(), |type_info| {
+            provider_scope(self, type_info)
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl FacadeRegistry for async_impl::RegistryWithSync {
+    fn check_facade(&self, facade: &'static str, scope: Option<ScopeData>, methods: &[FacadeMethod]) -> Result<(), ValidationErrorKind> {
+        check(
+            facade,
+            scope,
+            methods,
+            &self.registry.get_scope_with_child_scopes(),
+            |type_info| match self.registry.entries.get(type_info) {
+                Some(data) => Some(Some(&data.scope_data)),
+                None => provider_scope(&self.sync, type_info),
+            },
+        )
+    }
+}
+
+/// Scope of the provider of the type, `Some(None)` for external types
+fn provider_scope<'a>(registry: &'a Registry, type_info: &TypeInfo) -> Option<Option<&'a ScopeData>> {
+    match registry.entries.get(type_info) {
+        Some(data) => Some(Some(&data.scope_data)),
+        None => registry.externals.contains(type_info).then_some(None),
+    }
+}
+
+fn check<'a>(
+    facade: &'static str,
+    scope: Option<ScopeData>,
+    methods: &[FacadeMethod],
+    scopes: &ScopeDataWithChildScopesData,
+    provider_scope: impl Fn(&TypeInfo) -> Option<Option<&'a ScopeData>>,
+) -> Result<(), ValidationErrorKind> {
+    for FacadeMethod { name, type_info } in methods {
+        match (provider_scope(type_info), &scope) {
+            (None, _) => {
+                return Err(ValidationErrorKind::MissingFacadeDependency {
+                    facade,
+                    method: name,
+                    dependency: type_info.clone(),
+                });
+            }
+            (Some(Some(dependency_scope)), Some(scope)) if !scopes.is_ancestor_or_self(dependency_scope, scope) => {
+                return Err(ValidationErrorKind::UnreachableFacadeDependency {
+                    facade,
+                    method: name,
+                    facade_scope: Box::new(scope.clone()),
+                    dependency: type_info.clone(),
+                    dependency_scope: Box::new(dependency_scope.clone()),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Expands to the implementation of a facade trait for the async container if the `async` feature is enabled
+#[cfg(feature = "async")]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! facade_async_impl {
+    ($($tokens:tt)*) => {
+        $($tokens)*
+    };
+}
+
+#[cfg(not(feature = "async"))]
+#[macro_export]
+#[doc(hidden)]
+macro_rules! facade_async_impl {
+    ($($tokens:tt)*) => {};
+}
@@ -0,0 +1,553 @@
+use alloc::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::Write as _;
+
+use crate::{
+    any::{short_type_name, TypeInfo},
+    dependency::Dependency,
+    errors::ValidationErrorKind,
+    scope::ScopeData,
+};
+
+/// Wiring of a registry: its scopes, providers and their dependencies.
+///
+/// Built by [`crate::Registry::manifest`], rendered by [`Self::to_dot`] and [`Self::to_mermaid`],
+/// and serializable with the `serde` feature.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegistryManifest {
+    pub scopes: Vec<ScopeData>,
+    pub providers: Vec<ProviderManifest>,
+    /// Dependencies declared as supplied from outside the registry, or supplied by the container itself
+    pub externals: Vec<&'static str>,
+    /// Dependencies that aren't provided by the registry
+    pub missing: Vec<&'static str>,
+    /// Validation error of the registry, its providers and dependencies are marked as `highlighted`
+    pub error: Option<String>,
+    /// Types of [`Self::externals`] and [`Self::missing`] in the same order
+    #[cfg_attr(feature = "serde", serde(skip))]
+    unprovided: Vec<TypeInfo>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ProviderManifest {
+    pub type_name: &'static str,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    type_info: TypeInfo,
+    pub scope: ScopeData,
+    pub is_async: bool,
+    pub has_finalizer: bool,
+    pub cache_provides: bool,
+    pub highlighted: bool,
+    pub dependencies: Vec<DependencyManifest>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DependencyManifest {
+    pub type_name: &'static str,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    type_info: TypeInfo,
+    pub is_transient: bool,
+    pub is_weak: bool,
+    pub highlighted: bool,
+}
+
+pub(crate) struct ProviderInfo<'a> {
+    pub(crate) type_info: &'a TypeInfo,
+    pub(crate) dependencies: &'a BTreeSet<Dependency>,
+    pub(crate) scope_data: &'a ScopeData,
+    pub(crate) is_async: bool,
+    pub(crate) has_finalizer: bool,
+    pub(crate) cache_provides: bool,
+}
+
+impl RegistryManifest {
+    pub(crate) fn new<'a>(
+        mut scopes: Vec<ScopeData>,
+        providers: impl IntoIterator<Item = ProviderInfo<'a>>,
+        is_provided: impl Fn(&TypeInfo) -> bool,
+        validation: Result<(), ValidationErrorKind>,
+    ) -> Self {
+        scopes.sort_by_key(|scope| scope.priority);
+        scopes.dedup();
+
+        let error = validation.err();
+        // Highlighted edges as `(dependent, dependency)` and highlighted nodes
+        let (edges, nodes): (Vec<(&TypeInfo, &TypeInfo)>, Vec<&TypeInfo>) = match &error {
+            Some(ValidationErrorKind::CyclicDependency { graph: (first, rest) }) => {
+                let cycle: Vec<&TypeInfo> = core::iter::once(first).chain(rest.iter()).collect();
+                let edges = cycle
+                    .iter()
+                    .enumerate()
+                    .map(|(index, type_info)| (*type_info, cycle[(index + 1) % cycle.len()]))
+                    .collect();
+                (edges, cycle)
+            }
+            Some(
+                ValidationErrorKind::UnreachableDependency { dependent, dependency, .. }
+                | ValidationErrorKind::MissingDependency { dependent, dependency },
+            ) => (alloc::vec![(dependent, dependency)], alloc::vec![dependent]),
+            Some(
+                ValidationErrorKind::DuplicateProvider { type_info, .. }
+                | ValidationErrorKind::MissingFacadeDependency { dependency: type_info, .. }
+                | ValidationErrorKind::UnreachableFacadeDependency { dependency: type_info, .. },
+            ) => (Vec::new(), alloc::vec![type_info]),
+            None => (Vec::new(), Vec::new()),
+        };
+
+        let providers: Vec<ProviderInfo<'_>> = providers.into_iter().collect();
+        let mut externals = Vec::new();
+        let mut missing = Vec::new();
+        let providers = providers
+            .iter()
+            .map(|provider| {
+                let dependencies = provider
+                    .dependencies
+                    .iter()
+                    .map(|dependency @ Dependency { type_info, .. }| {
+                        let is_node = providers.iter().any(|provider| provider.type_info == type_info)
+                            || externals.contains(&type_info)
+                            || missing.contains(&type_info);
+                        if !is_node {
+                            if is_provided(type_info) {
+                                externals.push(type_info);
+                            } else {
+                                missing.push(type_info);
+                            }
+                        }
+                        DependencyManifest {
+                            type_name: type_info.name,
+                            type_info: type_info.clone(),
+                            is_transient: dependency.is_transient,
+                            is_weak: dependency.is_weak,
+                            highlighted: edges.contains(&(provider.type_info, type_info)),
+                        }
+                    })
+                    .collect();
+                ProviderManifest {
+                    type_name: provider.type_info.name,
+                    type_info: provider.type_info.clone(),
+                    scope: provider.scope_data.clone(),
+                    is_async: provider.is_async,
+                    has_finalizer: provider.has_finalizer,
+                    cache_provides: provider.cache_provides,
+                    highlighted: nodes.contains(&provider.type_info),
+                    dependencies,
+                }
+            })
+            .collect();
+
+        Self {
+            scopes,
+            providers,
+            externals: externals.iter().map(|type_info| type_info.name).collect(),
+            missing: missing.iter().map(|type_info| type_info.name).collect(),
+            error: error.as_ref().map(ToString::to_string),
+            unprovided: externals.into_iter().chain(missing).cloned().collect(),
+        }
+    }
+
+    /// Renders the manifest in the Graphviz DOT format.
+    ///
+    /// Scopes are clusters, async providers are rounded and filled, providers with a finalizer have a double border,
+    /// transient dependencies are dashed, weak ones end with a circle, and providers and dependencies from the validation error are red.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let ids = self.node_ids();
+        let mut dot = String::from("digraph registry {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (index, scope) in self.scopes.iter().enumerate() {
+            let _ = writeln!(dot, "    subgraph cluster_{index} {{\n        label=\"{}\";", escape(&scope.name));
+            for (id, provider) in self.providers_in(scope) {
+                let mut attrs = format!("label=\"{}\"", escape(short_type_name(provider.type_name)));
+                if provider.is_async {
+                    attrs.push_str(", style=\"rounded,filled\", fillcolor=lightblue");
+                }
+                if provider.has_finalizer {
+                    attrs.push_str(", peripheries=2");
+                }
+                if provider.highlighted {
+                    attrs.push_str(", color=red, penwidth=2");
+                }
+                let _ = writeln!(dot, "        {id} [{attrs}];");
+            }
+            dot.push_str("    }\n");
+        }
+        for (index, name) in self.externals.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\", style=dashed];",
+                self.unprovided_id(index),
+                escape(short_type_name(name))
+            );
+        }
+        for (index, name) in self.missing.iter().enumerate() {
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\", style=dashed, color=red];",
+                self.unprovided_id(self.externals.len() + index),
+                escape(short_type_name(name))
+            );
+        }
+        for provider in &self.providers {
+            for dependency in &provider.dependencies {
+                let mut attrs = Vec::new();
+                if dependency.is_transient {
+                    attrs.push("style=dashed");
+                }
+                if dependency.is_weak {
+                    attrs.push("arrowhead=odot");
+                }
+                if dependency.highlighted {
+                    attrs.push("color=red, penwidth=2");
+                }
+                let _ = write!(
+                    dot,
+                    "    {} -> {}",
+                    ids[&provider.node_key()],
+                    dependency_id(&ids, provider, dependency)
+                );
+                if !attrs.is_empty() {
+                    let _ = write!(dot, " [{}]", attrs.join(", "));
+                }
+                dot.push_str(";\n");
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the manifest as a Mermaid flowchart.
+    ///
+    /// Scopes are subgraphs, async providers are stadium-shaped, providers with a finalizer have a thick border,
+    /// transient dependencies are dotted, weak ones end with a circle, and providers and dependencies from the validation error are red.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        let ids = self.node_ids();
+        let mut mermaid = String::from("flowchart LR\n");
+        let mut classes = Vec::new();
+        for (index, scope) in self.scopes.iter().enumerate() {
+            let _ = writeln!(mermaid, "    subgraph scope_{index}[\"{}\"]", escape(&scope.name));
+            for (id, provider) in self.providers_in(scope) {
+                let label = escape(short_type_name(provider.type_name));
+                if provider.is_async {
+                    let _ = writeln!(mermaid, "        {id}([\"{label}\"])");
+                    classes.push((id.clone(), "async"));
+                } else {
+                    let _ = writeln!(mermaid, "        {id}[\"{label}\"]");
+                }
+                if provider.has_finalizer {
+                    classes.push((id.clone(), "finalizer"));
+                }
+                if provider.highlighted {
+                    classes.push((id.clone(), "error"));
+                }
+            }
+            mermaid.push_str("    end\n");
+        }
+        for (index, name) in self.externals.iter().enumerate() {
+            let id = self.unprovided_id(index);
+            let _ = writeln!(mermaid, "    {id}[\"{}\"]", escape(short_type_name(name)));
+            classes.push((id, "external"));
+        }
+        for (index, name) in self.missing.iter().enumerate() {
+            let id = self.unprovided_id(self.externals.len() + index);
+            let _ = writeln!(mermaid, "    {id}[\"{}\"]", escape(short_type_name(name)));
+            classes.push((id, "missing"));
+        }
+
+        let mut highlighted_links = Vec::new();
+        let mut link_index = 0;
+        for provider in &self.providers {
+            for dependency in &provider.dependencies {
+                let arrow = if dependency.is_weak {
+                    "--o"
+                } else if dependency.is_transient {
+                    "-.->"
+                } else {
+                    "-->"
+                };
+                let _ = writeln!(
+                    mermaid,
+                    "    {} {arrow} {}",
+                    ids[&provider.node_key()],
+                    dependency_id(&ids, provider, dependency)
+                );
+                if dependency.highlighted {
+                    highlighted_links.push(link_index);
+                }
+                link_index += 1;
+            }
+        }
+
+        mermaid.push_str(
+            "    classDef async fill:#dbeafe\n    \
+             classDef finalizer stroke-width:3px\n    \
+             classDef error stroke:#dc2626,stroke-width:2px\n    \
+             classDef external stroke-dasharray:4\n    \
+             classDef missing stroke:#dc2626,stroke-dasharray:4\n",
+        );
+        for (id, class) in classes {
+            let _ = writeln!(mermaid, "    class {id} {class}");
+        }
+        for link_index in highlighted_links {
+            let _ = writeln!(mermaid, "    linkStyle {link_index} stroke:#dc2626,stroke-width:2px");
+        }
+        mermaid
+    }
+
+    /// Serializes the manifest to JSON
+    #[cfg(feature = "serde")]
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("manifest serialization is infallible")
+    }
+
+    /// Node ids by type: providers of the async and the sync registry of a type are separate nodes
+    fn node_ids(&self) -> BTreeMap<NodeKey<'_>, String> {
+        self.providers
+            .iter()
+            .map(ProviderManifest::node_key)
+            .chain(self.unprovided.iter().map(NodeKey::Unprovided))
+            .enumerate()
+            .map(|(index, key)| (key, format!("n{index}")))
+            .collect()
+    }
+
+    /// Id of an external or missing dependency by its index in [`Self::unprovided`]
+    fn unprovided_id(&self, index: usize) -> String {
+        format!("n{}", self.providers.len() + index)
+    }
+
+    fn providers_in<'a>(&'a self, scope: &'a ScopeData) -> impl Iterator<Item = (String, &'a ProviderManifest)> + 'a {
+        self.providers
+            .iter()
+            .enumerate()
+            .filter(move |(_, provider)| provider.scope == *scope)
+            .map(|(index, provider)| (format!("n{index}"), provider))
+    }
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum NodeKey<'a> {
+    /// Provider of a type and whether it's async
+    Provider(&'a TypeInfo, bool),
+    Unprovided(&'a TypeInfo),
+}
+
+impl ProviderManifest {
+    fn node_key(&self) -> NodeKey<'_> {
+        NodeKey::Provider(&self.type_info, self.is_async)
+    }
+}
+
+/// Id of the node a dependency points at. The provider of the same kind as the dependent is preferred,
+/// as async providers are resolved by the async registry first and sync ones only by the sync registry
+fn dependency_id<'a>(ids: &'a BTreeMap<NodeKey<'a>, String>, provider: &ProviderManifest, dependency: &'a DependencyManifest) -> &'a str {
+    let type_info = &dependency.type_info;
+    ids.get(&NodeKey::Provider(type_info, provider.is_async))
+        .or_else(|| ids.get(&NodeKey::Provider(type_info, !provider.is_async)))
+        .or_else(|| ids.get(&NodeKey::Unprovided(type_info)))
+        .expect("every dependency should be a node")
+}
+
+fn escape(value: &str) -> String {
+    value.replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::{
+        format,
+        string::{String, ToString as _},
+    };
+    use tracing_test::traced_test;
+
+    use crate::{registry, utils::Merge as _, DefaultScope, Inject, InjectTransient, InjectWeak};
+
+    struct A;
+    struct B;
+    struct FromContext;
+
+    #[test]
+    #[traced_test]
+    fn test_manifest_describes_providers() {
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(|| Ok(A), finalizer = |_: crate::utils::thread_safety::RcThreadSafety<A>| {}),
+            ],
+            scope(DefaultScope::Request) [
+                provide(|Inject(_): Inject<A>, InjectTransient(_): InjectTransient<FromContext>| Ok(B)),
+            ],
+        }
+        .with_external::<FromContext>();
+        let manifest = registry.manifest();
+
+        assert!(manifest.error.is_none());
+        assert_eq!(manifest.externals, [core::any::type_name::<FromContext>()]);
+        assert!(manifest.missing.is_empty());
+
+        let a = manifest
+            .providers
+            .iter()
+            .find(|provider| provider.type_name.ends_with("::A"))
+            .unwrap();
+        assert!(a.has_finalizer && a.cache_provides && !a.is_async);
+        assert_eq!(a.scope, DefaultScope::App.into());
+
+        let b = manifest
+            .providers
+            .iter()
+            .find(|provider| provider.type_name.ends_with("::B"))
+            .unwrap();
+        assert_eq!(b.dependencies.len(), 2);
+        assert!(b.dependencies.iter().any(|dependency| dependency.is_transient));
+
+        let dot = registry.to_dot();
+        assert!(dot.starts_with("digraph registry {"));
+        assert!(dot.contains("label=\"app\""));
+        assert!(dot.contains("[label=\"A\", peripheries=2]"));
+        assert!(dot.contains("[style=dashed]"));
+
+        let mermaid = registry.to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR"));
+        assert!(mermaid.contains("[\"request\"]"));
+        assert!(mermaid.contains("-.->"));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_manifest_highlights_cycle() {
+        let registry = registry! { provide(DefaultScope::App, |Inject(_): Inject<B>| Ok(A)) }
+            .merge(registry! { provide(DefaultScope::App, |Inject(_): Inject<A>| Ok(B)) });
+        let manifest = registry.manifest();
+
+        assert!(manifest.error.as_ref().unwrap().starts_with("Cyclic dependency"));
+        for type_name in [core::any::type_name::<A>(), core::any::type_name::<B>()] {
+            let provider = manifest.providers.iter().find(|provider| provider.type_name == type_name).unwrap();
+            assert!(provider.highlighted);
+            assert!(provider.dependencies.iter().all(|dependency| dependency.highlighted));
+        }
+        assert_eq!(registry.to_dot().matches("color=red, penwidth=2").count(), 4);
+        assert_eq!(registry.to_mermaid().matches("linkStyle").count(), 2);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_manifest_marks_weak_cycle() {
+        let registry = registry! { provide(DefaultScope::App, |Inject(_): Inject<B>| Ok(A)) }
+            .merge(registry! { provide(DefaultScope::App, |_: InjectWeak<A>| Ok(B)) });
+        let manifest = registry.manifest();
+
+        assert!(manifest.error.is_none());
+        assert!(registry.to_dot().contains("[arrowhead=odot]"));
+        assert_eq!(registry.to_mermaid().matches("--o").count(), 1);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_manifest_marks_missing_dependency() {
+        let registry = registry! { provide(DefaultScope::App, |Inject(_): Inject<B>| Ok(A)) };
+        let manifest = registry.manifest();
+
+        assert_eq!(manifest.missing, [core::any::type_name::<B>()]);
+        assert!(registry.to_dot().contains("[label=\"B\", style=dashed, color=red]"));
+        assert!(registry.to_mermaid().contains("class n1 missing"));
+    }
+
+    #[test]
+    #[traced_test]
+    #[cfg(feature = "async")]
+    fn test_manifest_marks_async_providers() {
+        use crate::InstantiateErrorKind;
+
+        async fn inst_b(Inject(_): Inject<A>) -> Result<B, InstantiateErrorKind> {
+            Ok(B)
+        }
+
+        let registry = crate::async_registry! {
+            provide(DefaultScope::Request, inst_b),
+            extend(registry! { provide(DefaultScope::App, || Ok(A)) }),
+        };
+        let manifest = registry.manifest();
+
+        assert!(manifest.error.is_none());
+        let b = manifest
+            .providers
+            .iter()
+            .find(|provider| provider.type_name.ends_with("::B"))
+            .unwrap();
+        assert!(b.is_async);
+        assert!(registry.to_dot().contains("style=\"rounded,filled\""));
+        assert!(registry.to_mermaid().contains("([\"B\"])"));
+    }
+
+    #[test]
+    #[traced_test]
+    #[cfg(feature = "async")]
+    fn test_manifest_separates_async_and_sync_providers_of_type() {
+        use crate::InstantiateErrorKind;
+
+        struct C;
+
+        async fn inst_a() -> Result<A, InstantiateErrorKind> {
+            Ok(A)
+        }
+
+        async fn inst_b(Inject(_): Inject<A>) -> Result<B, InstantiateErrorKind> {
+            Ok(B)
+        }
+
+        let registry = crate::async_registry! {
+            provide(DefaultScope::App, inst_a),
+            provide(DefaultScope::App, inst_b),
+            extend(registry! {
+                provide(DefaultScope::App, || Ok(A)),
+                provide(DefaultScope::App, |Inject(_): Inject<A>| Ok(C)),
+            }),
+        };
+        let manifest = registry.manifest();
+        let id = |type_name: &str, is_async: bool| {
+            let index = manifest
+                .providers
+                .iter()
+                .position(|provider| provider.type_name == type_name && provider.is_async == is_async)
+                .unwrap();
+            format!("n{index}")
+        };
+        let (async_a, sync_a) = (id(core::any::type_name::<A>(), true), id(core::any::type_name::<A>(), false));
+        let (b, c) = (id(core::any::type_name::<B>(), true), id(core::any::type_name::<C>(), false));
+
+        let dot = registry.to_dot();
+        assert_eq!(dot.matches("[label=\"A\"").count(), 2);
+        assert!(dot.contains(&format!("{b} -> {async_a};")));
+        assert!(dot.contains(&format!("{c} -> {sync_a};")));
+        // The built-in containers aren't nodes
+        assert!(!dot.contains("label=\"Container\""));
+
+        let mermaid = registry.to_mermaid();
+        assert!(mermaid.contains(&format!("{b} --> {async_a}")));
+        assert!(mermaid.contains(&format!("{c} --> {sync_a}")));
+    }
+
+    #[test]
+    #[traced_test]
+    #[cfg(feature = "serde")]
+    fn test_manifest_to_json() {
+        let registry = registry! { provide(DefaultScope::App, |Inject(_): Inject<B>| Ok(A)) };
+        let json = registry.to_json();
+
+        assert!(json.starts_with("{\"scopes\":[{\"priority\":0,\"name\":\"runtime\",\"is_skipped_by_default\":true,\"parent\":null}"));
+        assert!(json.contains(&format!("\"missing\":[\"{}\"]", core::any::type_name::<B>())));
+        assert!(json.contains("\"error\":\"Missing dependency"));
+    }
+}
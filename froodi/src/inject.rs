@@ -3,7 +3,10 @@ use crate::async_impl::Container as AsyncContainer;
 use crate::{
     any::TypeInfo,
     dependency_resolver::DependencyResolver,
-    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
+    utils::{
+        lock::Mutex,
+        thread_safety::{RcThreadSafety, SendSafety, SyncSafety, WeakThreadSafety},
+    },
     Container, ResolveErrorKind,
 };
 
@@ -49,4 +52,71 @@ impl<Dep: 'static> DependencyResolver for InjectTransient<Dep> {
     fn type_info() -> TypeInfo {
         TypeInfo::of::<Dep>()
     }
+
+    #[inline]
+    fn is_transient() -> bool {
+        true
+    }
+}
+
+/// Weak reference to the cached instance of a dependency, which doesn't keep it alive.
+///
+/// Unlike [`Inject`], the dependency isn't instantiated: the reference points at the instance cached by the container
+/// of its scope, or at the one cached next if there is none yet. So a dependency can point back at its dependent,
+/// and cycles through weak dependencies pass [`crate::Registry::validate`].
+/// Instances of providers with [`crate::Config::cache_provides`] disabled are never cached, so they're never referenced.
+pub struct InjectWeak<Dep>(pub(crate) RcThreadSafety<Mutex<WeakThreadSafety<Dep>>>);
+
+impl<Dep> InjectWeak<Dep> {
+    #[inline]
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self(RcThreadSafety::new(Mutex::new(WeakThreadSafety::new())))
+    }
+
+    /// Returns the instance, or `None` if it isn't cached yet or is already dropped
+    #[inline]
+    #[must_use]
+    pub fn upgrade(&self) -> Option<RcThreadSafety<Dep>> {
+        self.0.lock().upgrade()
+    }
+
+    /// Returns the weak reference, which is dangling until the instance is cached
+    #[inline]
+    #[must_use]
+    pub fn weak(&self) -> WeakThreadSafety<Dep> {
+        self.0.lock().clone()
+    }
+}
+
+impl<Dep> Clone for InjectWeak<Dep> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<Dep: SendSafety + SyncSafety + 'static> DependencyResolver for InjectWeak<Dep> {
+    type Error = ResolveErrorKind;
+
+    #[inline]
+    fn resolve(container: &Container) -> Result<Self, Self::Error> {
+        container.get_weak()
+    }
+
+    #[inline]
+    #[cfg(feature = "async")]
+    async fn resolve_async(container: &AsyncContainer) -> Result<Self, Self::Error> {
+        container.get_weak()
+    }
+
+    #[inline]
+    fn type_info() -> TypeInfo {
+        TypeInfo::of::<Dep>()
+    }
+
+    #[inline]
+    fn is_weak() -> bool {
+        true
+    }
 }
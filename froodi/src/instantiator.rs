@@ -8,8 +8,9 @@ use super::{
     service::{service_fn, BoxCloneService},
 };
 use crate::{
+    any::TypeInfo,
     dependency::Dependency,
-    utils::thread_safety::{SendSafety, SyncSafety},
+    utils::thread_safety::{RcAnyThreadSafety, RcThreadSafety, SendSafety, SyncSafety},
     Container, ResolveErrorKind,
 };
 
@@ -26,17 +27,61 @@ where
     fn dependencies() -> BTreeSet<Dependency>;
 }
 
+/// Instance created by an instantiator.
+/// Scoped dependencies are created shared and transient ones owned, so the container never moves them to another allocation.
+#[derive(Debug)]
+pub(crate) enum Instance {
+    Shared(RcAnyThreadSafety),
+    Owned(Box<dyn Any>),
+}
+
+impl Instance {
+    /// # Errors
+    /// Returns the instance back if it's not `T`
+    #[inline]
+    pub(crate) fn downcast_shared<T: SendSafety + SyncSafety + 'static>(self) -> Result<RcThreadSafety<T>, Self> {
+        match self {
+            Self::Shared(dependency) => dependency.downcast().map_err(Self::Shared),
+            Self::Owned(dependency) => match dependency.downcast() {
+                Ok(dependency) => Ok(RcThreadSafety::new(*dependency)),
+                Err(dependency) => Err(Self::Owned(dependency)),
+            },
+        }
+    }
+
+    /// # Errors
+    /// Returns the instance back if it's not `T` or it's shared,
+    /// instantiators create owned instances when they're requested
+    #[inline]
+    pub(crate) fn downcast_owned<T: 'static>(self) -> Result<T, Self> {
+        match self {
+            Self::Owned(dependency) => dependency.downcast().map(|dependency| *dependency).map_err(Self::Owned),
+            dependency @ Self::Shared(_) => Err(dependency),
+        }
+    }
+
+    #[must_use]
+    pub(crate) fn type_info(&self) -> TypeInfo {
+        match self {
+            Self::Shared(dependency) => TypeInfo::of_val(&**dependency),
+            Self::Owned(dependency) => TypeInfo::of_val(&**dependency),
+        }
+    }
+}
+
+/// Instantiator called with the container and whether the instance should be shared
 pub(crate) type BoxedCloneInstantiator<DepsErr, FactoryErr> =
-    BoxCloneService<Container, Box<dyn Any>, InstantiatorErrorKind<DepsErr, FactoryErr>>;
+    BoxCloneService<(Container, bool), Instance, InstantiatorErrorKind<DepsErr, FactoryErr>>;
 
 #[must_use]
 pub(crate) fn boxed_instantiator<Inst, Deps>(instantiator: Inst) -> BoxedCloneInstantiator<Deps::Error, Inst::Error>
 where
     Inst: Instantiator<Deps> + SendSafety + SyncSafety,
+    Inst::Provides: SendSafety + SyncSafety,
     Deps: DependencyResolver,
 {
     BoxCloneService(Box::new(service_fn({
-        move |container| {
+        move |(container, shared): (Container, bool)| {
             let dependencies = match Deps::resolve(&container) {
                 Ok(dependencies) => dependencies,
                 Err(err) => return Err(InstantiatorErrorKind::Deps(err)),
@@ -48,14 +93,24 @@ where
 
             debug!("Resolved");
 
-            Ok(Box::new(dependency) as _)
+            Ok(if shared {
+                Instance::Shared(RcThreadSafety::new(dependency))
+            } else {
+                Instance::Owned(Box::new(dependency))
+            })
         }
     })))
 }
 
 #[must_use]
 pub(crate) fn boxed_container_instantiator() -> BoxedCloneInstantiator<ResolveErrorKind, InstantiateErrorKind> {
-    BoxCloneService(Box::new(service_fn(move |container| Ok(Box::new(container) as _))))
+    BoxCloneService(Box::new(service_fn(move |(container, shared): (Container, bool)| {
+        Ok(if shared {
+            Instance::Shared(RcThreadSafety::new(container))
+        } else {
+            Instance::Owned(Box::new(container))
+        })
+    })))
 }
 
 macro_rules! impl_instantiator {
@@ -84,6 +139,8 @@ macro_rules! impl_instantiator {
                     $(
                         Dependency {
                             type_info: $ty::type_info(),
+                            is_transient: $ty::is_transient(),
+                            is_weak: $ty::is_weak(),
                         }
                     ),*
                 ])
@@ -199,11 +256,11 @@ mod tests {
             ]
         });
 
-        let response_1 = instantiator_response.call(container.clone()).unwrap();
-        let response_2 = instantiator_response.call(container).unwrap();
+        let response_1 = instantiator_response.call((container.clone(), false)).unwrap();
+        let response_2 = instantiator_response.call((container, true)).unwrap();
 
-        assert!(response_1.downcast::<Response>().unwrap().0);
-        assert!(response_2.downcast::<Response>().unwrap().0);
+        assert!(response_1.downcast_owned::<Response>().unwrap().0);
+        assert!(response_2.downcast_shared::<Response>().unwrap().0);
         assert_eq!(instantiator_request_call_count.load(Ordering::SeqCst), 4);
         assert_eq!(instantiator_response_call_count.load(Ordering::SeqCst), 2);
     }
@@ -240,13 +297,13 @@ mod tests {
             ]
         });
 
-        let response_1 = instantiator_response.call(container.clone()).unwrap();
-        let response_2 = instantiator_response.call(container.clone()).unwrap();
-        let response_3 = instantiator_response.call(container).unwrap();
+        let response_1 = instantiator_response.call((container.clone(), true)).unwrap();
+        let response_2 = instantiator_response.call((container.clone(), true)).unwrap();
+        let response_3 = instantiator_response.call((container, false)).unwrap();
 
-        assert!(response_1.downcast::<Response>().unwrap().0);
-        assert!(response_2.downcast::<Response>().unwrap().0);
-        assert!(response_3.downcast::<Response>().unwrap().0);
+        assert!(response_1.downcast_shared::<Response>().unwrap().0);
+        assert!(response_2.downcast_shared::<Response>().unwrap().0);
+        assert!(response_3.downcast_owned::<Response>().unwrap().0);
         assert_eq!(instantiator_request_call_count.load(Ordering::SeqCst), 1);
         // We don't cache instantiator provides of main factory here, we do it in container
         assert_eq!(instantiator_response_call_count.load(Ordering::SeqCst), 3);
@@ -1,45 +1,71 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 #[macro_use]
 pub(crate) mod macros;
 
 pub(crate) mod any;
 pub(crate) mod cache;
+pub(crate) mod clock;
 pub(crate) mod config;
+pub(crate) mod conflict;
 pub(crate) mod container;
 pub(crate) mod context;
 pub(crate) mod dependency;
 pub(crate) mod dependency_resolver;
 pub(crate) mod errors;
+pub(crate) mod facade;
 pub(crate) mod finalizer;
+pub(crate) mod graph;
 pub(crate) mod inject;
 pub(crate) mod instantiator;
 pub(crate) mod integrations;
+pub(crate) mod lint;
 pub(crate) mod lock;
+pub(crate) mod plan;
 pub(crate) mod registry;
 pub(crate) mod scope;
 pub(crate) mod service;
+pub(crate) mod slot;
+pub(crate) mod snapshot;
+pub(crate) mod static_container;
 
 pub mod macros_utils;
+pub mod typed;
 pub mod utils;
 
 #[cfg(feature = "async")]
 pub mod async_impl;
 
 pub use any::TypeInfo;
-pub use config::Config;
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use clock::SystemClock;
+pub use config::{Config, Refresh, RetryPolicy};
+pub use conflict::{ConflictPolicy, RegistrationSite};
 pub use container::Container;
 pub use context::Context;
 pub use dependency::Dependency;
 pub use dependency_resolver::DependencyResolver;
-pub use errors::{InstantiateErrorKind, InstantiatorResult, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind};
+pub use errors::{InstantiateErrorKind, InstantiatorResult, ResolveErrorKind, ScopeErrorKind, ScopeWithErrorKind, ValidationErrorKind};
+pub use facade::{FacadeMethod, FacadeRegistry};
 pub use finalizer::Finalizer;
-pub use inject::{Inject, InjectTransient};
+pub use graph::{DependencyManifest, ProviderManifest, RegistryManifest};
+pub use inject::{Inject, InjectTransient, InjectWeak};
 pub use instantiator::{instance, Instantiator};
+pub use lint::LintWarning;
+pub use plan::{PlanProblem, ResolutionPlan};
 pub use registry::{InstantiatorData, Registry};
-pub use scope::{DefaultScope, Scope, Scopes};
+pub use scope::{DefaultScope, Scope, ScopeData, Scopes};
+pub use snapshot::{ContainerSnapshot, LevelSnapshot};
+pub use static_container::{StaticDependencyResolver, StaticResolve, StaticResolveTransient};
+pub use typed::TypedContainer;
+
+#[cfg(feature = "macros")]
+pub use froodi_macros::{facade, static_container, static_registry, Scope};
 
 #[cfg(feature = "axum")]
 pub use integrations::axum;
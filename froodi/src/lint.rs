@@ -0,0 +1,162 @@
+use alloc::{collections::btree_map::BTreeMap, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+
+use crate::{
+    any::TypeInfo,
+    dependency::Dependency,
+    registry::{InstantiatorData, Registry},
+    scope::ScopeData,
+    Container,
+};
+
+/// A legal but likely wrong registry configuration, reported by [`Registry::lint`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintWarning {
+    /// The type has a finalizer, but every provider depending on it injects it with [`crate::InjectTransient`],
+    /// which doesn't register finalizers
+    FinalizerOnTransientOnly { type_info: TypeInfo, scope_data: ScopeData },
+    /// The type has a finalizer and isn't cached, so every resolution creates a new instance
+    /// that's kept alive until the container is closed
+    FinalizerWithoutCache { type_info: TypeInfo, scope_data: ScopeData },
+    /// No other provider depends on the type. It's expected for types resolved directly from a container
+    UnusedProvider { type_info: TypeInfo, scope_data: ScopeData },
+    /// The scope has no providers. Scopes skipped by default aren't reported
+    EmptyScope { scope_data: ScopeData },
+    /// The retry policy of the type has a backoff, but without the `std` feature sync instantiators wait with
+    /// [`crate::Clock::sleep`] and the registry has no clock, so the attempts are made without delay
+    RetryDelayWithoutClock { type_info: TypeInfo, scope_data: ScopeData },
+}
+
+impl LintWarning {
+    #[inline]
+    #[must_use]
+    pub const fn type_info(&self) -> Option<&TypeInfo> {
+        match self {
+            Self::FinalizerOnTransientOnly { type_info, .. }
+            | Self::FinalizerWithoutCache { type_info, .. }
+            | Self::UnusedProvider { type_info, .. }
+            | Self::RetryDelayWithoutClock { type_info, .. } => Some(type_info),
+            Self::EmptyScope { .. } => None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn scope_data(&self) -> &ScopeData {
+        match self {
+            Self::FinalizerOnTransientOnly { scope_data, .. }
+            | Self::FinalizerWithoutCache { scope_data, .. }
+            | Self::UnusedProvider { scope_data, .. }
+            | Self::RetryDelayWithoutClock { scope_data, .. }
+            | Self::EmptyScope { scope_data } => scope_data,
+        }
+    }
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FinalizerOnTransientOnly { type_info, scope_data } => write!(
+                f,
+                "`{}` (scope `{}`) has a finalizer, but it's only injected as transient, so the finalizer never runs",
+                type_info.short_name(),
+                scope_data.name,
+            ),
+            Self::FinalizerWithoutCache { type_info, scope_data } => write!(
+                f,
+                "`{}` (scope `{}`) has a finalizer with `cache_provides: false`, so every resolution keeps a new instance \
+                 alive until the container is closed",
+                type_info.short_name(),
+                scope_data.name,
+            ),
+            Self::UnusedProvider { type_info, scope_data } => write!(
+                f,
+                "`{}` (scope `{}`) isn't a dependency of any other provider",
+                type_info.short_name(),
+                scope_data.name,
+            ),
+            Self::EmptyScope { scope_data } => write!(f, "Scope `{}` has no providers", scope_data.name),
+            Self::RetryDelayWithoutClock { type_info, scope_data } => write!(
+                f,
+                "`{}` (scope `{}`) retries with a backoff, but there's neither `std` nor a clock to wait with, \
+                 so the attempts are made without delay",
+                type_info.short_name(),
+                scope_data.name,
+            ),
+        }
+    }
+}
+
+pub(crate) fn lint(registry: &Registry) -> Vec<LintWarning> {
+    let container_type_info = TypeInfo::of::<Container>();
+    // For every type, whether each of its dependents injects it as transient
+    let mut dependents: BTreeMap<&TypeInfo, Vec<bool>> = BTreeMap::new();
+    for InstantiatorData { dependencies, .. } in registry.entries.values() {
+        for Dependency {
+            type_info, is_transient, ..
+        } in dependencies
+        {
+            dependents.entry(type_info).or_default().push(*is_transient);
+        }
+    }
+
+    let mut warnings = Vec::new();
+    for (
+        type_info,
+        InstantiatorData {
+            finalizer,
+            config,
+            scope_data,
+            ..
+        },
+    ) in &registry.entries
+    {
+        if *type_info == container_type_info {
+            continue;
+        }
+        let dependents = dependents.get(type_info);
+        if finalizer.is_some() {
+            if dependents.is_some_and(|dependents| dependents.iter().all(|is_transient| *is_transient)) {
+                warnings.push(LintWarning::FinalizerOnTransientOnly {
+                    type_info: type_info.clone(),
+                    scope_data: scope_data.clone(),
+                });
+            }
+            if !config.cache_provides {
+                warnings.push(LintWarning::FinalizerWithoutCache {
+                    type_info: type_info.clone(),
+                    scope_data: scope_data.clone(),
+                });
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        if registry.clock.is_none() && config.retry.max_attempts > 1 && !config.retry.backoff.is_zero() {
+            warnings.push(LintWarning::RetryDelayWithoutClock {
+                type_info: type_info.clone(),
+                scope_data: scope_data.clone(),
+            });
+        }
+        if dependents.is_none() {
+            warnings.push(LintWarning::UnusedProvider {
+                type_info: type_info.clone(),
+                scope_data: scope_data.clone(),
+            });
+        }
+    }
+
+    for scope_data in &registry.scopes_data {
+        if scope_data.is_skipped_by_default {
+            continue;
+        }
+        let has_providers = registry
+            .entries
+            .iter()
+            .any(|(type_info, data)| data.scope_data == *scope_data && *type_info != container_type_info);
+        if !has_providers {
+            warnings.push(LintWarning::EmptyScope {
+                scope_data: scope_data.clone(),
+            });
+        }
+    }
+    warnings
+}
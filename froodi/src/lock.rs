@@ -7,7 +7,7 @@
 //! intentionally not pruned: doing so on the hot `close()` path would add write-lock contention on
 //! the shared map for negligible gain.
 //!
-//! The sync container uses `parking_lot::Mutex` and the async container uses `tokio::sync::Mutex`;
+//! The sync container uses the `Mutex` of the selected lock backend and the async container uses the `AsyncMutex` of the async backend;
 //! both share the [`generic::TypeKeyedLocks`] map below (the primitive only differs at the call
 //! site, where the sync side `lock()`s and the async side `lock().await`s).
 
@@ -16,9 +16,7 @@ mod generic {
     use alloc::collections::BTreeMap;
     use core::any::TypeId;
 
-    use parking_lot::RwLock;
-
-    use crate::utils::thread_safety::RcThreadSafety;
+    use crate::utils::{lock::RwLock, thread_safety::RcThreadSafety};
 
     /// A `TypeId`-keyed registry of lazily-created locks of type `M`. Cloning shares the same map.
     pub(crate) struct TypeKeyedLocks<M> {
@@ -58,10 +56,10 @@ mod generic {
     }
 }
 
-/// Synchronous per-type instantiation locks (`parking_lot::Mutex`). See the module docs.
+/// Synchronous per-type instantiation locks (`Mutex` of the lock backend, see [`crate::utils::lock`]). See the module docs.
 #[cfg(feature = "thread_safe")]
-pub(crate) type PerTypeLocks = generic::TypeKeyedLocks<parking_lot::Mutex<()>>;
+pub(crate) type PerTypeLocks = generic::TypeKeyedLocks<crate::utils::lock::Mutex<()>>;
 
-/// Async per-type instantiation locks (`tokio::sync::Mutex`). See the module docs.
+/// Async per-type instantiation locks (`AsyncMutex` of the async backend, see [`crate::async_impl::runtime`]). See the module docs.
 #[cfg(feature = "async")]
-pub(crate) type PerTypeSharedLocks = generic::TypeKeyedLocks<tokio::sync::Mutex<()>>;
+pub(crate) type PerTypeSharedLocks = generic::TypeKeyedLocks<crate::async_impl::runtime::AsyncMutex<()>>;
@@ -4,4 +4,5 @@ pub mod types;
 #[cfg(feature = "async")]
 pub mod async_impl;
 
+pub mod static_container;
 pub mod sync;
@@ -1,4 +1,3 @@
-use alloc::collections::btree_map::BTreeMap;
 use core::{future::Future, pin::Pin};
 
 use crate::{
@@ -10,70 +9,96 @@ use crate::{
         registry::InstantiatorData,
         Finalizer, RegistryWithSync,
     },
+    conflict::{ProvideKind, Registration, RegistrationSite},
     dependency_resolver::DependencyResolver,
-    macros_utils::types::{RegistryKind, RegistryKindOrEntry},
+    macros_utils::types::RegistryKindOrEntry,
     utils::{
         hlist,
         thread_safety::{SendSafety, SyncSafety},
+        Merge as _,
     },
-    Config, InstantiateErrorKind, Registry, ResolveErrorKind, Scope, Scopes,
+    Config, InstantiateErrorKind, Registry, ResolveErrorKind, ScopeData,
 };
 
+use super::types::{entries_scopes_data, RegistryScopes};
+
 #[inline]
 #[must_use]
 #[doc(hidden)]
 pub fn build_registry<H, S, const N: usize>((_, iterable): (S, H)) -> RegistryWithSync
 where
-    S: Scope + Scopes<N, Scope = S>,
+    S: RegistryScopes<N>,
     H: hlist::IntoIterator<RegistryKindOrEntry>,
 {
-    let mut entries = BTreeMap::new();
-    let mut sync_entries = BTreeMap::new();
-    for registry_kind_or_entry in iterable.into_iter() {
-        match registry_kind_or_entry {
-            RegistryKindOrEntry::Kind(RegistryKind::Sync(registry)) => {
-                sync_entries.extend(registry.entries);
-            }
-            RegistryKindOrEntry::Kind(RegistryKind::Async(registry)) => {
-                entries.extend(registry.entries);
-            }
-            RegistryKindOrEntry::Kind(RegistryKind::AsyncWithSync(RegistryWithSync { registry, sync })) => {
-                entries.extend(registry.entries);
-                sync_entries.extend(sync.entries);
-            }
-            RegistryKindOrEntry::Entry((key, value)) => {
-                entries.insert(key, value);
-            }
-        }
-    }
+    let RegistryWithSync { registry, sync } = RegistryWithSync::default().merge(iterable);
+    let scopes_data = S::all_scopes_data().unwrap_or_else(|| {
+        entries_scopes_data(
+            registry
+                .entries
+                .values()
+                .map(|data| &data.scope_data)
+                .chain(sync.entries.values().map(|data| &data.scope_data)),
+        )
+    });
+    let mut sync_registry = Registry::with_scopes_data(sync.entries, scopes_data.clone());
+    sync_registry.externals = sync.externals;
+    sync_registry.conflict_policy = sync.conflict_policy;
+    sync_registry.duplicates = sync.duplicates;
+    let mut async_registry = async_impl::Registry::with_scopes_data(registry.entries, scopes_data);
+    async_registry.conflict_policy = registry.conflict_policy;
+    async_registry.duplicates = registry.duplicates;
     RegistryWithSync {
-        registry: async_impl::Registry::new::<S, S, N>(entries),
-        sync: Registry::new::<S, S, N>(sync_entries),
+        registry: async_registry,
+        sync: sync_registry,
     }
 }
 
 #[inline]
 #[must_use]
 #[doc(hidden)]
-pub fn make_entry<Inst, Deps, Fin>(scope: impl Scope, inst: Inst, config: Option<Config>, fin: Option<Fin>) -> (TypeInfo, InstantiatorData)
+#[track_caller]
+pub fn make_entry<Inst, Deps, Fin>(
+    scope: impl Into<ScopeData>,
+    inst: Inst,
+    config: Option<Config>,
+    fin: Option<Fin>,
+) -> (TypeInfo, InstantiatorData)
 where
     Inst: Instantiator<Deps, Error = InstantiateErrorKind> + SendSafety + SyncSafety,
     Inst::Provides: SendSafety + SyncSafety,
     Deps: DependencyResolver<Error = ResolveErrorKind>,
     Fin: Finalizer<Inst::Provides> + SendSafety + SyncSafety,
 {
+    let config = config.unwrap_or_default();
     (
         TypeInfo::of::<Inst::Provides>(),
         InstantiatorData {
             dependencies: Inst::dependencies(),
-            instantiator: boxed_instantiator(inst),
+            instantiator: boxed_instantiator(inst, config.resolve_dependencies_concurrently),
             finalizer: fin.map(boxed_finalizer_factory),
-            config: config.unwrap_or_default(),
+            config,
             scope_data: scope.into(),
+            registration: Registration {
+                kind: ProvideKind::Provide,
+                site: RegistrationSite::of::<Inst>(),
+            },
         },
     )
 }
 
+#[inline]
+#[must_use]
+#[doc(hidden)]
+pub fn with_kind(entry: RegistryKindOrEntry, kind: ProvideKind) -> RegistryKindOrEntry {
+    match entry {
+        RegistryKindOrEntry::Entry((key, mut value)) => {
+            value.registration.kind = kind;
+            RegistryKindOrEntry::Entry((key, value))
+        }
+        registry @ RegistryKindOrEntry::Kind(_) => registry,
+    }
+}
+
 #[cfg(feature = "thread_safe")]
 pub type FinDummy<T> = fn(T) -> Pin<super::aliases::Box<dyn Future<Output = ()> + Send>>;
 #[cfg(not(feature = "thread_safe"))]
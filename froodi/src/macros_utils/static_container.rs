@@ -0,0 +1,122 @@
+use alloc::{boxed::Box, vec::Vec};
+use tracing::warn;
+
+use crate::{
+    any::TypeInfo,
+    conflict::{ProvideKind, Registration, RegistrationSite},
+    errors::InstantiatorErrorKind,
+    instantiator::{Instance, Instantiator},
+    registry::InstantiatorData,
+    service::{service_fn, BoxCloneService},
+    static_container::StaticDependencyResolver,
+    utils::{
+        thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
+        Merge as _,
+    },
+    Config, Container, InstantiateErrorKind, Registry, ResolveErrorKind, ScopeData,
+};
+
+use super::types::{entries_scopes_data, RegistryScopes};
+
+pub use crate::static_container::{ResolvedOrder, Slot, StaticResolve, StaticResolveTransient};
+
+/// Calls the instantiator with dependencies resolved from the generated container,
+/// retrying its own failures by the retry policy of the config
+#[inline]
+#[doc(hidden)]
+#[allow(clippy::missing_errors_doc)]
+pub fn instantiate<C, Inst, Deps>(container: &C, mut instantiator: Inst, config: &Config) -> Result<Inst::Provides, ResolveErrorKind>
+where
+    Inst: Instantiator<Deps, Error = InstantiateErrorKind>,
+    Deps: StaticDependencyResolver<C>,
+{
+    let mut attempt = 1;
+    loop {
+        let dependencies =
+            Deps::resolve_static(container).map_err(|err| ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(Box::new(err))))?;
+        match instantiator.instantiate(dependencies) {
+            Ok(dependency) => return Ok(dependency),
+            Err(err) => {
+                if attempt >= config.retry.max_attempts {
+                    return Err(ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(err)));
+                }
+                warn!(attempt, error = %err, "Failed to instantiate, retrying");
+                #[cfg(feature = "std")]
+                std::thread::sleep(config.retry.delay(attempt));
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[inline]
+#[doc(hidden)]
+pub fn finalize<Dep, Fin>(mut finalizer: Fin, dependency: RcThreadSafety<Dep>)
+where
+    Fin: FnMut(RcThreadSafety<Dep>),
+{
+    finalizer(dependency);
+}
+
+/// Entry of a registry that resolves the dependency from the generated container,
+/// so the dynamic container shares its instances. Finalizers are left to the generated container
+#[inline]
+#[must_use]
+#[doc(hidden)]
+#[track_caller]
+pub fn make_entry<C, Dep, Inst, Deps>(
+    scope: impl Into<ScopeData>,
+    container: C,
+    _instantiator: &Inst,
+    config: Config,
+) -> (TypeInfo, InstantiatorData)
+where
+    C: StaticResolve<Dep> + StaticResolveTransient<Dep> + Clone + SendSafety + SyncSafety + 'static,
+    Dep: SendSafety + SyncSafety + 'static,
+    Inst: Instantiator<Deps, Provides = Dep, Error = InstantiateErrorKind>,
+    Deps: StaticDependencyResolver<C>,
+{
+    let instantiator = service_fn(move |(_, shared): (Container, bool)| {
+        let dependency = if shared {
+            container.resolve().map(|dependency| Instance::Shared(dependency))
+        } else {
+            container
+                .resolve_transient()
+                .map(|dependency| Instance::Owned(Box::new(dependency)))
+        };
+        // Errors are unwrapped, so the dynamic container reports them as its own
+        dependency.map_err(|err| match err {
+            ResolveErrorKind::Instantiator(InstantiatorErrorKind::Deps(err)) => InstantiatorErrorKind::Deps(*err),
+            ResolveErrorKind::Instantiator(InstantiatorErrorKind::Factory(err)) => InstantiatorErrorKind::Factory(err),
+            err => InstantiatorErrorKind::Deps(err),
+        })
+    });
+    (
+        TypeInfo::of::<Dep>(),
+        InstantiatorData {
+            dependencies: Inst::dependencies(),
+            instantiator: BoxCloneService(Box::new(instantiator)),
+            finalizer: None,
+            config,
+            scope_data: scope.into(),
+            registration: Registration {
+                kind: ProvideKind::Provide,
+                site: RegistrationSite::of::<Inst>(),
+            },
+        },
+    )
+}
+
+#[inline]
+#[must_use]
+#[doc(hidden)]
+pub fn build_registry<S, const N: usize>(_scope: &S, entries: Vec<(TypeInfo, InstantiatorData)>) -> Registry
+where
+    S: RegistryScopes<N>,
+{
+    let Registry { entries, .. } = entries
+        .into_iter()
+        .fold(Registry::default(), |registry, entry| registry.merge(entry));
+    let scopes_data = S::all_scopes_data().unwrap_or_else(|| entries_scopes_data(entries.values().map(|data| &data.scope_data)));
+    Registry::with_scopes_data(entries, scopes_data)
+}
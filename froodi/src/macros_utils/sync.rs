@@ -1,7 +1,6 @@
-use alloc::collections::btree_map::BTreeMap;
-
 use crate::{
     any::TypeInfo,
+    conflict::{ProvideKind, Registration, RegistrationSite},
     dependency_resolver::DependencyResolver,
     finalizer::boxed_finalizer_factory,
     instantiator::{boxed_instantiator, Instantiator},
@@ -10,36 +9,46 @@ use crate::{
     utils::{
         hlist,
         thread_safety::{SendSafety, SyncSafety},
+        Merge as _,
     },
-    Config, Finalizer, InstantiateErrorKind, Registry, ResolveErrorKind, Scope, Scopes,
+    Config, Finalizer, InstantiateErrorKind, Registry, ResolveErrorKind, ScopeData,
 };
 
+use super::types::{entries_scopes_data, RegistryScopes};
+
 #[inline]
 #[must_use]
 #[doc(hidden)]
 pub fn build_registry<H, S, const N: usize>((_, iterable): (S, H)) -> Registry
 where
-    S: Scope + Scopes<N, Scope = S>,
+    S: RegistryScopes<N>,
     H: hlist::IntoIterator<RegistryOrEntry>,
 {
-    let mut entries = BTreeMap::new();
-    for registry_or_entry in iterable.into_iter() {
-        match registry_or_entry {
-            RegistryOrEntry::Registry(registry) => {
-                entries.extend(registry.entries);
-            }
-            RegistryOrEntry::Entry((key, value)) => {
-                entries.insert(key, value);
-            }
-        }
-    }
-    Registry::new::<S, S, N>(entries)
+    let Registry {
+        entries,
+        externals,
+        conflict_policy,
+        duplicates,
+        ..
+    } = Registry::default().merge(iterable);
+    let scopes_data = S::all_scopes_data().unwrap_or_else(|| entries_scopes_data(entries.values().map(|data| &data.scope_data)));
+    let mut registry = Registry::with_scopes_data(entries, scopes_data);
+    registry.externals = externals;
+    registry.conflict_policy = conflict_policy;
+    registry.duplicates = duplicates;
+    registry
 }
 
 #[inline]
 #[must_use]
 #[doc(hidden)]
-pub fn make_entry<Inst, Deps, Fin>(scope: impl Scope, inst: Inst, config: Option<Config>, fin: Option<Fin>) -> (TypeInfo, InstantiatorData)
+#[track_caller]
+pub fn make_entry<Inst, Deps, Fin>(
+    scope: impl Into<ScopeData>,
+    inst: Inst,
+    config: Option<Config>,
+    fin: Option<Fin>,
+) -> (TypeInfo, InstantiatorData)
 where
     Inst: Instantiator<Deps, Error = InstantiateErrorKind> + SendSafety + SyncSafety,
     Inst::Provides: SendSafety + SyncSafety,
@@ -54,8 +63,25 @@ where
             finalizer: fin.map(boxed_finalizer_factory),
             config: config.unwrap_or_default(),
             scope_data: scope.into(),
+            registration: Registration {
+                kind: ProvideKind::Provide,
+                site: RegistrationSite::of::<Inst>(),
+            },
         },
     )
 }
 
+#[inline]
+#[must_use]
+#[doc(hidden)]
+pub fn with_kind(entry: RegistryOrEntry, kind: ProvideKind) -> RegistryOrEntry {
+    match entry {
+        RegistryOrEntry::Entry((key, mut value)) => {
+            value.registration.kind = kind;
+            RegistryOrEntry::Entry((key, value))
+        }
+        registry @ RegistryOrEntry::Registry(_) => registry,
+    }
+}
+
 pub type FinDummy<T> = fn(T) -> ();
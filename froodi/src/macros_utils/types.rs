@@ -1,4 +1,46 @@
-use crate::{any::TypeInfo, registry::InstantiatorData, Registry};
+use alloc::vec::Vec;
+
+use crate::{any::TypeInfo, registry::InstantiatorData, Registry, Scope, ScopeData, Scopes};
+
+pub use crate::conflict::ProvideKind;
+
+/// Scopes of a registry built by the macros, given by the scope of its first entry
+pub trait RegistryScopes<const N: usize> {
+    /// All scopes of the registry, `None` for runtime-defined scopes.
+    /// Registries of runtime-defined scopes have the scopes of their entries
+    fn all_scopes_data() -> Option<Vec<ScopeData>>;
+}
+
+impl<S, const N: usize> RegistryScopes<N> for S
+where
+    S: Scope + Scopes<N, Scope = S>,
+{
+    fn all_scopes_data() -> Option<Vec<ScopeData>> {
+        let (scope, child_scopes) = S::all();
+
+        let mut scopes_data = Vec::with_capacity(N + 1);
+        scopes_data.push(scope.into());
+        for scope in child_scopes {
+            scopes_data.push(scope.into());
+        }
+        Some(scopes_data)
+    }
+}
+
+impl RegistryScopes<0> for ScopeData {
+    #[inline]
+    fn all_scopes_data() -> Option<Vec<ScopeData>> {
+        None
+    }
+}
+
+/// Scopes of the entries, for registries of runtime-defined scopes
+pub(crate) fn entries_scopes_data<'a>(scopes: impl IntoIterator<Item = &'a ScopeData>) -> Vec<ScopeData> {
+    let mut scopes_data: Vec<_> = scopes.into_iter().cloned().collect();
+    scopes_data.sort();
+    scopes_data.dedup();
+    scopes_data
+}
 
 pub enum RegistryOrEntry {
     Registry(Registry),
@@ -0,0 +1,127 @@
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+
+use crate::{any::short_type_name, scope::ScopeData};
+
+/// Plan of resolving a type from a container, built by [`crate::Container::explain`]
+/// and [`crate::async_impl::Container::explain`] without calling any instantiator.
+///
+/// Serializable with the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ResolutionPlan {
+    pub type_name: &'static str,
+    /// The type is requested as transient, so the cache is bypassed and no finalizer is registered
+    pub is_transient: bool,
+    /// Scope the type would be resolved in, `None` if there's no instantiator for it
+    pub scope: Option<ScopeData>,
+    /// The type is provided by an async instantiator
+    pub is_async: bool,
+    /// The type is already in the cache or the context, so nothing would be instantiated
+    pub cached: bool,
+    /// A finalizer would be registered for the created instance
+    pub registers_finalizer: bool,
+    pub problem: Option<PlanProblem>,
+    /// Plans of the dependencies, empty unless the type would be instantiated
+    pub dependencies: Vec<ResolutionPlan>,
+}
+
+/// Why a type in a [`ResolutionPlan`] can't be resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum PlanProblem {
+    /// See [`crate::ResolveErrorKind::NoInstantiator`]
+    NoInstantiator,
+    /// See [`crate::ResolveErrorKind::NoAccessible`]
+    NoAccessible {
+        expected_scope_data: ScopeData,
+        actual_scope_data: ScopeData,
+    },
+    /// The type depends on itself, its dependencies aren't expanded again
+    CyclicDependency,
+}
+
+impl ResolutionPlan {
+    pub(crate) const fn new(type_name: &'static str, is_transient: bool) -> Self {
+        Self {
+            type_name,
+            is_transient,
+            scope: None,
+            is_async: false,
+            cached: false,
+            registers_finalizer: false,
+            problem: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    /// Whether the type and all its dependencies can be resolved
+    #[must_use]
+    pub fn is_resolvable(&self) -> bool {
+        self.problem.is_none() && self.dependencies.iter().all(Self::is_resolvable)
+    }
+
+    /// Plans with a problem, in depth-first order
+    #[must_use]
+    pub fn problems(&self) -> Vec<&Self> {
+        let mut problems = Vec::new();
+        self.collect_problems(&mut problems);
+        problems
+    }
+
+    fn collect_problems<'a>(&'a self, problems: &mut Vec<&'a Self>) {
+        if self.problem.is_some() {
+            problems.push(self);
+        }
+        for dependency in &self.dependencies {
+            dependency.collect_problems(problems);
+        }
+    }
+
+    fn fmt_with_depth(&self, f: &mut Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", short_type_name(self.type_name), indent = depth * 2)?;
+        match (&self.problem, &self.scope) {
+            (Some(PlanProblem::NoInstantiator), _) => f.write_str(" [no instantiator]")?,
+            (
+                Some(PlanProblem::NoAccessible {
+                    expected_scope_data,
+                    actual_scope_data,
+                }),
+                _,
+            ) => write!(
+                f,
+                " [provided in `{}`, not accessible from `{}`]",
+                expected_scope_data.name, actual_scope_data.name
+            )?,
+            (Some(PlanProblem::CyclicDependency), _) => f.write_str(" [cyclic dependency]")?,
+            (None, Some(scope)) if self.cached => write!(f, " [cached in `{}`]", scope.name)?,
+            (None, Some(scope)) => {
+                write!(f, " [instantiated in `{}`", scope.name)?;
+                if self.is_async {
+                    f.write_str(", async")?;
+                }
+                if self.is_transient {
+                    f.write_str(", transient")?;
+                }
+                if self.registers_finalizer {
+                    f.write_str(", finalizer")?;
+                }
+                f.write_str("]")?;
+            }
+            (None, None) => {}
+        }
+        for dependency in &self.dependencies {
+            f.write_str("\n")?;
+            dependency.fmt_with_depth(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renders the plan as an indented tree, one type per line
+impl Display for ResolutionPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with_depth(f, 0)
+    }
+}
@@ -1,15 +1,26 @@
 use alloc::{
+    boxed::Box,
     collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    string::String,
     vec::Vec,
 };
 
 use crate::{
     any::TypeInfo,
+    clock::{Clock, RcClock},
+    conflict::{
+        detect_duplicate_providers, insert_entry, ConflictPolicy, DuplicateProvider, ProvideKind, Registered, Registration,
+        RegistrationSite,
+    },
     dependency::{Dependency, EMPTY_DEPENDENCIES},
     errors::ValidationErrorKind,
     finalizer::BoxedCloneFinalizer,
+    graph::{ProviderInfo, RegistryManifest},
     instantiator::{boxed_container_instantiator, BoxedCloneInstantiator},
-    scope::{ScopeData, ScopeDataWithChildScopesData},
+    lint::{self, LintWarning},
+    scope::{extend_scopes_data, ScopeData, ScopeDataWithChildScopesData},
+    slot::{Frozen, SlotIndex},
+    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
     Config, Container, DefaultScope, InstantiateErrorKind, ResolveErrorKind, Scope, Scopes,
 };
 
@@ -20,17 +31,31 @@ pub struct InstantiatorData {
     pub(crate) finalizer: Option<BoxedCloneFinalizer>,
     pub(crate) config: Config,
     pub(crate) scope_data: ScopeData,
+    pub(crate) registration: Registration,
+}
+
+impl Registered for InstantiatorData {
+    #[inline]
+    fn registration(&self) -> &Registration {
+        &self.registration
+    }
 }
 
 #[derive(Clone, Default)]
 pub struct Registry {
     pub(crate) entries: BTreeMap<TypeInfo, InstantiatorData>,
     pub(crate) scopes_data: Vec<ScopeData>,
+    pub(crate) externals: BTreeSet<TypeInfo>,
+    /// Set explicitly with [`Self::with_conflict_policy`], or taken over from a merged registry
+    pub(crate) conflict_policy: Option<ConflictPolicy>,
+    pub(crate) duplicates: Vec<DuplicateProvider>,
+    pub(crate) clock: Option<RcClock>,
+    /// Slot order of the entries, dropped on changes, see [`Self::freeze`]
+    pub(crate) frozen: Option<Frozen<InstantiatorData>>,
 }
 
 impl Registry {
-    #[allow(clippy::similar_names)]
-    pub(crate) fn new<T, S, const N: usize>(mut entries: BTreeMap<TypeInfo, InstantiatorData>) -> Self
+    pub(crate) fn new<T, S, const N: usize>(entries: BTreeMap<TypeInfo, InstantiatorData>) -> Self
     where
         S: Scope,
         T: Scopes<N, Scope = S>,
@@ -38,27 +63,44 @@ impl Registry {
         let (scope, child_scopes) = T::all();
 
         let mut scopes_data = Vec::with_capacity(N + 1);
-        let scope_data = scope.into();
-
-        entries.insert(
-            TypeInfo::new::<Container>("Container"),
-            InstantiatorData {
-                instantiator: boxed_container_instantiator(),
-                dependencies: EMPTY_DEPENDENCIES,
-                finalizer: None,
-                // Caching the container in its own cache creates an cycle
-                // that prevents `Drop`/`close` from ever running
-                config: Config { cache_provides: false },
-                scope_data,
-            },
-        );
-
-        scopes_data.push(scope_data);
+        scopes_data.push(scope.into());
         for scope in child_scopes {
             scopes_data.push(scope.into());
         }
+        Self::with_scopes_data(entries, scopes_data)
+    }
 
-        Self { entries, scopes_data }
+    /// Creates a registry with the scopes, registering the built-in [`Container`] entry in the root (lowest-priority) scope
+    pub(crate) fn with_scopes_data(mut entries: BTreeMap<TypeInfo, InstantiatorData>, mut scopes_data: Vec<ScopeData>) -> Self {
+        scopes_data.sort_by_key(|scope| scope.priority);
+        if let Some(scope_data) = scopes_data.first() {
+            entries.insert(
+                TypeInfo::new::<Container>("Container"),
+                InstantiatorData {
+                    instantiator: boxed_container_instantiator(),
+                    dependencies: EMPTY_DEPENDENCIES,
+                    finalizer: None,
+                    // Caching the container in its own cache creates an cycle
+                    // that prevents `Drop`/`close` from ever running
+                    config: Config::default().cache_provides(false),
+                    scope_data: scope_data.clone(),
+                    registration: Registration {
+                        kind: ProvideKind::Default,
+                        site: RegistrationSite::of::<Container>(),
+                    },
+                },
+            );
+        }
+
+        Self {
+            entries,
+            scopes_data,
+            externals: BTreeSet::new(),
+            conflict_policy: None,
+            duplicates: Vec::new(),
+            clock: None,
+            frozen: None,
+        }
     }
 
     #[inline]
@@ -66,12 +108,144 @@ impl Registry {
     pub fn new_with_default_entries() -> Self {
         Self::new::<DefaultScope, DefaultScope, 5>(BTreeMap::new())
     }
+
+    /// Replaces the scopes of the registry, for scopes that are only known at runtime.
+    ///
+    /// Entries of a registry built from [`ScopeData`] values instead of a [`Scope`] type are only aware of their own scopes,
+    /// so this is how the full scope list, including scopes without providers, is set.
+    /// The built-in [`Container`] entry moves to the root (lowest-priority) scope.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use froodi::{registry, Container, ScopeData};
+    ///
+    /// // For example, read from a config file
+    /// let scopes = ["app", "pipeline", "stage"];
+    /// let scopes: Vec<_> = (0..).zip(scopes).map(|(priority, name)| ScopeData::new(priority, name.to_owned())).collect();
+    ///
+    /// let registry = registry! {
+    ///     scope(scopes[2].clone()) [
+    ///         provide(|| Ok(1u8)),
+    ///     ],
+    /// }
+    /// .with_scopes(scopes);
+    ///
+    /// let app = Container::new(registry);
+    /// let stage = app.enter().with_scope_name("stage").build().unwrap();
+    /// assert_eq!(*stage.get::<u8>().unwrap(), 1);
+    /// ```
+    #[must_use]
+    pub fn with_scopes(self, scopes: impl IntoIterator<Item = ScopeData>) -> Self {
+        let Self {
+            mut entries,
+            externals,
+            conflict_policy,
+            duplicates,
+            clock,
+            ..
+        } = self;
+        entries.remove(&TypeInfo::of::<Container>());
+        Self {
+            clock,
+            externals,
+            conflict_policy,
+            duplicates,
+            ..Self::with_scopes_data(entries, scopes.into_iter().collect())
+        }
+    }
+
+    /// Declares `T` as supplied from outside the registry, for example through a [`crate::Context`],
+    /// so [`Self::validate`] doesn't report dependencies on it as missing.
+    #[inline]
+    #[must_use]
+    pub fn with_external<T: ?Sized + 'static>(mut self) -> Self {
+        self.externals.insert(TypeInfo::of::<T>());
+        self
+    }
+
+    /// Sets the policy for registrations of already registered types in registries merged into this one afterwards.
+    /// See [`ConflictPolicy`].
+    #[inline]
+    #[must_use]
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = Some(policy);
+        self
+    }
+
+    /// Sets the clock used to expire cached values with a TTL, see [`Clock`]
+    #[inline]
+    #[must_use]
+    pub fn with_clock<C: Clock + SendSafety + SyncSafety + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(RcThreadSafety::new(clock));
+        self
+    }
+
+    /// Assigns each registered type a dense slot, so looking providers and cached values up is an array access.
+    ///
+    /// [`Container::new`] freezes its registry, so it's only needed to pay for it upfront.
+    /// Changing the registry afterwards, for example by merging another one into it, drops the slots.
+    #[must_use]
+    pub fn freeze(mut self) -> Self {
+        if self.frozen.is_none() {
+            self.frozen = Some(Frozen::new(&self.entries));
+        }
+        self
+    }
+
+    /// Slot index of the frozen registry, empty if it isn't frozen
+    #[inline]
+    #[must_use]
+    pub(crate) fn slot_index(&self) -> RcThreadSafety<SlotIndex> {
+        self.frozen.as_ref().map(|frozen| frozen.index.clone()).unwrap_or_default()
+    }
+
+    #[inline]
+    pub(crate) fn insert_entry(&mut self, key: TypeInfo, value: InstantiatorData) {
+        self.frozen = None;
+        let unknown_scope_data =
+            (!self.scopes_data.iter().any(|known| known.priority == value.scope_data.priority)).then(|| value.scope_data.clone());
+        insert_entry(
+            &mut self.entries,
+            &mut self.duplicates,
+            self.conflict_policy.unwrap_or_default(),
+            key,
+            value,
+        );
+        self.extend_scopes_data(unknown_scope_data);
+    }
+
+    /// Adds the scopes unknown to the registry, keeping the built-in container entry in the root scope
+    fn extend_scopes_data(&mut self, scopes_data: impl IntoIterator<Item = ScopeData>) {
+        self.frozen = None;
+        extend_scopes_data(&mut self.scopes_data, scopes_data);
+        // The root scope may have changed, and the built-in container entry of a merged registry may have replaced ours
+        if let (Some(container), Some(root)) = (self.entries.get_mut(&TypeInfo::of::<Container>()), self.scopes_data.first()) {
+            if container.scope_data.priority != root.priority {
+                container.scope_data = root.clone();
+            }
+        }
+    }
+
+    pub(crate) fn extend(&mut self, other: Registry) {
+        // The policy is adopted first, so it also applies to the conflicts of the merged entries
+        self.conflict_policy = self.conflict_policy.or(other.conflict_policy);
+        self.duplicates.extend(other.duplicates);
+        for (key, value) in other.entries {
+            self.insert_entry(key, value);
+        }
+        self.extend_scopes_data(other.scopes_data);
+        self.clock = self.clock.take().or(other.clock);
+        self.externals.extend(other.externals);
+    }
 }
 
 impl Registry {
     #[inline]
     pub(crate) fn get(&self, type_info: &TypeInfo) -> Option<&InstantiatorData> {
-        self.entries.get(type_info)
+        match &self.frozen {
+            Some(frozen) => frozen.get(type_info),
+            None => self.entries.get(type_info),
+        }
     }
 
     #[inline]
@@ -80,11 +254,116 @@ impl Registry {
         ScopeDataWithChildScopesData::new_with_sort(self.scopes_data.clone())
     }
 
+    #[inline]
+    #[must_use]
+    pub(crate) fn provides(&self, type_info: &TypeInfo) -> bool {
+        self.entries.contains_key(type_info) || self.externals.contains(type_info)
+    }
+
+    /// Validates the registry: conflicting registrations, cycles, scope reachability and dependencies without a provider.
+    ///
+    /// There's no lazy injector, a dependency is instantiated before its dependent,
+    /// so only [`crate::InjectWeak`] edges are exempt from the cycle check.
+    ///
+    /// # Errors
+    /// - Returns [`ValidationErrorKind::DuplicateProvider`] if a type is registered twice under [`ConflictPolicy::Error`]
+    /// - Returns [`ValidationErrorKind::CyclicDependency`] if dependencies form a cycle without a [`crate::InjectWeak`] edge
+    /// - Returns [`ValidationErrorKind::UnreachableDependency`] if a dependency lives in a scope that isn't the scope of its dependent or one of its ancestors
+    /// - Returns [`ValidationErrorKind::MissingDependency`] if a dependency is neither registered nor declared as external
+    ///   with [`Self::with_external`]
     pub fn validate(&self) -> Result<(), ValidationErrorKind> {
+        self.validate_partial()?;
+        self.detect_missing_dependencies(|type_info| self.provides(type_info))
+    }
+
+    /// Describes the wiring of the registry, highlighting the error reported by [`Self::validate`]
+    #[must_use]
+    pub fn manifest(&self) -> RegistryManifest {
+        RegistryManifest::new(
+            self.scopes_data.clone(),
+            self.provider_infos(),
+            |type_info| self.provides(type_info),
+            self.validate(),
+        )
+    }
+
+    /// Renders the registry in the Graphviz DOT format, see [`RegistryManifest::to_dot`]
+    #[inline]
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        self.manifest().to_dot()
+    }
+
+    /// Renders the registry as a Mermaid flowchart, see [`RegistryManifest::to_mermaid`]
+    #[inline]
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        self.manifest().to_mermaid()
+    }
+
+    /// Serializes the registry manifest to JSON
+    #[cfg(feature = "serde")]
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        self.manifest().to_json()
+    }
+
+    pub(crate) fn provider_infos(&self) -> impl Iterator<Item = ProviderInfo<'_>> {
+        // The built-in container entry isn't part of the wiring, as in the lints
+        let container_type_info = TypeInfo::of::<Container>();
+        self.entries
+            .iter()
+            .filter(move |(type_info, _)| **type_info != container_type_info)
+            .map(|(type_info, data)| ProviderInfo {
+                type_info,
+                dependencies: &data.dependencies,
+                scope_data: &data.scope_data,
+                is_async: false,
+                has_finalizer: data.finalizer.is_some(),
+                cache_provides: data.config.cache_provides,
+            })
+    }
+
+    /// Reports legal but likely wrong configurations, such as finalizers that never run or scopes without providers.
+    ///
+    /// Unlike [`Self::validate`], the warnings don't prevent the registry from working, so they're meant to be
+    /// asserted in tests or logged at startup:
+    /// ```rust
+    /// use froodi::{registry, DefaultScope::*, InstantiateErrorKind};
+    ///
+    /// fn inst() -> Result<(), InstantiateErrorKind> {
+    ///     Ok(())
+    /// }
+    ///
+    /// for warning in registry! { provide(App, inst) }.lint() {
+    ///     tracing::warn!(%warning, "Registry lint");
+    /// }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn lint(&self) -> Vec<LintWarning> {
+        lint::lint(self)
+    }
+
+    /// Validates conflicting registrations, cycles and scope reachability only.
+    ///
+    /// The `registry!` macro uses this, because its registries may be merged with the ones providing
+    /// the missing dependencies later.
+    #[doc(hidden)]
+    pub fn validate_partial(&self) -> Result<(), ValidationErrorKind> {
+        detect_duplicate_providers(&self.duplicates)?;
         self.detect_cyclic_dependencies()?;
         self.detect_unreachable_scopes()
     }
 
+    pub(crate) fn detect_missing_dependencies(&self, is_provided: impl Fn(&TypeInfo) -> bool) -> Result<(), ValidationErrorKind> {
+        detect_missing_dependencies(
+            self.entries.iter().map(|(type_info, data)| (type_info, &data.dependencies)),
+            is_provided,
+        )
+    }
+
     fn detect_cyclic_dependencies(&self) -> Result<(), ValidationErrorKind> {
         let mut visited = BTreeSet::new();
         let mut stack = Vec::new();
@@ -100,6 +379,7 @@ impl Registry {
     }
 
     fn detect_unreachable_scopes(&self) -> Result<(), ValidationErrorKind> {
+        let scopes = self.get_scope_with_child_scopes();
         for (
             type_info,
             InstantiatorData {
@@ -107,18 +387,18 @@ impl Registry {
             },
         ) in &self.entries
         {
-            for Dependency { type_info: dependency } in dependencies {
+            for Dependency { type_info: dependency, .. } in dependencies {
                 if let Some(InstantiatorData {
                     scope_data: dependency_scope,
                     ..
                 }) = self.entries.get(dependency)
                 {
-                    if dependency_scope.priority > scope_data.priority {
+                    if !scopes.is_ancestor_or_self(dependency_scope, scope_data) {
                         return Err(ValidationErrorKind::UnreachableDependency {
                             dependent: type_info.clone(),
-                            dependent_scope: *scope_data,
+                            dependent_scope: Box::new(scope_data.clone()),
                             dependency: dependency.clone(),
-                            dependency_scope: *dependency_scope,
+                            dependency_scope: Box::new(dependency_scope.clone()),
                         });
                     }
                 }
@@ -142,7 +422,8 @@ impl Registry {
         }
         stack.push(type_info.clone());
 
-        for Dependency { type_info } in dependencies {
+        // Weak dependencies aren't instantiated, so cycles through them are fine
+        for Dependency { type_info, .. } in dependencies.iter().filter(|dependency| !dependency.is_weak) {
             if let Some(InstantiatorData { dependencies, .. }) = self.entries.get(type_info) {
                 if self.dfs_visit(type_info, dependencies, visited, stack) {
                     return true;
@@ -156,6 +437,25 @@ impl Registry {
     }
 }
 
+/// Returns [`ValidationErrorKind::MissingDependency`] for the first dependency of the providers that isn't provided,
+/// shared by the sync and async registries
+pub(crate) fn detect_missing_dependencies<'a>(
+    providers: impl IntoIterator<Item = (&'a TypeInfo, &'a BTreeSet<Dependency>)>,
+    is_provided: impl Fn(&TypeInfo) -> bool,
+) -> Result<(), ValidationErrorKind> {
+    for (type_info, dependencies) in providers {
+        for Dependency { type_info: dependency, .. } in dependencies {
+            if !is_provided(dependency) {
+                return Err(ValidationErrorKind::MissingDependency {
+                    dependent: type_info.clone(),
+                    dependency: dependency.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
 /// The `registry!` macro is used to create a dependency registry with various configuration options.
 ///
 /// ### `provide` syntax
@@ -171,6 +471,9 @@ impl Registry {
 /// provide(inst, finalizer = fin, config = Config::default()) // order doesn’t matter
 /// ```
 ///
+/// `provide_default(...)` and `override(...)` accept the same forms and mark the registration
+/// as a replaceable default or as an intentional replacement, see [`crate::ConflictPolicy`].
+///
 /// Parameters:
 /// - `config` *(optional)* — configuration object.
 /// - `finalizer` *(optional)* — function called when the dependency is finalized.
@@ -280,6 +583,42 @@ impl Registry {
 /// let registry = registry!();
 /// ```
 /// In this case, a registry with default entries is created.
+///
+/// ### 9. Defaults and overrides
+/// ```rust
+/// use froodi::{registry, InstantiateErrorKind, DefaultScope::*};
+///
+/// fn default_inst() -> Result<(), InstantiateErrorKind> {
+///     Ok(())
+/// }
+///
+/// fn inst() -> Result<(), InstantiateErrorKind> {
+///     Ok(())
+/// }
+///
+/// registry! {
+///     provide_default(App, default_inst),
+///     extend(registry! { override(App, inst) }),
+/// };
+/// ```
+/// Registering the same type twice with plain `provide` keeps the last registration,
+/// unless the registry uses another [`crate::ConflictPolicy`].
+///
+/// ### 10. Scope of the provided type
+/// ```rust
+/// use froodi::{registry, typed::{Request, Scoped}};
+///
+/// struct RequestId(u64);
+///
+/// impl Scoped for RequestId {
+///     type Scope = Request;
+/// }
+///
+/// registry! {
+///     provide_typed(|| Ok(RequestId(1))),
+/// };
+/// ```
+/// `provide_typed` takes the scope from the [`crate::typed::Scoped`] impl of the provided type, as `provide` in that scope would do.
 #[macro_export]
 macro_rules! registry {
     () => {{
@@ -290,12 +629,12 @@ macro_rules! registry {
             $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { scope($scope) [ $($entries)+ ] })),
             $crate::registry_internal! { $($rest)+ }
         );
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
     (scope($scope:expr $(,)?) [ $($entries:tt)+ ] $(,)?) => {{
         let registry = $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { scope($scope) [ $($entries)+ ] }));
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
     (provide($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
@@ -303,12 +642,53 @@ macro_rules! registry {
             $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { provide($scope, $($entry)+) })),
             $crate::registry_internal! { $($rest)+ }
         );
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
     (provide($scope:expr, $($entry:tt)+) $(,)?) => {{
         $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { provide($scope, $($entry)+) }))
     }};
+    (provide_default($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        let registry = $crate::utils::Merge::merge(
+            $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { provide_default($scope, $($entry)+) })),
+            $crate::registry_internal! { $($rest)+ }
+        );
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (provide_default($scope:expr, $($entry:tt)+) $(,)?) => {{
+        $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { provide_default($scope, $($entry)+) }))
+    }};
+    (override($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        let registry = $crate::utils::Merge::merge(
+            $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { override($scope, $($entry)+) })),
+            $crate::registry_internal! { $($rest)+ }
+        );
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (override($scope:expr, $($entry:tt)+) $(,)?) => {{
+        $crate::macros_utils::sync::build_registry(($scope, $crate::registry_internal! { override($scope, $($entry)+) }))
+    }};
+    (provide_typed($inst:expr $(, $($args:tt)+)?), $($rest:tt)+) => {{
+        let inst = $inst;
+        let registry = $crate::utils::Merge::merge(
+            $crate::macros_utils::sync::build_registry((
+                $crate::typed::scope_of(&inst),
+                $crate::registry_internal! { provide($crate::typed::scope_of(&inst), inst $(, $($args)+)?) },
+            )),
+            $crate::registry_internal! { $($rest)+ }
+        );
+        registry.validate_partial().unwrap();
+        registry
+    }};
+    (provide_typed($inst:expr $(, $($args:tt)+)?) $(,)?) => {{
+        let inst = $inst;
+        $crate::macros_utils::sync::build_registry((
+            $crate::typed::scope_of(&inst),
+            $crate::registry_internal! { provide($crate::typed::scope_of(&inst), inst $(, $($args)+)?) },
+        ))
+    }};
     (extend($registry:expr $(, $($registries:expr),+ )? $(,)?) $(,)?) => {{
         #[allow(unused_mut)]
         let mut registry: $crate::Registry = $registry;
@@ -318,7 +698,7 @@ macro_rules! registry {
                 registry = $crate::utils::Merge::merge(registry, registry_to_merge);
             )+
         )?
-        registry.validate().unwrap();
+        registry.validate_partial().unwrap();
         registry
     }};
 
@@ -352,6 +732,9 @@ macro_rules! registry {
     (provide($($entry:tt)*) $($rest:tt)+) => {
         compile_error!("Missing comma after `provide` block")
     };
+    (provide_typed($($entry:tt)*) $($rest:tt)+) => {
+        compile_error!("Missing comma after `provide_typed` block")
+    };
     (extend($($entry:tt)*), $($rest:tt)+) => {
         compile_error!("`extend` macro must be at the last macro invocation")
     };
@@ -398,6 +781,34 @@ macro_rules! registry_internal {
     (provide($scope:expr, $($entry:tt)+) $(,)?) => {{
         $crate::registry_internal! { @entries_with_scope provide($scope, $($entry)*) }
     }};
+    (provide_default($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        $crate::macros_utils::aliases::hlist![
+            $crate::registry_internal! { @entries_with_scope provide_default($scope, $($entry)+) },
+            $crate::registry_internal! { $($rest)+ }
+        ]
+    }};
+    (provide_default($scope:expr, $($entry:tt)+) $(,)?) => {{
+        $crate::registry_internal! { @entries_with_scope provide_default($scope, $($entry)+) }
+    }};
+    (override($scope:expr, $($entry:tt)+), $($rest:tt)+) => {{
+        $crate::macros_utils::aliases::hlist![
+            $crate::registry_internal! { @entries_with_scope override($scope, $($entry)+) },
+            $crate::registry_internal! { $($rest)+ }
+        ]
+    }};
+    (override($scope:expr, $($entry:tt)+) $(,)?) => {{
+        $crate::registry_internal! { @entries_with_scope override($scope, $($entry)+) }
+    }};
+    (provide_typed($inst:expr $(, $($args:tt)+)?), $($rest:tt)+) => {{
+        $crate::macros_utils::aliases::hlist![
+            $crate::registry_internal! { provide_typed($inst $(, $($args)+)?) },
+            $crate::registry_internal! { $($rest)+ }
+        ]
+    }};
+    (provide_typed($inst:expr $(, $($args:tt)+)?) $(,)?) => {{
+        let inst = $inst;
+        $crate::registry_internal! { provide($crate::typed::scope_of(&inst), inst $(, $($args)+)?) }
+    }};
     (extend($registry:expr $(, $($registries:expr),+ )? $(,)?) $(,)?) => {{
         #[allow(unused_mut)]
         let mut registry: $crate::Registry = $registry;
@@ -410,12 +821,32 @@ macro_rules! registry_internal {
         $crate::macros_utils::types::RegistryOrEntry::Registry(registry)
     }};
 
-    (@entries_with_scope $( provide($scope:expr, $($entry:tt)+) ),+ $(,)?) => {{
-        $crate::macros_utils::aliases::hlist![$( $crate::registry_internal! { @entry scope($scope), $($entry)+ } ),+]
+    (@entries_with_scope $( $kind:ident($scope:expr, $($entry:tt)+) ),+ $(,)?) => {{
+        $crate::macros_utils::aliases::hlist![$( $crate::registry_internal! { @marked $kind scope($scope), $($entry)+ } ),+]
+    }};
+    (@entries_in_scope scope($scope:expr) [ $( $kind:ident($($entry:tt)+) ),+ $(,)? ]) => {{
+        $crate::macros_utils::aliases::hlist![$( $crate::registry_internal! { @marked $kind scope($scope), $($entry)+ } ),+]
     }};
-    (@entries_in_scope scope($scope:expr) [ $( provide($($entry:tt)+) ),+ $(,)? ]) => {{
-        $crate::macros_utils::aliases::hlist![$( $crate::registry_internal! { @entry scope($scope), $($entry)+ } ),+]
+    (@marked provide scope($scope:expr), $($entry:tt)+) => {{
+        $crate::registry_internal! { @entry scope($scope), $($entry)+ }
     }};
+    (@marked provide_default scope($scope:expr), $($entry:tt)+) => {{
+        $crate::macros_utils::sync::with_kind(
+            $crate::registry_internal! { @entry scope($scope), $($entry)+ },
+            $crate::macros_utils::types::ProvideKind::Default,
+        )
+    }};
+    (@marked override scope($scope:expr), $($entry:tt)+) => {{
+        $crate::macros_utils::sync::with_kind(
+            $crate::registry_internal! { @entry scope($scope), $($entry)+ },
+            $crate::macros_utils::types::ProvideKind::Override,
+        )
+    }};
+    (@marked $kind:ident scope($scope:expr), $($entry:tt)+) => {
+        compile_error!(concat!(
+            "Unknown entry `", stringify!($kind), "(...)`. Expected `provide(...)`, `provide_default(...)` or `override(...)`"
+        ))
+    };
     (@entry scope($scope:expr), $inst:expr $(,)?) => {{
         $crate::macros_utils::types::RegistryOrEntry::Entry(
             $crate::macros_utils::sync::make_entry($scope, $inst, None, None::<$crate::macros_utils::sync::FinDummy<_>>)
@@ -497,6 +928,9 @@ macro_rules! registry_internal {
     (provide($($entry:tt)*) $($rest:tt)+) => {
         compile_error!("Missing comma after `provide` block")
     };
+    (provide_typed($($entry:tt)*) $($rest:tt)+) => {
+        compile_error!("Missing comma after `provide_typed` block")
+    };
     (extend($($entry:tt)*), $($rest:tt)+) => {
         compile_error!("`extend` macro must be at the last macro invocation")
     };
@@ -527,7 +961,12 @@ mod tests {
     };
     use tracing_test::traced_test;
 
-    use crate::{any::TypeInfo, utils::thread_safety::RcThreadSafety, Config, DefaultScope, Inject, InjectTransient, InstantiateErrorKind};
+    use crate::{
+        any::TypeInfo,
+        errors::ValidationErrorKind,
+        utils::{thread_safety::RcThreadSafety, Merge as _},
+        Config, ConflictPolicy, Container, DefaultScope, Inject, InjectTransient, InjectWeak, InstantiateErrorKind, LintWarning,
+    };
 
     fn inst_a() -> Result<(), InstantiateErrorKind> {
         Ok(())
@@ -870,6 +1309,21 @@ mod tests {
         };
     }
 
+    #[test]
+    #[traced_test]
+    fn test_registry_dfs_allows_weak_cycle() {
+        struct Parent;
+        struct Child;
+
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(|Inject(_): Inject<Child>| Ok(Parent)),
+                provide(|_: InjectWeak<Parent>| Ok(Child)),
+            ],
+        };
+        registry.validate().unwrap();
+    }
+
     #[test]
     #[should_panic]
     #[traced_test]
@@ -887,6 +1341,232 @@ mod tests {
         };
     }
 
+    #[test]
+    #[traced_test]
+    fn test_registry_validate_detects_missing_dependency() {
+        struct Registered;
+        struct Unregistered;
+
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(|Inject(_): Inject<Unregistered>| Ok(Registered)),
+            ],
+        };
+
+        match registry.validate() {
+            Err(ValidationErrorKind::MissingDependency { dependent, dependency }) => {
+                assert_eq!(dependent, TypeInfo::of::<Registered>());
+                assert_eq!(dependency, TypeInfo::of::<Unregistered>());
+            }
+            other => panic!("expected MissingDependency, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_validate_allows_external_and_container_dependencies() {
+        struct FromContext;
+        struct Registered;
+
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(|Inject(_): Inject<FromContext>, InjectTransient(_): InjectTransient<Container>| Ok(Registered)),
+            ],
+        };
+        assert!(registry.validate().is_err());
+
+        registry.with_external::<FromContext>().validate().unwrap();
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_extend_keeps_externals() {
+        struct FromContext;
+        struct Registered;
+
+        let registry = registry! {
+            provide(DefaultScope::App, |Inject(_): Inject<FromContext>| Ok(Registered)),
+            extend(registry!().with_external::<FromContext>()),
+        };
+
+        registry.validate().unwrap();
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_validate_detects_duplicate_provider() {
+        let registry = registry! { provide(DefaultScope::App, inst_a) }
+            .with_conflict_policy(ConflictPolicy::Error)
+            .merge(registry! { provide(DefaultScope::Session, || Ok(())) });
+
+        match registry.validate() {
+            Err(ValidationErrorKind::DuplicateProvider { type_info, first, second }) => {
+                assert_eq!(type_info, TypeInfo::of::<()>());
+                assert!(first.instantiator.ends_with("inst_a"));
+                assert!(second.instantiator.contains("{{closure}}"));
+                assert_eq!(first.location.file(), file!());
+            }
+            other => panic!("expected DuplicateProvider, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_merge_adopts_conflict_policy_before_entries() {
+        let registry = registry! { provide(DefaultScope::App, inst_a) }
+            .merge(registry! { provide(DefaultScope::Session, inst_a) }.with_conflict_policy(ConflictPolicy::Error));
+
+        assert!(matches!(registry.validate(), Err(ValidationErrorKind::DuplicateProvider { .. })));
+    }
+
+    #[test]
+    #[should_panic]
+    #[traced_test]
+    fn test_registry_macro_panics_on_duplicate_provider() {
+        let _ = registry! {
+            extend(
+                registry! { provide(DefaultScope::App, inst_a) }.with_conflict_policy(ConflictPolicy::Error),
+                registry! { provide(DefaultScope::Session, inst_a) },
+            ),
+        };
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_conflict_policy_keep_first() {
+        let registry = registry! {
+            extend(
+                registry! { provide(DefaultScope::App, inst_a) }.with_conflict_policy(ConflictPolicy::KeepFirst),
+                registry! { provide(DefaultScope::Session, inst_a) },
+            ),
+        };
+
+        assert_eq!(registry.get(&TypeInfo::of::<()>()).unwrap().scope_data, DefaultScope::App.into());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_macro_keeps_conflict_policy() {
+        let registry = registry! {
+            provide(DefaultScope::App, inst_b),
+            extend(registry! { provide(DefaultScope::App, inst_a) }.with_conflict_policy(ConflictPolicy::KeepFirst)),
+        }
+        .merge(registry! { provide(DefaultScope::Session, inst_a) });
+
+        assert_eq!(registry.get(&TypeInfo::of::<()>()).unwrap().scope_data, DefaultScope::App.into());
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_default_and_override_ignore_conflict_policy() {
+        let registry = registry! {
+            extend(
+                registry! { provide(DefaultScope::App, inst_a) }.with_conflict_policy(ConflictPolicy::Error),
+                registry! { provide_default(DefaultScope::Session, inst_a) },
+                registry! { override(DefaultScope::Request, inst_b) },
+                registry! { provide(DefaultScope::Session, inst_b) },
+            ),
+        };
+
+        assert_eq!(registry.get(&TypeInfo::of::<()>()).unwrap().scope_data, DefaultScope::App.into());
+        assert_eq!(
+            registry.get(&TypeInfo::of::<((), ())>()).unwrap().scope_data,
+            DefaultScope::Request.into()
+        );
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_lint_reports_suspicious_configurations() {
+        struct Transient;
+        struct Uncached;
+        struct Root;
+
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(|| Ok(Transient), finalizer = |_: RcThreadSafety<Transient>| {}),
+                provide(
+                    || Ok(Uncached),
+                    config = Config::default().cache_provides(false),
+                    finalizer = |_: RcThreadSafety<Uncached>| {},
+                ),
+                provide(|InjectTransient(_): InjectTransient<Transient>, Inject(_): Inject<Uncached>| Ok(Root)),
+            ],
+        };
+        let warnings = registry.lint();
+        let app: crate::ScopeData = DefaultScope::App.into();
+
+        assert!(warnings.contains(&LintWarning::FinalizerOnTransientOnly {
+            type_info: TypeInfo::of::<Transient>(),
+            scope_data: app.clone(),
+        }));
+        assert!(warnings.contains(&LintWarning::FinalizerWithoutCache {
+            type_info: TypeInfo::of::<Uncached>(),
+            scope_data: app.clone(),
+        }));
+        assert!(warnings.contains(&LintWarning::UnusedProvider {
+            type_info: TypeInfo::of::<Root>(),
+            scope_data: app.clone(),
+        }));
+        for scope in [DefaultScope::Request, DefaultScope::Action, DefaultScope::Step] {
+            assert!(warnings.contains(&LintWarning::EmptyScope { scope_data: scope.into() }));
+        }
+        assert_eq!(warnings.len(), 6);
+    }
+
+    #[test]
+    #[traced_test]
+    #[cfg(not(feature = "std"))]
+    fn test_registry_lint_reports_retry_delay_without_clock() {
+        use core::time::Duration;
+
+        struct ZeroClock;
+
+        impl crate::Clock for ZeroClock {
+            fn now(&self) -> Duration {
+                Duration::ZERO
+            }
+        }
+
+        let warning = LintWarning::RetryDelayWithoutClock {
+            type_info: TypeInfo::of::<()>(),
+            scope_data: DefaultScope::App.into(),
+        };
+        let registry = || {
+            registry! {
+                scope(DefaultScope::App) [
+                    provide(|| Ok(()), config = Config::default().retry(crate::RetryPolicy::new(2, Duration::from_millis(1)))),
+                ],
+            }
+        };
+
+        assert!(registry().lint().contains(&warning));
+        assert!(!registry().with_clock(ZeroClock).lint().contains(&warning));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_lint_ignores_cached_and_injected_providers() {
+        struct Finalized;
+        struct Root;
+
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(|| Ok(Finalized), finalizer = |_: RcThreadSafety<Finalized>| {}),
+                provide(|Inject(_): Inject<Finalized>| Ok(Root)),
+            ],
+        };
+
+        assert_eq!(
+            registry
+                .lint()
+                .iter()
+                .filter_map(LintWarning::type_info)
+                .collect::<alloc::vec::Vec<_>>(),
+            [&TypeInfo::of::<Root>()]
+        );
+    }
+
     #[test]
     #[traced_test]
     fn test_registry_extend_entries() {
@@ -1016,7 +1696,7 @@ mod tests {
                     provide(
                         DefaultScope::App,
                         inst_a,
-                        config = Config { cache_provides: false },
+                        config = Config::default().cache_provides(false),
                     ),
                 },
                 registry! {
@@ -1040,7 +1720,7 @@ mod tests {
                     provide(
                         DefaultScope::App,
                         inst_a,
-                        config = Config { cache_provides: false },
+                        config = Config::default().cache_provides(false),
                     ),
                 },
                 registry! {
@@ -1050,7 +1730,7 @@ mod tests {
                     provide(
                         DefaultScope::Request,
                         inst_a,
-                        config = Config { cache_provides: false },
+                        config = Config::default().cache_provides(false),
                     ),
                 },
             ),
@@ -1069,7 +1749,7 @@ mod tests {
             provide(
                 DefaultScope::App,
                 inst_a,
-                config = Config { cache_provides: false },
+                config = Config::default().cache_provides(false),
             ),
             extend(
                 registry! {
@@ -1083,4 +1763,35 @@ mod tests {
         assert!(entry.config.cache_provides);
         assert_eq!(entry.scope_data, DefaultScope::Request.into());
     }
+
+    #[test]
+    #[traced_test]
+    fn test_registry_freeze() {
+        let registry = registry! {
+            scope(DefaultScope::App) [
+                provide(inst_a),
+                provide(inst_b),
+            ],
+        }
+        .freeze();
+
+        assert!(registry.frozen.is_some());
+        assert_eq!(registry.slot_index().len(), registry.entries.len());
+        for type_info in registry.entries.keys() {
+            assert!(registry.get(type_info).is_some());
+        }
+        assert!(registry.get(&TypeInfo::of::<((), (), ())>()).is_none());
+
+        // Changes drop the slots, lookups fall back to the entries
+        let mut registry = registry;
+        registry.extend(registry! {
+            scope(DefaultScope::Request) [
+                provide(inst_c),
+            ],
+        });
+        assert!(registry.frozen.is_none());
+        assert_eq!(registry.slot_index().len(), 0);
+        assert!(registry.get(&TypeInfo::of::<((), (), ())>()).is_some());
+        assert!(registry.freeze().get(&TypeInfo::of::<((), (), ())>()).is_some());
+    }
 }
@@ -1,6 +1,6 @@
 use core::fmt::{self, Display, Formatter};
 
-use alloc::vec::Vec;
+use alloc::{borrow::Cow, vec::Vec};
 
 pub trait Scope: Ord + Into<ScopeData> {
     #[must_use]
@@ -13,6 +13,16 @@ pub trait Scope: Ord + Into<ScopeData> {
     fn is_skipped_by_default(&self) -> bool {
         false
     }
+
+    /// Parent of the scope in the scope tree.
+    /// `None` makes the preceding scope by priority the parent, so scopes form a single chain by default.
+    ///
+    /// The parent must have a lower priority than the scope.
+    /// Scopes with the same parent are siblings and don't see each other's providers.
+    #[must_use]
+    fn parent(&self) -> Option<Self> {
+        None
+    }
 }
 
 pub trait Scopes<const N: usize> {
@@ -36,8 +46,9 @@ impl From<DefaultScope> for ScopeData {
     fn from(scope: DefaultScope) -> Self {
         Self {
             priority: scope.priority(),
-            name: scope.name(),
+            name: Cow::Borrowed(scope.name()),
             is_skipped_by_default: scope.is_skipped_by_default(),
+            parent: scope.parent().map(|parent| parent.priority()),
         }
     }
 }
@@ -77,11 +88,42 @@ impl Scopes<5> for DefaultScope {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// Data of a scope, the form scopes are stored in registries and containers.
+///
+/// Built from a [`Scope`] value, or at runtime with [`Self::new`] for scopes that are only known at runtime,
+/// for example read from a config file. See [`crate::Registry::with_scopes`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ScopeData {
     pub priority: u8,
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     pub is_skipped_by_default: bool,
+    /// Priority of the parent scope, `None` for the preceding scope by priority. See [`Scope::parent`]
+    pub parent: Option<u8>,
+}
+
+impl ScopeData {
+    /// Creates data of a scope that isn't skipped by default and is a child of the preceding scope by priority
+    #[inline]
+    #[must_use]
+    pub fn new(priority: u8, name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            priority,
+            name: name.into(),
+            is_skipped_by_default: false,
+            parent: None,
+        }
+    }
+}
+
+/// Adds the scopes with priorities unknown to `scopes_data`, keeping it sorted by priority
+pub(crate) fn extend_scopes_data(scopes_data: &mut Vec<ScopeData>, other: impl IntoIterator<Item = ScopeData>) {
+    for scope_data in other {
+        if !scopes_data.iter().any(|known| known.priority == scope_data.priority) {
+            scopes_data.push(scope_data);
+        }
+    }
+    scopes_data.sort_by_key(|scope_data| scope_data.priority);
 }
 
 impl Display for ScopeData {
@@ -94,6 +136,8 @@ impl Display for ScopeData {
     }
 }
 
+/// Scope of a container with the scopes after it by priority.
+/// The scopes after it include ones of other branches of the scope tree, they're needed to resolve the parents of the descendants.
 pub(crate) struct ScopeDataWithChildScopesData {
     pub scope_data: Option<ScopeData>,
     pub child_scopes_data: Vec<ScopeData>,
@@ -109,9 +153,20 @@ impl ScopeDataWithChildScopesData {
         }
     }
 
+    /// # Panics
+    /// Panics if a scope has a parent that isn't one of the scopes with a lower priority
     #[must_use]
     pub(crate) fn new_with_sort(mut scopes: Vec<ScopeData>) -> Self {
         scopes.sort_by_key(|scope| scope.priority);
+        for scope in &scopes {
+            if let Some(parent) = scope.parent {
+                assert!(
+                    parent < scope.priority && scopes.iter().any(|scope| scope.priority == parent),
+                    "parent of scope `{}` should be a scope with a lower priority",
+                    scope.name,
+                );
+            }
+        }
         Self::from_sorted(scopes)
     }
 
@@ -131,19 +186,65 @@ impl ScopeDataWithChildScopesData {
         }
     }
 
-    #[inline]
+    /// Child scope on the branch to the first scope accepted by `is_target`:
+    /// the first direct child that is accepted or has an accepted descendant.
+    /// Returns `None` if there's no such child.
     #[must_use]
-    pub(crate) fn child(mut self) -> Self {
-        if self.child_scopes_data.is_empty() {
-            Self {
-                scope_data: None,
-                child_scopes_data: Vec::new(),
-            }
-        } else {
-            let scope_data = self.child_scopes_data.remove(0);
-            Self {
-                scope_data: Some(scope_data),
-                child_scopes_data: self.child_scopes_data,
+    pub(crate) fn child(&self, is_target: impl Fn(&ScopeData) -> bool) -> Option<Self> {
+        let priority = self.scope_data.as_ref()?.priority;
+        let scopes = &self.child_scopes_data;
+        let index = (0..scopes.len())
+            .filter(|&index| self.parent_priority(index) == Some(priority))
+            .find(|&index| {
+                is_target(&scopes[index])
+                    || (index + 1..scopes.len())
+                        .any(|descendant| is_target(&scopes[descendant]) && self.is_descendant(descendant, scopes[index].priority))
+            })?;
+
+        Some(Self {
+            scope_data: Some(scopes[index].clone()),
+            child_scopes_data: scopes[index + 1..].to_vec(),
+        })
+    }
+
+    #[must_use]
+    pub(crate) fn has_children(&self) -> bool {
+        self.scope_data.as_ref().is_some_and(|scope_data| {
+            (0..self.child_scopes_data.len()).any(|index| self.parent_priority(index) == Some(scope_data.priority))
+        })
+    }
+
+    /// Whether `ancestor` is `scope` or one of its ancestors
+    #[must_use]
+    pub(crate) fn is_ancestor_or_self(&self, ancestor: &ScopeData, scope: &ScopeData) -> bool {
+        if ancestor.priority == scope.priority {
+            return true;
+        }
+        self.child_scopes_data
+            .iter()
+            .position(|child| child.priority == scope.priority)
+            .is_some_and(|index| self.is_descendant(index, ancestor.priority))
+    }
+
+    /// Priority of the parent of `child_scopes_data[index]`
+    fn parent_priority(&self, index: usize) -> Option<u8> {
+        self.child_scopes_data[index].parent.or_else(|| match index.checked_sub(1) {
+            Some(preceding) => Some(self.child_scopes_data[preceding].priority),
+            None => self.scope_data.as_ref().map(|scope_data| scope_data.priority),
+        })
+    }
+
+    /// Whether `child_scopes_data[index]` is a descendant of the scope with `ancestor` priority
+    fn is_descendant(&self, mut index: usize, ancestor: u8) -> bool {
+        loop {
+            match self.parent_priority(index) {
+                Some(parent) if parent == ancestor => return true,
+                // Parents have lower priorities, so the walk ends at the scopes before `ancestor`
+                Some(parent) if parent > ancestor => match self.child_scopes_data.iter().position(|scope| scope.priority == parent) {
+                    Some(parent_index) => index = parent_index,
+                    None => return false,
+                },
+                _ => return false,
             }
         }
     }
@@ -218,8 +319,9 @@ mod tests {
             app,
             ScopeData {
                 priority: 1,
-                name: "app",
+                name: "app".into(),
                 is_skipped_by_default: false,
+                parent: None,
             }
         );
 
@@ -231,8 +333,9 @@ mod tests {
             runtime,
             ScopeData {
                 priority: 0,
-                name: "runtime",
+                name: "runtime".into(),
                 is_skipped_by_default: true,
+                parent: None,
             }
         );
     }
@@ -292,30 +395,67 @@ mod tests {
         assert_eq!(root.scope_data, Some(ScopeData::from(App)));
         assert_eq!(root.child_scopes_data.len(), 2);
 
-        let first = root.child();
+        let first = root.child(|_| true).unwrap();
         assert_eq!(first.scope_data, Some(ScopeData::from(Request)));
         assert_eq!(first.child_scopes_data.len(), 1);
         assert_eq!(first.child_scopes_data, vec![ScopeData::from(Step)]);
 
-        let second = first.child();
+        let second = first.child(|_| true).unwrap();
         assert_eq!(second.scope_data, Some(ScopeData::from(Step)));
         assert!(second.child_scopes_data.is_empty());
+        assert!(!second.has_children());
+        assert!(second.child(|_| true).is_none());
+
+        // Skipped on the way to the target
+        let step = root.child(|scope| scope.priority == Step.priority()).unwrap();
+        assert_eq!(step.scope_data, Some(ScopeData::from(Request)));
+        assert!(root.child(|scope| scope.priority == Runtime.priority()).is_none());
+        assert!(root.has_children());
+    }
 
-        let third = second.child();
-        assert_eq!(third.scope_data, None);
-        assert!(third.child_scopes_data.is_empty());
+    #[test]
+    fn test_child_with_branches() {
+        let scope = |priority, name: &'static str, parent| ScopeData {
+            parent,
+            ..ScopeData::new(priority, name)
+        };
+        let app = scope(0, "app", None);
+        let request = scope(1, "request", None);
+        let action = scope(2, "action", None);
+        let job = scope(3, "job", Some(0));
+        let job_step = scope(4, "job_step", None);
+
+        let root = ScopeDataWithChildScopesData::new_with_sort(vec![job_step.clone(), job.clone(), action.clone(), request.clone(), app]);
+        assert_eq!(root.child(|_| true).unwrap().scope_data, Some(request));
+        assert_eq!(root.child(|scope| scope.name == "job").unwrap().scope_data, Some(job.clone()));
+
+        let job_container = root.child(|scope| scope.name == "job_step").unwrap();
+        assert_eq!(job_container.scope_data, Some(job));
+        assert_eq!(job_container.child(|_| true).unwrap().scope_data, Some(job_step));
+
+        // `job` isn't a descendant of `request`
+        let request_container = root.child(|_| true).unwrap();
+        assert_eq!(request_container.child(|_| true).unwrap().scope_data, Some(action));
+        assert!(request_container.child(|scope| scope.name == "job").is_none());
+        assert!(request_container.child(|scope| scope.name == "job_step").is_none());
+
+        let action_container = request_container.child(|_| true).unwrap();
+        assert!(!action_container.has_children());
+    }
 
-        // Calling child() on an already-exhausted value stays None.
-        let fourth = third.child();
-        assert_eq!(fourth.scope_data, None);
-        assert!(fourth.child_scopes_data.is_empty());
+    #[test]
+    #[should_panic(expected = "parent of scope `request` should be a scope with a lower priority")]
+    fn test_new_with_sort_invalid_parent() {
+        let mut request = ScopeData::from(Request);
+        request.parent = Some(Step.priority());
+        let _ = ScopeDataWithChildScopesData::new_with_sort(vec![ScopeData::from(App), request, ScopeData::from(Step)]);
     }
 
     #[test]
     fn test_new() {
         let app = ScopeData::from(App);
         let children = vec![ScopeData::from(Request), ScopeData::from(Step)];
-        let built = ScopeDataWithChildScopesData::new(app, children.clone());
+        let built = ScopeDataWithChildScopesData::new(app.clone(), children.clone());
         assert_eq!(built.scope_data, Some(app));
         assert_eq!(built.child_scopes_data, children);
     }
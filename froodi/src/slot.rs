@@ -0,0 +1,275 @@
+use alloc::{collections::btree_map::BTreeMap, vec::Vec};
+use core::{
+    any::TypeId,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    any::{Map, TypeInfo},
+    utils::thread_safety::{RcAnyThreadSafety, RcThreadSafety},
+};
+
+/// Dense slot indexes of the types of a frozen registry, see [`crate::Registry::freeze`].
+///
+/// Slots are found in an open addressing table keyed by the already hashed `TypeId`,
+/// so a lookup doesn't depend on the number of registered types.
+#[derive(Default)]
+pub(crate) struct SlotIndex {
+    /// Power of two sized, at most half full, so probing always ends on an empty entry
+    table: Vec<Option<(TypeId, usize)>>,
+    types: Vec<TypeInfo>,
+}
+
+impl SlotIndex {
+    #[must_use]
+    pub(crate) fn new(types: Vec<TypeInfo>) -> Self {
+        let mut table = Vec::new();
+        if !types.is_empty() {
+            table.resize((types.len() * 2).next_power_of_two(), None);
+        }
+        let mask = table.len().wrapping_sub(1);
+        for (slot, type_info) in types.iter().enumerate() {
+            let mut position = hash(&type_info.id) & mask;
+            while table[position].is_some() {
+                position = (position + 1) & mask;
+            }
+            table[position] = Some((type_info.id, slot));
+        }
+        Self { table, types }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn get(&self, type_info: &TypeInfo) -> Option<usize> {
+        if self.table.is_empty() {
+            return None;
+        }
+        let mask = self.table.len() - 1;
+        let mut position = hash(&type_info.id) & mask;
+        loop {
+            match self.table[position] {
+                Some((id, slot)) if id == type_info.id => return Some(slot),
+                Some(_) => position = (position + 1) & mask,
+                None => return None,
+            }
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn len(&self) -> usize {
+        self.types.len()
+    }
+}
+
+#[inline]
+fn hash(id: &TypeId) -> usize {
+    let mut hasher = IdHasher(0);
+    id.hash(&mut hasher);
+    #[allow(clippy::cast_possible_truncation)]
+    {
+        hasher.0 as usize
+    }
+}
+
+/// `TypeId` is already a hash, so it only has to be mixed into the low bits used by the table
+struct IdHasher(u64);
+
+impl Hasher for IdHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut buf = [0; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(buf));
+        }
+    }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(29) ^ n).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        self.0 ^= self.0 >> 32;
+    }
+}
+
+/// Entries of a frozen registry in slot order
+#[derive(Clone)]
+pub(crate) struct Frozen<T> {
+    pub(crate) index: RcThreadSafety<SlotIndex>,
+    entries: Vec<T>,
+}
+
+impl<T: Clone> Frozen<T> {
+    #[must_use]
+    pub(crate) fn new(entries: &BTreeMap<TypeInfo, T>) -> Self {
+        Self {
+            index: RcThreadSafety::new(SlotIndex::new(entries.keys().cloned().collect())),
+            entries: entries.values().cloned().collect(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn get(&self, type_info: &TypeInfo) -> Option<&T> {
+        self.index.get(type_info).map(|slot| &self.entries[slot])
+    }
+}
+
+/// Cached values stored by slot of a [`SlotIndex`].
+/// Values of types without a slot, like the ones only in the context, are kept in a map.
+#[derive(Clone)]
+pub(crate) struct SlotMap {
+    index: RcThreadSafety<SlotIndex>,
+    slots: Vec<Option<RcAnyThreadSafety>>,
+    slotted: usize,
+    unslotted: Map,
+}
+
+impl Default for SlotMap {
+    fn default() -> Self {
+        Self::new(RcThreadSafety::default())
+    }
+}
+
+impl SlotMap {
+    #[must_use]
+    pub(crate) fn new(index: RcThreadSafety<SlotIndex>) -> Self {
+        let mut slots = Vec::new();
+        slots.resize(index.len(), None);
+        Self {
+            index,
+            slots,
+            slotted: 0,
+            unslotted: Map::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn get(&self, type_info: &TypeInfo) -> Option<&RcAnyThreadSafety> {
+        match self.index.get(type_info) {
+            Some(slot) => self.slots[slot].as_ref(),
+            None => self.unslotted.get(type_info),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn contains_key(&self, type_info: &TypeInfo) -> bool {
+        self.get(type_info).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, type_info: TypeInfo, value: RcAnyThreadSafety) -> Option<RcAnyThreadSafety> {
+        match self.index.get(&type_info) {
+            Some(slot) => {
+                let previous = self.slots[slot].replace(value);
+                if previous.is_none() {
+                    self.slotted += 1;
+                }
+                previous
+            }
+            None => self.unslotted.insert(type_info, value),
+        }
+    }
+
+    /// Removes all values, then inserts the ones of `map`
+    pub(crate) fn reset(&mut self, map: &Map) {
+        self.slots.iter_mut().for_each(|slot| *slot = None);
+        self.slotted = 0;
+        self.unslotted.clear();
+        self.extend(map.iter().map(|(type_info, value)| (type_info.clone(), value.clone())));
+    }
+
+    #[cfg(test)]
+    #[inline]
+    #[must_use]
+    pub(crate) fn len(&self) -> usize {
+        self.slotted + self.unslotted.len()
+    }
+
+    #[cfg(test)]
+    #[inline]
+    #[must_use]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&TypeInfo, &RcAnyThreadSafety)> {
+        self.index
+            .types
+            .iter()
+            .zip(&self.slots)
+            .filter_map(|(type_info, value)| Some((type_info, value.as_ref()?)))
+            .chain(&self.unslotted)
+    }
+
+    #[inline]
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &TypeInfo> {
+        self.iter().map(|(type_info, _)| type_info)
+    }
+}
+
+impl Extend<(TypeInfo, RcAnyThreadSafety)> for SlotMap {
+    fn extend<I: IntoIterator<Item = (TypeInfo, RcAnyThreadSafety)>>(&mut self, iter: I) {
+        for (type_info, value) in iter {
+            self.insert(type_info, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{SlotIndex, SlotMap};
+    use crate::{any::TypeInfo, utils::thread_safety::RcThreadSafety};
+
+    use alloc::{
+        format,
+        string::{String, ToString as _},
+        vec,
+    };
+    use tracing_test::traced_test;
+
+    struct A;
+    struct B;
+    struct C;
+    struct Unregistered;
+
+    #[test]
+    #[traced_test]
+    fn test_slot_index() {
+        let index = SlotIndex::new(vec![TypeInfo::of::<A>(), TypeInfo::of::<B>(), TypeInfo::of::<C>()]);
+
+        assert_eq!(index.get(&TypeInfo::of::<A>()), Some(0));
+        assert_eq!(index.get(&TypeInfo::of::<B>()), Some(1));
+        assert_eq!(index.get(&TypeInfo::of::<C>()), Some(2));
+        assert_eq!(index.get(&TypeInfo::of::<Unregistered>()), None);
+        assert_eq!(SlotIndex::default().get(&TypeInfo::of::<A>()), None);
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_slot_map() {
+        let index = RcThreadSafety::new(SlotIndex::new(vec![TypeInfo::of::<A>(), TypeInfo::of::<B>()]));
+        let mut map = SlotMap::new(index);
+
+        assert!(map.insert(TypeInfo::of::<A>(), RcThreadSafety::new(1u8)).is_none());
+        assert!(map.insert(TypeInfo::of::<Unregistered>(), RcThreadSafety::new(2u8)).is_none());
+        assert!(map.insert(TypeInfo::of::<A>(), RcThreadSafety::new(3u8)).is_some());
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&TypeInfo::of::<A>()));
+        assert!(!map.contains_key(&TypeInfo::of::<B>()));
+        assert!(map.contains_key(&TypeInfo::of::<Unregistered>()));
+        assert_eq!(map.keys().count(), 2);
+
+        map.reset(&crate::any::Map::new());
+        assert!(map.is_empty());
+        assert!(!map.contains_key(&TypeInfo::of::<A>()));
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(feature = "serde")]
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{any::TypeInfo, cache::Cache, context::Context, scope::ScopeData};
+
+/// State of a live container, built by [`crate::Container::describe`] and [`crate::async_impl::Container::describe`].
+///
+/// Serializable with the `serde` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ContainerSnapshot {
+    /// Levels of the container, from the root to the current one
+    pub levels: Vec<LevelSnapshot>,
+}
+
+/// State of one level (scope) of a container
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LevelSnapshot {
+    pub scope: ScopeData,
+    /// Types provided in the scope of the level
+    pub registered: Vec<&'static str>,
+    /// Types in the cache of the level, including ones cached from parent levels.
+    /// Context values aren't included, see [`Self::context`]
+    pub cached: Vec<&'static str>,
+    /// Resolved types waiting for their finalizers, in resolution order.
+    /// Finalizers are called from the end on close
+    pub pending_finalizers: Vec<&'static str>,
+    /// Types of the context values
+    pub context: Vec<&'static str>,
+}
+
+impl ContainerSnapshot {
+    /// Scopes of the levels, from the root to the current one
+    #[inline]
+    pub fn scope_path(&self) -> impl Iterator<Item = &ScopeData> {
+        self.levels.iter().map(|level| &level.scope)
+    }
+
+    /// The level of the described container
+    ///
+    /// # Panics
+    /// Panics if the snapshot has no levels, which can't happen for snapshots built by a container
+    #[inline]
+    #[must_use]
+    pub fn current(&self) -> &LevelSnapshot {
+        self.levels.last().expect("container snapshot should have at least one level")
+    }
+
+    /// Serializes the snapshot to JSON
+    #[cfg(feature = "serde")]
+    #[must_use]
+    #[allow(clippy::missing_panics_doc)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("snapshot serialization is infallible")
+    }
+}
+
+impl LevelSnapshot {
+    /// Builds a level from registered types and caches of the level.
+    /// `caches` are listed in the order their resolved sets are finalized in reverse.
+    pub(crate) fn new<'a>(
+        scope: ScopeData,
+        registered: impl IntoIterator<Item = &'a TypeInfo>,
+        caches: &[&Cache],
+        context: &Context,
+    ) -> Self {
+        let mut registered: Vec<_> = registered.into_iter().map(|type_info| type_info.name).collect();
+        registered.sort_unstable();
+        registered.dedup();
+
+        let mut cached: Vec<_> = caches
+            .iter()
+            .flat_map(|cache| cache.map.keys())
+            .filter(|type_info| !context.map.contains_key(type_info))
+            .map(|type_info| type_info.name)
+            .collect();
+        cached.sort_unstable();
+        cached.dedup();
+
+        let pending_finalizers = caches
+            .iter()
+            .flat_map(|cache| cache.resolved.0.iter())
+            .map(|resolved| resolved.type_info.name)
+            .collect();
+
+        let mut context: Vec<_> = context.map.keys().map(|type_info| type_info.name).collect();
+        context.sort_unstable();
+
+        Self {
+            scope,
+            registered,
+            cached,
+            pending_finalizers,
+            context,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    extern crate std;
+
+    use alloc::{
+        format,
+        string::{String, ToString as _},
+    };
+    use tracing_test::traced_test;
+
+    use crate::{registry, Container, DefaultScope};
+
+    struct A;
+
+    #[test]
+    #[traced_test]
+    fn test_snapshot_to_json() {
+        let container = Container::new(registry! {
+            scope(DefaultScope::App) [
+                provide(|| Ok(A)),
+            ],
+        });
+        container.get::<A>().unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&container.describe().to_json()).unwrap();
+        let levels = json["levels"].as_array().unwrap();
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[1]["scope"]["name"], "app");
+        assert_eq!(levels[1]["cached"][0], core::any::type_name::<A>());
+        assert_eq!(levels[1]["pending_finalizers"].as_array().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,148 @@
+//! Support of containers generated by [`crate::static_container`].
+//!
+//! A generated container resolves dependencies through [`StaticResolve`] and [`StaticResolveTransient`],
+//! implemented for each type provided in its scope or in one of its ancestors.
+
+use alloc::vec::Vec;
+
+use crate::{
+    dependency_resolver::DependencyResolver,
+    inject::{Inject, InjectTransient},
+    utils::{
+        lock::{Mutex, RwLock},
+        thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
+    },
+    ResolveErrorKind,
+};
+
+/// Resolves a scoped dependency, like [`crate::Container::get`]
+pub trait StaticResolve<Dep> {
+    #[allow(clippy::missing_errors_doc)]
+    fn resolve(&self) -> Result<RcThreadSafety<Dep>, ResolveErrorKind>;
+}
+
+/// Creates a fresh dependency, like [`crate::Container::get_transient`]
+pub trait StaticResolveTransient<Dep> {
+    #[allow(clippy::missing_errors_doc)]
+    fn resolve_transient(&self) -> Result<Dep, ResolveErrorKind>;
+}
+
+/// [`DependencyResolver`] for a generated container `C`
+pub trait StaticDependencyResolver<C>: DependencyResolver {
+    #[allow(clippy::missing_errors_doc)]
+    fn resolve_static(container: &C) -> Result<Self, ResolveErrorKind>;
+}
+
+impl<C, Dep> StaticDependencyResolver<C> for Inject<Dep>
+where
+    C: StaticResolve<Dep>,
+    Dep: SendSafety + SyncSafety + 'static,
+{
+    #[inline]
+    fn resolve_static(container: &C) -> Result<Self, ResolveErrorKind> {
+        container.resolve().map(Self)
+    }
+}
+
+impl<C, Dep> StaticDependencyResolver<C> for InjectTransient<Dep>
+where
+    C: StaticResolveTransient<Dep>,
+    Dep: 'static,
+{
+    #[inline]
+    fn resolve_static(container: &C) -> Result<Self, ResolveErrorKind> {
+        container.resolve_transient().map(Self)
+    }
+}
+
+macro_rules! impl_static_dependency_resolver {
+    (
+        [$($ty:ident),*]
+    ) => {
+        #[allow(unused_variables)]
+        impl<C, $($ty,)*> StaticDependencyResolver<C> for ($($ty,)*)
+        where
+            $( $ty: StaticDependencyResolver<C> + SendSafety, )*
+        {
+            #[inline]
+            fn resolve_static(container: &C) -> Result<Self, ResolveErrorKind> {
+                Ok(($($ty::resolve_static(container)?,)*))
+            }
+        }
+    };
+}
+
+all_the_tuples!(impl_static_dependency_resolver);
+
+/// Field of a generated container with the cached instance of a dependency
+/// and the instances waiting for the finalizer, in resolution order
+#[doc(hidden)]
+pub struct Slot<T> {
+    value: RwLock<Option<RcThreadSafety<T>>>,
+    pending: Mutex<Vec<RcThreadSafety<T>>>,
+}
+
+impl<T> Default for Slot<T> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            value: RwLock::new(None),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T> Slot<T> {
+    /// Returns the cached instance or caches the created one.
+    /// Instantiation is serialized by the lock of the slot, dependencies of the instance are in other slots,
+    /// because the macro rejects cycles.
+    #[inline]
+    pub fn get_or_try_init<E>(&self, init: impl FnOnce() -> Result<T, E>) -> Result<(RcThreadSafety<T>, bool), E> {
+        if let Some(value) = &*self.value.read() {
+            return Ok((value.clone(), false));
+        }
+
+        let mut value = self.value.write();
+        if let Some(value) = &*value {
+            return Ok((value.clone(), false));
+        }
+        let instance = RcThreadSafety::new(init()?);
+        *value = Some(instance.clone());
+        Ok((instance, true))
+    }
+
+    #[inline]
+    pub fn push_pending(&self, instance: RcThreadSafety<T>) {
+        self.pending.lock().push(instance);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn pop_pending(&self) -> Option<RcThreadSafety<T>> {
+        self.pending.lock().pop()
+    }
+
+    /// Drops the cached instance, so the next resolution creates a new one
+    #[inline]
+    pub fn reset(&self) {
+        self.value.write().take();
+    }
+}
+
+/// Slots of the instances waiting for finalizers of a generated container, in resolution order
+#[doc(hidden)]
+#[derive(Default)]
+pub struct ResolvedOrder(Mutex<Vec<usize>>);
+
+impl ResolvedOrder {
+    #[inline]
+    pub fn push(&self, slot: usize) {
+        self.0.lock().push(slot);
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn pop(&self) -> Option<usize> {
+        self.0.lock().pop()
+    }
+}
@@ -0,0 +1,540 @@
+//! Containers with the current scope in their type, so resolving a dependency of an inaccessible scope doesn't compile.
+//!
+//! Scopes are marker types implementing [`TypedScope`], the ones of [`DefaultScope`] are declared here,
+//! other scopes are declared with [`crate::typed_scopes`]. Dependencies declare their scope by implementing [`Scoped`].
+//!
+//! # Examples
+//! ```rust
+//! use froodi::{
+//!     registry,
+//!     typed::{App, Request, Scoped, TypedContainer},
+//!     DefaultScope, Inject,
+//! };
+//!
+//! struct Config;
+//! struct RequestId(u64);
+//!
+//! impl Scoped for Config {
+//!     type Scope = App;
+//! }
+//! impl Scoped for RequestId {
+//!     type Scope = Request;
+//! }
+//!
+//! let registry = registry! {
+//!     scope(DefaultScope::App) [
+//!         provide(|| Ok(Config)),
+//!     ],
+//!     scope(DefaultScope::Request) [
+//!         provide(|Inject(_): Inject<Config>| Ok(RequestId(1))),
+//!     ],
+//! };
+//!
+//! let app = TypedContainer::<App>::new(registry);
+//! let _config = app.get::<Config>().unwrap();
+//!
+//! let request: TypedContainer<Request> = app.enter_build().unwrap();
+//! assert_eq!(request.get::<RequestId>().unwrap().0, 1);
+//! let _config = request.get::<Config>().unwrap();
+//! ```
+//!
+//! Resolving a dependency of a descendant scope doesn't compile:
+//! ```rust,compile_fail
+//! use froodi::{
+//!     registry,
+//!     typed::{App, Request, Scoped, TypedContainer},
+//! };
+//!
+//! struct RequestId(u64);
+//!
+//! impl Scoped for RequestId {
+//!     type Scope = Request;
+//! }
+//!
+//! let app = TypedContainer::<App>::new(registry! {});
+//! let _request_id = app.get::<RequestId>();
+//! ```
+
+use core::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+use crate::{
+    dependency_resolver::DependencyResolver,
+    utils::thread_safety::{RcThreadSafety, SendSafety, SyncSafety},
+    Container, Context, DefaultScope, Instantiator, Registry, ResolveErrorKind, Scope, ScopeWithErrorKind,
+};
+
+/// Marker type of a scope
+pub trait TypedScope: Sized + 'static {
+    type Scope: Scope;
+
+    #[must_use]
+    fn scope() -> Self::Scope;
+}
+
+/// Implemented by a scope for itself and its ancestors, whose dependencies are accessible from it
+pub trait Within<S: TypedScope>: TypedScope {}
+
+/// Implemented by a scope for its ancestors, so it can be entered from them
+pub trait Below<S: TypedScope>: TypedScope {}
+
+/// Next scope not skipped by default, the one entered by [`TypedContainer::enter_build`]
+pub trait NextScope: TypedScope {
+    type Next: Below<Self>;
+}
+
+/// Scope of the provider of a dependency.
+///
+/// Registering the provider with a `provide_typed(instantiator)` entry of [`crate::registry`] takes its scope from here,
+/// so the registration and the impl can't disagree. Providers registered in an explicit scope aren't checked against it.
+pub trait Scoped {
+    type Scope: TypedScope;
+}
+
+/// Scope of the type provided by the instantiator, see [`Scoped`]
+#[doc(hidden)]
+#[inline]
+#[must_use]
+pub fn scope_of<Inst, Deps>(_instantiator: &Inst) -> <<Inst::Provides as Scoped>::Scope as TypedScope>::Scope
+where
+    Inst: Instantiator<Deps>,
+    Inst::Provides: Scoped,
+    Deps: DependencyResolver,
+{
+    <Inst::Provides as Scoped>::Scope::scope()
+}
+
+/// Declares marker types of the scopes of a [`Scope`], ordered by priority.
+/// Scopes skipped by default are prefixed with `skip`.
+///
+/// Scopes separated by commas form a chain, each one is a child of the preceding one.
+/// A scope with several children, as in scope trees declared with `#[scope(parent = ...)]` of `#[derive(Scope)]`,
+/// is followed by its branches in brackets, each a chain of its own. Branches end the chain they're in.
+/// The first branch is the one entered by [`TypedContainer::enter_build`], as the first child is with [`Container::enter_build`].
+///
+/// The macro can't see the parents of the scopes, so the tree must be declared as the [`Scope`] defines it.
+///
+/// # Examples
+#[cfg_attr(feature = "macros", doc = "```rust")]
+#[cfg_attr(not(feature = "macros"), doc = "```rust,ignore")]
+/// use froodi::{
+///     registry,
+///     typed::{Scoped, TypedContainer},
+/// };
+///
+/// mod job {
+///     use froodi::Scope;
+///
+///     #[derive(Scope, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+///     pub enum JobScope {
+///         #[scope(skip)]
+///         Runtime,
+///         Worker,
+///         Job,
+///         #[scope(parent = Worker)]
+///         Health,
+///     }
+///
+///     // `Job` and `Health` are siblings, children of `Worker`
+///     froodi::typed_scopes!(pub JobScope { skip Runtime, Worker [Job] [Health] });
+/// }
+///
+/// struct JobId(u64);
+///
+/// impl Scoped for JobId {
+///     type Scope = job::Job;
+/// }
+///
+/// let registry = registry! {
+///     provide_typed(|| Ok(JobId(1))),
+/// };
+/// let worker = TypedContainer::<job::Worker>::new(registry);
+/// let job = worker.clone().enter_build().unwrap();
+/// assert_eq!(job.get::<JobId>().unwrap().0, 1);
+/// let _health = worker.enter::<job::Health>().unwrap();
+/// ```
+///
+/// Resolving a dependency of a sibling scope doesn't compile:
+#[cfg_attr(feature = "macros", doc = "```rust,compile_fail")]
+#[cfg_attr(not(feature = "macros"), doc = "```rust,ignore")]
+/// use froodi::{registry, typed::{Scoped, TypedContainer}, Scope};
+///
+/// #[derive(Scope, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// enum JobScope {
+///     Worker,
+///     Job,
+///     #[scope(parent = Worker)]
+///     Health,
+/// }
+///
+/// froodi::typed_scopes!(JobScope { Worker [Job] [Health] });
+///
+/// struct JobId(u64);
+///
+/// impl Scoped for JobId {
+///     type Scope = Job;
+/// }
+///
+/// let health = TypedContainer::<Worker>::new(registry! {}).enter::<Health>().unwrap();
+/// let _job_id = health.get::<JobId>();
+/// ```
+#[macro_export]
+macro_rules! typed_scopes {
+    // Each scope takes the ancestors and the scopes waiting for the next scope not skipped by default
+    (@chain $vis:vis $scope:ident $ancestors:tt $pending:tt) => {};
+    (@chain $vis:vis $scope:ident [$($ancestors:ident)*] [$($pending:ident)*] skip $name:ident $($rest:tt)*) => {
+        $crate::typed_scopes!(@scope $vis $scope [$($ancestors)*] $name);
+        $crate::typed_scopes!(@then $vis $scope [$($ancestors)* $name] [$($pending)* $name] $($rest)*);
+    };
+    (@chain $vis:vis $scope:ident [$($ancestors:ident)*] [$($pending:ident)*] $name:ident $($rest:tt)*) => {
+        $crate::typed_scopes!(@scope $vis $scope [$($ancestors)*] $name);
+        $(
+            impl $crate::typed::NextScope for $pending {
+                type Next = $name;
+            }
+        )*
+        $crate::typed_scopes!(@then $vis $scope [$($ancestors)* $name] [$name] $($rest)*);
+    };
+    // The first branch continues the chain, so the scopes waiting for the next one are passed to it
+    (@then $vis:vis $scope:ident $ancestors:tt $pending:tt $([$($branch:tt)+])+ , $($rest:tt)*) => {
+        compile_error!("Branches must end the chain they're in");
+    };
+    (@then $vis:vis $scope:ident $ancestors:tt $pending:tt [$($first:tt)+] $([$($branch:tt)+])*) => {
+        $crate::typed_scopes!(@chain $vis $scope $ancestors $pending $($first)+);
+        $(
+            $crate::typed_scopes!(@chain $vis $scope $ancestors [] $($branch)+);
+        )*
+    };
+    (@then $vis:vis $scope:ident $ancestors:tt $pending:tt $(, $($rest:tt)*)?) => {
+        $crate::typed_scopes!(@chain $vis $scope $ancestors $pending $($($rest)*)?);
+    };
+    (@scope $vis:vis $scope:ident [$($ancestors:ident)*] $name:ident) => {
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        $vis struct $name;
+
+        impl $crate::typed::TypedScope for $name {
+            type Scope = $scope;
+
+            #[inline]
+            fn scope() -> $scope {
+                $scope::$name
+            }
+        }
+
+        impl $crate::typed::Within<$name> for $name {}
+        $(
+            impl $crate::typed::Within<$ancestors> for $name {}
+            impl $crate::typed::Below<$ancestors> for $name {}
+        )*
+    };
+    ($vis:vis $scope:ident { $($scopes:tt)+ }) => {
+        $crate::typed_scopes!(@chain $vis $scope [] [] $($scopes)+);
+    };
+}
+
+typed_scopes!(pub DefaultScope { skip Runtime, App, skip Session, Request, Action, Step });
+
+/// Wrapper of a [`Container`] of the scope `S`.
+///
+/// Dependencies are resolved only if they declare a scope accessible from `S`, see [`Scoped`].
+/// The dynamic container is still available with [`Self::container`].
+pub struct TypedContainer<S> {
+    container: Container,
+    scope: PhantomData<fn() -> S>,
+}
+
+impl<S: TypedScope> TypedContainer<S> {
+    /// Creates a container starting at the scope `S`, see [`Container::new_with_start_scope`]
+    ///
+    /// # Panics
+    /// See [`Container::new_with_start_scope`].
+    #[inline]
+    #[must_use]
+    pub fn new(registry: Registry) -> Self {
+        Self::from_container(Container::new_with_start_scope(registry, S::scope()))
+    }
+
+    #[inline]
+    #[must_use]
+    fn from_container(container: Container) -> Self {
+        Self {
+            container,
+            scope: PhantomData,
+        }
+    }
+
+    /// Enters the scope `C`, a descendant of `S`
+    ///
+    /// # Errors
+    /// Returns [`ScopeWithErrorKind`] if the scope isn't in the registry.
+    #[inline]
+    pub fn enter<C: Below<S>>(self) -> Result<TypedContainer<C>, ScopeWithErrorKind> {
+        self.container
+            .enter()
+            .with_scope(C::scope())
+            .build()
+            .map(TypedContainer::from_container)
+    }
+
+    /// Enters the scope `C`, a descendant of `S`, with the context
+    ///
+    /// # Errors
+    /// Returns [`ScopeWithErrorKind`] if the scope isn't in the registry.
+    #[inline]
+    pub fn enter_with_context<C: Below<S>>(self, context: Context) -> Result<TypedContainer<C>, ScopeWithErrorKind> {
+        self.container
+            .enter()
+            .with_scope(C::scope())
+            .with_context(context)
+            .build()
+            .map(TypedContainer::from_container)
+    }
+
+    /// Enters the next scope not skipped by default, like [`Container::enter_build`]
+    ///
+    /// # Errors
+    /// Returns [`ScopeWithErrorKind`] if the scope isn't in the registry.
+    #[inline]
+    pub fn enter_build(self) -> Result<TypedContainer<S::Next>, ScopeWithErrorKind>
+    where
+        S: NextScope,
+    {
+        self.enter()
+    }
+
+    /// Gets a scoped dependency, see [`Container::get`]
+    #[inline]
+    #[allow(clippy::missing_errors_doc)]
+    pub fn get<Dep>(&self) -> Result<RcThreadSafety<Dep>, ResolveErrorKind>
+    where
+        Dep: Scoped + SendSafety + SyncSafety + 'static,
+        S: Within<Dep::Scope>,
+    {
+        self.container.get()
+    }
+
+    /// Creates a fresh dependency, see [`Container::get_transient`]
+    #[inline]
+    #[allow(clippy::missing_errors_doc)]
+    pub fn get_transient<Dep>(&self) -> Result<Dep, ResolveErrorKind>
+    where
+        Dep: Scoped + 'static,
+        S: Within<Dep::Scope>,
+    {
+        self.container.get_transient()
+    }
+
+    /// See [`Container::close`]
+    #[inline]
+    pub fn close(&self) {
+        self.container.close();
+    }
+
+    /// Dynamic container, for dependencies that don't implement [`Scoped`]
+    #[inline]
+    #[must_use]
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Container {
+        self.container
+    }
+}
+
+impl<S> Clone for TypedContainer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            container: self.container.clone(),
+            scope: PhantomData,
+        }
+    }
+}
+
+impl<S> Debug for TypedContainer<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.container, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::{Action, App, Request, Scoped, Session, Step, TypedContainer};
+    use crate::{registry, utils::thread_safety::RcThreadSafety, DefaultScope, Inject, Scope, ScopeData, Scopes};
+
+    use alloc::{
+        format,
+        string::{String, ToString as _},
+    };
+    use tracing_test::traced_test;
+
+    struct AppValue;
+    struct SessionValue;
+    struct RequestValue;
+
+    impl Scoped for AppValue {
+        type Scope = App;
+    }
+    impl Scoped for SessionValue {
+        type Scope = Session;
+    }
+    impl Scoped for RequestValue {
+        type Scope = Request;
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_typed_container() {
+        let app = TypedContainer::<App>::new(registry! {
+            scope(DefaultScope::App) [
+                provide(|| Ok(AppValue)),
+            ],
+            scope(DefaultScope::Session) [
+                provide(|| Ok(SessionValue)),
+            ],
+            scope(DefaultScope::Request) [
+                provide(|Inject(_): Inject<AppValue>| Ok(RequestValue)),
+            ],
+        });
+        let app_value = app.get::<AppValue>().unwrap();
+
+        // Session is skipped, like with the dynamic container
+        let request = app.clone().enter_build().unwrap();
+        assert!(RcThreadSafety::ptr_eq(&app_value, &request.get::<AppValue>().unwrap()));
+        request.get::<RequestValue>().unwrap();
+        // The skipped session is still a parent of the request
+        request.get::<SessionValue>().unwrap();
+
+        let session = app.enter::<Session>().unwrap();
+        session.get::<SessionValue>().unwrap();
+        let request = session.enter_build().unwrap();
+        request.get::<SessionValue>().unwrap();
+        request.get_transient::<RequestValue>().unwrap();
+
+        let step: TypedContainer<Step> = request.enter_build().and_then(TypedContainer::<Action>::enter_build).unwrap();
+        step.get::<AppValue>().unwrap();
+        step.close();
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_provide_typed() {
+        let app = TypedContainer::<App>::new(registry! {
+            provide_typed(|| Ok(AppValue)),
+            provide_typed(|Inject(_): Inject<AppValue>| Ok(RequestValue)),
+            scope(DefaultScope::Session) [
+                provide(|| Ok(SessionValue)),
+            ],
+        });
+        let app_value = app.get::<AppValue>().unwrap();
+
+        let request = app.clone().enter_build().unwrap();
+        assert!(RcThreadSafety::ptr_eq(&app_value, &request.get::<AppValue>().unwrap()));
+        let request_value = request.get::<RequestValue>().unwrap();
+        assert!(RcThreadSafety::ptr_eq(&request_value, &request.get::<RequestValue>().unwrap()));
+        // Cached in the request, not in the app
+        let request = app.enter_build().unwrap();
+        assert!(!RcThreadSafety::ptr_eq(&request_value, &request.get::<RequestValue>().unwrap()));
+    }
+
+    #[test]
+    #[traced_test]
+    fn test_typed_scopes_tree() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        enum TreeScope {
+            Runtime,
+            Worker,
+            Job,
+            Health,
+        }
+
+        impl From<TreeScope> for ScopeData {
+            fn from(scope: TreeScope) -> Self {
+                Self {
+                    priority: scope.priority(),
+                    name: scope.name().into(),
+                    is_skipped_by_default: scope.is_skipped_by_default(),
+                    parent: scope.parent().map(|parent| parent.priority()),
+                }
+            }
+        }
+
+        impl Scope for TreeScope {
+            fn name(&self) -> &'static str {
+                match self {
+                    TreeScope::Runtime => "runtime",
+                    TreeScope::Worker => "worker",
+                    TreeScope::Job => "job",
+                    TreeScope::Health => "health",
+                }
+            }
+
+            fn priority(&self) -> u8 {
+                *self as u8
+            }
+
+            fn is_skipped_by_default(&self) -> bool {
+                matches!(self, TreeScope::Runtime)
+            }
+
+            fn parent(&self) -> Option<Self> {
+                match self {
+                    TreeScope::Health => Some(TreeScope::Worker),
+                    _ => None,
+                }
+            }
+        }
+
+        impl Scopes<3> for TreeScope {
+            type Scope = Self;
+
+            fn all() -> (Self::Scope, [Self::Scope; 3]) {
+                use TreeScope::{Health, Job, Runtime, Worker};
+
+                (Runtime, [Worker, Job, Health])
+            }
+        }
+
+        typed_scopes!(TreeScope { skip Runtime, Worker [Job] [Health] });
+
+        struct WorkerValue;
+        struct JobValue;
+        struct HealthValue;
+
+        impl Scoped for WorkerValue {
+            type Scope = Worker;
+        }
+        impl Scoped for JobValue {
+            type Scope = Job;
+        }
+        impl Scoped for HealthValue {
+            type Scope = Health;
+        }
+
+        let runtime = TypedContainer::<Runtime>::new(registry! {
+            provide_typed(|| Ok(WorkerValue)),
+            provide_typed(|Inject(_): Inject<WorkerValue>| Ok(JobValue)),
+            provide_typed(|Inject(_): Inject<WorkerValue>| Ok(HealthValue)),
+        });
+        // Runtime is skipped, so the worker is the next scope
+        let worker: TypedContainer<Worker> = runtime.enter_build().unwrap();
+        let worker_value = worker.get::<WorkerValue>().unwrap();
+
+        // The first branch is the next scope of the worker
+        let job: TypedContainer<Job> = worker.clone().enter_build().unwrap();
+        job.get::<JobValue>().unwrap();
+        assert!(RcThreadSafety::ptr_eq(&worker_value, &job.get::<WorkerValue>().unwrap()));
+
+        let health = worker.enter::<Health>().unwrap();
+        health.get::<HealthValue>().unwrap();
+        assert!(RcThreadSafety::ptr_eq(&worker_value, &health.get::<WorkerValue>().unwrap()));
+        // Job is a sibling of health, so it's only reachable from the dynamic container, where it fails
+        assert!(health.container().get::<JobValue>().is_err());
+    }
+}
@@ -2,6 +2,7 @@ pub(crate) mod future;
 pub(crate) mod hlist;
 pub(crate) mod merge;
 
+pub mod lock;
 pub mod thread_safety;
 
 pub use merge::Merge;
@@ -0,0 +1,414 @@
+//! Lock primitives of containers and caches.
+//!
+//! Without the `thread_safe` feature, `RefCell` is used, so single-threaded containers don't pay for atomic operations.
+//! Otherwise the backend is chosen by the cargo features, in order of priority:
+//! - `parking_lot` (enabled by default)
+//! - `spin`, which doesn't need `std`, so `thread_safe` containers can be used in `no_std` environments
+//! - `std_sync`, which uses `std::sync` and recovers poisoned locks, because a panic can't leave a cache half-updated
+//!
+//! If none of them is enabled with the `thread_safe` feature, `spin` is used.
+
+use core::ops::{Deref, DerefMut};
+
+/// Reader-writer lock of a backend
+pub trait RwLockBackend<T> {
+    type ReadGuard<'a>: Deref<Target = T>
+    where
+        Self: 'a;
+    type WriteGuard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self;
+    fn read(&self) -> Self::ReadGuard<'_>;
+    fn write(&self) -> Self::WriteGuard<'_>;
+    fn get_mut(&mut self) -> &mut T;
+
+    /// Like [`Self::read`], but returns `None` if the lock is already held by the caller and the backend can detect it.
+    /// Thread-safe backends can't tell the caller from other threads, so they block
+    #[inline]
+    fn read_checked(&self) -> Option<Self::ReadGuard<'_>> {
+        Some(self.read())
+    }
+
+    /// Like [`Self::write`], but returns `None` if the lock is already held by the caller and the backend can detect it.
+    /// Thread-safe backends can't tell the caller from other threads, so they block
+    #[inline]
+    fn write_checked(&self) -> Option<Self::WriteGuard<'_>> {
+        Some(self.write())
+    }
+}
+
+/// Mutual exclusion lock of a backend
+pub trait MutexBackend<T> {
+    type Guard<'a>: DerefMut<Target = T>
+    where
+        Self: 'a;
+
+    fn new(value: T) -> Self;
+    fn lock(&self) -> Self::Guard<'_>;
+}
+
+#[cfg(not(feature = "thread_safe"))]
+mod cell_backend {
+    use core::cell::{Ref, RefCell, RefMut};
+
+    use super::{MutexBackend, RwLockBackend};
+
+    impl<T> RwLockBackend<T> for RefCell<T> {
+        type ReadGuard<'a>
+            = Ref<'a, T>
+        where
+            Self: 'a;
+        type WriteGuard<'a>
+            = RefMut<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn read(&self) -> Self::ReadGuard<'_> {
+            self.borrow()
+        }
+
+        #[inline]
+        fn write(&self) -> Self::WriteGuard<'_> {
+            self.borrow_mut()
+        }
+
+        #[inline]
+        fn get_mut(&mut self) -> &mut T {
+            Self::get_mut(self)
+        }
+
+        #[inline]
+        fn read_checked(&self) -> Option<Self::ReadGuard<'_>> {
+            self.try_borrow().ok()
+        }
+
+        #[inline]
+        fn write_checked(&self) -> Option<Self::WriteGuard<'_>> {
+            self.try_borrow_mut().ok()
+        }
+    }
+
+    impl<T> MutexBackend<T> for RefCell<T> {
+        type Guard<'a>
+            = RefMut<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn lock(&self) -> Self::Guard<'_> {
+            self.borrow_mut()
+        }
+    }
+}
+
+#[cfg(feature = "parking_lot")]
+mod parking_lot_backend {
+    use super::{MutexBackend, RwLockBackend};
+
+    impl<T> RwLockBackend<T> for parking_lot::RwLock<T> {
+        type ReadGuard<'a>
+            = parking_lot::RwLockReadGuard<'a, T>
+        where
+            Self: 'a;
+        type WriteGuard<'a>
+            = parking_lot::RwLockWriteGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn read(&self) -> Self::ReadGuard<'_> {
+            Self::read(self)
+        }
+
+        #[inline]
+        fn write(&self) -> Self::WriteGuard<'_> {
+            Self::write(self)
+        }
+
+        #[inline]
+        fn get_mut(&mut self) -> &mut T {
+            Self::get_mut(self)
+        }
+    }
+
+    impl<T> MutexBackend<T> for parking_lot::Mutex<T> {
+        type Guard<'a>
+            = parking_lot::MutexGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn lock(&self) -> Self::Guard<'_> {
+            Self::lock(self)
+        }
+    }
+}
+
+// `thread_safe` depends on `spin` as the fallback backend
+#[cfg(feature = "thread_safe")]
+mod spin_backend {
+    use super::{MutexBackend, RwLockBackend};
+
+    impl<T> RwLockBackend<T> for spin::RwLock<T> {
+        type ReadGuard<'a>
+            = spin::RwLockReadGuard<'a, T>
+        where
+            Self: 'a;
+        type WriteGuard<'a>
+            = spin::RwLockWriteGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn read(&self) -> Self::ReadGuard<'_> {
+            Self::read(self)
+        }
+
+        #[inline]
+        fn write(&self) -> Self::WriteGuard<'_> {
+            Self::write(self)
+        }
+
+        #[inline]
+        fn get_mut(&mut self) -> &mut T {
+            Self::get_mut(self)
+        }
+    }
+
+    impl<T> MutexBackend<T> for spin::Mutex<T> {
+        type Guard<'a>
+            = spin::MutexGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn lock(&self) -> Self::Guard<'_> {
+            Self::lock(self)
+        }
+    }
+}
+
+#[cfg(feature = "std_sync")]
+mod std_backend {
+    use std::sync::{self, PoisonError};
+
+    use super::{MutexBackend, RwLockBackend};
+
+    impl<T> RwLockBackend<T> for sync::RwLock<T> {
+        type ReadGuard<'a>
+            = sync::RwLockReadGuard<'a, T>
+        where
+            Self: 'a;
+        type WriteGuard<'a>
+            = sync::RwLockWriteGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn read(&self) -> Self::ReadGuard<'_> {
+            Self::read(self).unwrap_or_else(PoisonError::into_inner)
+        }
+
+        #[inline]
+        fn write(&self) -> Self::WriteGuard<'_> {
+            Self::write(self).unwrap_or_else(PoisonError::into_inner)
+        }
+
+        #[inline]
+        fn get_mut(&mut self) -> &mut T {
+            Self::get_mut(self).unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl<T> MutexBackend<T> for sync::Mutex<T> {
+        type Guard<'a>
+            = sync::MutexGuard<'a, T>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn new(value: T) -> Self {
+            Self::new(value)
+        }
+
+        #[inline]
+        fn lock(&self) -> Self::Guard<'_> {
+            Self::lock(self).unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+}
+
+#[cfg(not(feature = "thread_safe"))]
+mod selected {
+    pub(crate) type RwLock<T> = core::cell::RefCell<T>;
+    pub(crate) type Mutex<T> = core::cell::RefCell<T>;
+}
+
+#[cfg(all(feature = "thread_safe", feature = "parking_lot"))]
+mod selected {
+    pub(crate) type RwLock<T> = parking_lot::RwLock<T>;
+    pub(crate) type Mutex<T> = parking_lot::Mutex<T>;
+}
+
+#[cfg(all(
+    feature = "thread_safe",
+    not(feature = "parking_lot"),
+    any(feature = "spin", not(feature = "std_sync"))
+))]
+mod selected {
+    pub(crate) type RwLock<T> = spin::RwLock<T>;
+    pub(crate) type Mutex<T> = spin::Mutex<T>;
+}
+
+#[cfg(all(feature = "thread_safe", feature = "std_sync", not(any(feature = "parking_lot", feature = "spin"))))]
+mod selected {
+    pub(crate) type RwLock<T> = std::sync::RwLock<T>;
+    pub(crate) type Mutex<T> = std::sync::Mutex<T>;
+}
+
+pub(crate) type RwLockReadGuard<'a, T> = <selected::RwLock<T> as RwLockBackend<T>>::ReadGuard<'a>;
+pub(crate) type RwLockWriteGuard<'a, T> = <selected::RwLock<T> as RwLockBackend<T>>::WriteGuard<'a>;
+pub(crate) type MutexGuard<'a, T> = <selected::Mutex<T> as MutexBackend<T>>::Guard<'a>;
+
+/// Reader-writer lock of the selected backend
+pub(crate) struct RwLock<T>(selected::RwLock<T>);
+
+impl<T> RwLock<T> {
+    #[inline]
+    #[must_use]
+    pub(crate) fn new(value: T) -> Self {
+        Self(RwLockBackend::new(value))
+    }
+
+    #[inline]
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, T> {
+        RwLockBackend::read(&self.0)
+    }
+
+    #[inline]
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, T> {
+        RwLockBackend::write(&self.0)
+    }
+
+    /// See [`RwLockBackend::read_checked`]
+    #[inline]
+    pub(crate) fn read_checked(&self) -> Option<RwLockReadGuard<'_, T>> {
+        RwLockBackend::read_checked(&self.0)
+    }
+
+    /// See [`RwLockBackend::write_checked`]
+    #[inline]
+    pub(crate) fn write_checked(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        RwLockBackend::write_checked(&self.0)
+    }
+
+    #[cfg(feature = "async")]
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut T {
+        RwLockBackend::get_mut(&mut self.0)
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Mutual exclusion lock of the selected backend
+pub(crate) struct Mutex<T>(selected::Mutex<T>);
+
+impl<T> Mutex<T> {
+    #[inline]
+    #[must_use]
+    pub(crate) fn new(value: T) -> Self {
+        Self(MutexBackend::new(value))
+    }
+
+    #[inline]
+    pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+        MutexBackend::lock(&self.0)
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "std_sync")]
+    #[test]
+    fn test_std_recovers_poisoned_locks() {
+        extern crate std;
+
+        use super::{MutexBackend, RwLockBackend};
+        use std::{panic, sync};
+
+        let lock = sync::RwLock::new(1);
+        let mutex = sync::Mutex::new(1);
+        let _ = panic::catch_unwind(|| {
+            let _lock = RwLockBackend::write(&lock);
+            let _mutex = MutexBackend::lock(&mutex);
+            panic!("poison");
+        });
+        assert!(lock.is_poisoned() && mutex.is_poisoned());
+
+        *RwLockBackend::write(&lock) += 1;
+        assert_eq!(*RwLockBackend::read(&lock), 2);
+        assert_eq!(*MutexBackend::lock(&mutex), 1);
+    }
+
+    #[test]
+    fn test_selected_backend() {
+        let lock = super::RwLock::new(1);
+        *lock.write() += 1;
+        assert_eq!(*lock.read(), 2);
+
+        let mutex = super::Mutex::<u8>::default();
+        *mutex.lock() += 1;
+        assert_eq!(*mutex.lock(), 1);
+    }
+}
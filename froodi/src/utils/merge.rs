@@ -12,7 +12,7 @@ impl Merge<Registry> for Registry {
 
     #[inline]
     fn merge(mut self, other: Registry) -> Self::Output {
-        self.entries.extend(other.entries);
+        self.extend(other);
         self
     }
 }
@@ -22,7 +22,7 @@ impl Merge<(TypeInfo, InstantiatorData)> for Registry {
 
     #[inline]
     fn merge(mut self, (key, value): (TypeInfo, InstantiatorData)) -> Self::Output {
-        self.entries.insert(key, value);
+        self.insert_entry(key, value);
         self
     }
 }
@@ -67,7 +67,7 @@ mod async_impl {
 
         #[inline]
         fn merge(mut self, registry: Self) -> Self::Output {
-            self.entries.extend(registry.entries);
+            self.extend(registry);
             self
         }
     }
@@ -76,7 +76,8 @@ mod async_impl {
         type Output = RegistryWithSync;
 
         #[inline]
-        fn merge(self, sync: Registry) -> Self::Output {
+        fn merge(self, mut sync: Registry) -> Self::Output {
+            sync.conflict_policy = self.conflict_policy;
             Self::Output { registry: self, sync }
         }
     }
@@ -85,7 +86,8 @@ mod async_impl {
         type Output = RegistryWithSync;
 
         #[inline]
-        fn merge(self, registry: async_impl::Registry) -> Self::Output {
+        fn merge(self, mut registry: async_impl::Registry) -> Self::Output {
+            registry.conflict_policy = self.conflict_policy;
             Self::Output { registry, sync: self }
         }
     }
@@ -95,8 +97,8 @@ mod async_impl {
 
         #[inline]
         fn merge(mut self, registry: RegistryWithSync) -> Self::Output {
-            self.sync.entries.extend(registry.sync.entries);
-            self.registry.entries.extend(registry.registry.entries);
+            self.sync.extend(registry.sync);
+            self.registry.extend(registry.registry);
             self
         }
     }
@@ -106,7 +108,7 @@ mod async_impl {
 
         #[inline]
         fn merge(mut self, registry: Registry) -> Self::Output {
-            self.sync.entries.extend(registry.entries);
+            self.sync.extend(registry);
             self
         }
     }
@@ -116,7 +118,7 @@ mod async_impl {
 
         #[inline]
         fn merge(mut self, registry: async_impl::Registry) -> Self::Output {
-            self.registry.entries.extend(registry.entries);
+            self.registry.extend(registry);
             self
         }
     }
@@ -126,8 +128,11 @@ mod async_impl {
 
         #[inline]
         fn merge(self, (key, value): (TypeInfo, async_impl::InstantiatorData)) -> Self::Output {
-            let mut registry = async_impl::Registry::default();
-            registry.entries.insert(key, value);
+            let mut registry = async_impl::Registry {
+                conflict_policy: self.conflict_policy,
+                ..Default::default()
+            };
+            registry.insert_entry(key, value);
             Self::Output { registry, sync: self }
         }
     }
@@ -137,7 +142,7 @@ mod async_impl {
 
         #[inline]
         fn merge(mut self, (key, value): (TypeInfo, async_impl::InstantiatorData)) -> Self::Output {
-            self.entries.insert(key, value);
+            self.insert_entry(key, value);
             self
         }
     }
@@ -147,7 +152,7 @@ mod async_impl {
 
         #[inline]
         fn merge(mut self, (key, value): (TypeInfo, async_impl::InstantiatorData)) -> Self::Output {
-            self.registry.entries.insert(key, value);
+            self.registry.insert_entry(key, value);
             self
         }
     }
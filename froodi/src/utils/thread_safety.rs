@@ -1,6 +1,6 @@
 #[cfg(feature = "thread_safe")]
 mod thread_safe {
-    use alloc::sync::Arc;
+    use alloc::sync::{Arc, Weak};
     use core::any::Any;
 
     pub trait SendSafety: Send {}
@@ -11,11 +11,13 @@ mod thread_safe {
 
     pub type RcThreadSafety<T> = Arc<T>;
     pub type RcAnyThreadSafety = RcThreadSafety<dyn Any + Send + Sync>;
+    pub type WeakThreadSafety<T> = Weak<T>;
+    pub type WeakAnyThreadSafety = WeakThreadSafety<dyn Any + Send + Sync>;
 }
 
 #[cfg(not(feature = "thread_safe"))]
 mod thread_unsafe {
-    use alloc::rc::Rc;
+    use alloc::rc::{Rc, Weak};
     use core::any::Any;
 
     pub trait SendSafety {}
@@ -26,14 +28,16 @@ mod thread_unsafe {
 
     pub type RcThreadSafety<T> = Rc<T>;
     pub type RcAnyThreadSafety = RcThreadSafety<dyn Any>;
+    pub type WeakThreadSafety<T> = Weak<T>;
+    pub type WeakAnyThreadSafety = WeakThreadSafety<dyn Any>;
 }
 
 #[cfg(feature = "thread_safe")]
-pub use thread_safe::RcThreadSafety;
+pub(crate) use thread_safe::{RcAnyThreadSafety, SendSafety, SyncSafety, WeakAnyThreadSafety};
 #[cfg(feature = "thread_safe")]
-pub(crate) use thread_safe::{RcAnyThreadSafety, SendSafety, SyncSafety};
+pub use thread_safe::{RcThreadSafety, WeakThreadSafety};
 
 #[cfg(not(feature = "thread_safe"))]
-pub use thread_unsafe::RcThreadSafety;
+pub(crate) use thread_unsafe::{RcAnyThreadSafety, SendSafety, SyncSafety, WeakAnyThreadSafety};
 #[cfg(not(feature = "thread_safe"))]
-pub(crate) use thread_unsafe::{RcAnyThreadSafety, SendSafety, SyncSafety};
+pub use thread_unsafe::{RcThreadSafety, WeakThreadSafety};
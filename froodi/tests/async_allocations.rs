@@ -0,0 +1,71 @@
+#![cfg(feature = "async")]
+
+extern crate std;
+
+use core::{
+    future::Future,
+    pin::pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+use froodi::{async_impl::Container, async_registry, DefaultScope::*, Inject};
+use std::alloc::{GlobalAlloc, Layout, System};
+
+/// Counts allocations of the process, so there must be a single test in this binary
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Polls the future once, it must be ready
+fn poll_ready<F: Future>(future: F) -> F::Output {
+    let mut context = Context::from_waker(futures::task::noop_waker_ref());
+    match pin!(future).poll(&mut context) {
+        Poll::Ready(output) => output,
+        Poll::Pending => panic!("the future should be ready"),
+    }
+}
+
+#[test]
+fn get_of_cached_instance_does_not_allocate() {
+    struct Config;
+    struct Handler;
+
+    let container = Container::new(async_registry! {
+        scope(App) [
+            provide(async || Ok(Config)),
+        ],
+        scope(Request) [
+            provide(async |Inject(_): Inject<Config>| Ok(Handler)),
+        ],
+    });
+    let request_container = container.clone().enter_build().unwrap();
+    futures::executor::block_on(request_container.get::<Handler>()).unwrap();
+
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    for _ in 0..100 {
+        poll_ready(request_container.get::<Handler>()).unwrap();
+        // Cached in the request container, when it got the instance from its parent
+        poll_ready(request_container.get::<Config>()).unwrap();
+        poll_ready(container.get::<Config>()).unwrap();
+    }
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst) - before, 0);
+}
@@ -0,0 +1,345 @@
+#![cfg(feature = "local_async")]
+
+use alloc::rc::Rc;
+use core::{cell::Cell, time::Duration};
+use froodi::utils::thread_safety::RcThreadSafety;
+use froodi::{
+    async_impl::{local, Container},
+    async_registry, Config,
+    DefaultScope::*,
+    Inject, InstantiateErrorKind, ResolveErrorKind, RetryPolicy, ScopeWithErrorKind,
+};
+
+extern crate alloc;
+
+struct Settings(u8);
+struct Counter(u8);
+struct Handler(u8);
+struct Session(u8);
+struct Report(u8);
+
+/// Runs each test on a Tokio local set and on the executor of `futures`
+macro_rules! on_executors {
+    ($($name:ident),* $(,)?) => {
+        $(
+            mod $name {
+                #[tokio::test]
+                async fn on_local_set() {
+                    tokio::task::LocalSet::new().run_until(super::$name()).await;
+                }
+
+                #[test]
+                fn on_futures() {
+                    futures::executor::block_on(super::$name());
+                }
+            }
+        )*
+    };
+}
+
+on_executors!(
+    get_caches_instance_of_not_send_factory,
+    get_transient_is_fresh_each_call,
+    child_resolves_local_factory_of_its_scope,
+    close_drops_local_instances,
+    local_factory_depends_on_local_types,
+    async_factory_depends_on_local_types,
+    wrapped_container_gets_local_instances,
+    close_calls_local_finalizers,
+    local_factory_is_retried,
+    enter_unknown_scope_returns_error,
+);
+
+/// Yields once, so the `Rc` below is held across an await
+async fn yield_now() {
+    let mut yielded = false;
+    core::future::poll_fn(|cx| {
+        if yielded {
+            return core::task::Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        core::task::Poll::Pending
+    })
+    .await;
+}
+
+fn container(calls: &Rc<Cell<u8>>) -> local::Container {
+    let container = Container::new(async_registry! {
+        scope(App) [
+            provide(async || Ok(Settings(1))),
+        ],
+    });
+    let registry = local::Registry::new()
+        .provide(App, {
+            let calls = calls.clone();
+            move |Inject(config): Inject<Settings>| {
+                // `!Send` state across an await
+                let calls = calls.clone();
+                async move {
+                    yield_now().await;
+                    calls.set(calls.get() + 1);
+                    Ok::<_, InstantiateErrorKind>(Counter(config.0 + calls.get()))
+                }
+            }
+        })
+        .provide(Request, {
+            let calls = calls.clone();
+            move |Inject(config): Inject<Settings>| {
+                let calls = calls.clone();
+                async move {
+                    yield_now().await;
+                    calls.set(calls.get() + 1);
+                    Ok::<_, InstantiateErrorKind>(Handler(config.0 + calls.get()))
+                }
+            }
+        });
+    local::Container::new(container, registry)
+}
+
+async fn get_caches_instance_of_not_send_factory() {
+    let calls = Rc::new(Cell::new(0));
+    let container = container(&calls);
+
+    let first = container.get::<Counter>().await.unwrap();
+    let second = container.get::<Counter>().await.unwrap();
+
+    assert!(RcThreadSafety::ptr_eq(&first, &second));
+    assert_eq!(first.0, 2);
+    assert_eq!(calls.get(), 1);
+    // Other types are resolved by the wrapped container
+    assert_eq!(container.get::<Settings>().await.unwrap().0, 1);
+
+    container.close().await;
+}
+
+async fn get_transient_is_fresh_each_call() {
+    let calls = Rc::new(Cell::new(0));
+    let container = container(&calls);
+
+    let first = container.get_transient::<Counter>().await.unwrap();
+    let second = container.get_transient::<Counter>().await.unwrap();
+
+    assert_eq!((first.0, second.0), (2, 3));
+    container.close().await;
+}
+
+async fn child_resolves_local_factory_of_its_scope() {
+    let calls = Rc::new(Cell::new(0));
+    let app_container = container(&calls);
+
+    assert!(matches!(
+        app_container.get::<Handler>().await,
+        Err(ResolveErrorKind::NoAccessible { .. })
+    ));
+
+    let counter = app_container.get::<Counter>().await.unwrap();
+    let request_container = app_container.clone().enter().with_scope(Request).build().unwrap();
+    // App instances are shared with the child
+    assert!(RcThreadSafety::ptr_eq(&counter, &request_container.get::<Counter>().await.unwrap()));
+    assert_eq!(request_container.get::<Handler>().await.unwrap().0, 3);
+
+    let other_request_container = app_container.clone().enter_build().unwrap();
+    assert_eq!(other_request_container.get::<Handler>().await.unwrap().0, 4);
+
+    request_container.close().await;
+    other_request_container.close().await;
+    app_container.close().await;
+}
+
+async fn close_drops_local_instances() {
+    let calls = Rc::new(Cell::new(0));
+    let container = container(&calls);
+
+    let first = container.get::<Counter>().await.unwrap();
+    container.close().await;
+    let second = container.get::<Counter>().await.unwrap();
+
+    assert!(!RcThreadSafety::ptr_eq(&first, &second));
+    assert_eq!(calls.get(), 2);
+    container.close().await;
+}
+
+/// Local factories of `Session` and `Handler` depending on the local `Counter`, and an async factory depending on all of them
+fn dependent_container(calls: &Rc<Cell<u8>>) -> local::Container {
+    let container = Container::new(
+        async_registry! {
+            scope(App) [
+                provide(async || Ok(Settings(1))),
+            ],
+            scope(Request) [
+                provide(async |Inject(counter): Inject<Counter>, Inject(session): Inject<Session>, Inject(handler): Inject<Handler>| {
+                    Ok(Report(counter.0 + session.0 + handler.0))
+                }),
+            ],
+        }
+        .with_external::<Counter>()
+        .with_external::<Session>()
+        .with_external::<Handler>(),
+    );
+    let registry = local::Registry::new()
+        .provide(App, {
+            let calls = calls.clone();
+            move |Inject(settings): Inject<Settings>| {
+                let calls = calls.clone();
+                async move {
+                    yield_now().await;
+                    calls.set(calls.get() + 1);
+                    Ok::<_, InstantiateErrorKind>(Counter(settings.0))
+                }
+            }
+        })
+        .provide(App, move |Inject(counter): Inject<Counter>| async move {
+            yield_now().await;
+            Ok::<_, InstantiateErrorKind>(Session(counter.0 + 1))
+        })
+        .provide(
+            Request,
+            move |Inject(counter): Inject<Counter>, Inject(session): Inject<Session>| async move {
+                yield_now().await;
+                Ok::<_, InstantiateErrorKind>(Handler(counter.0 + session.0))
+            },
+        );
+    local::Container::new(container, registry)
+}
+
+async fn local_factory_depends_on_local_types() {
+    let calls = Rc::new(Cell::new(0));
+    let app_container = dependent_container(&calls);
+
+    assert_eq!(app_container.get::<Session>().await.unwrap().0, 2);
+
+    let request_container = app_container.clone().enter_build().unwrap();
+    assert_eq!(request_container.get::<Handler>().await.unwrap().0, 3);
+    // The counter is instantiated once, in the app container
+    assert_eq!(calls.get(), 1);
+
+    request_container.close().await;
+    app_container.close().await;
+}
+
+async fn async_factory_depends_on_local_types() {
+    let calls = Rc::new(Cell::new(0));
+    let app_container = dependent_container(&calls);
+    let request_container = app_container.clone().enter_build().unwrap();
+
+    assert_eq!(request_container.get::<Report>().await.unwrap().0, 6);
+    assert_eq!(request_container.get_transient::<Report>().await.unwrap().0, 6);
+    assert_eq!(calls.get(), 1);
+    // The local instances are cached in the app container
+    assert_eq!(app_container.container().get::<Session>().await.unwrap().0, 2);
+
+    request_container.close().await;
+    app_container.close().await;
+}
+
+async fn wrapped_container_gets_local_instances() {
+    let calls = Rc::new(Cell::new(0));
+    let app_container = container(&calls);
+
+    let counter = app_container.get::<Counter>().await.unwrap();
+    assert!(RcThreadSafety::ptr_eq(
+        &counter,
+        &app_container.container().get::<Counter>().await.unwrap()
+    ));
+
+    let request_container = app_container.clone().enter_build().unwrap();
+    let handler = request_container.get::<Handler>().await.unwrap();
+    assert!(RcThreadSafety::ptr_eq(
+        &handler,
+        &request_container.container().get::<Handler>().await.unwrap()
+    ));
+    assert!(RcThreadSafety::ptr_eq(
+        &counter,
+        &request_container.container().get::<Counter>().await.unwrap()
+    ));
+    // Not resolved yet by the local container
+    assert!(app_container
+        .clone()
+        .enter_build()
+        .unwrap()
+        .container()
+        .get::<Handler>()
+        .await
+        .is_err());
+
+    request_container.close().await;
+    app_container.close().await;
+}
+
+async fn close_calls_local_finalizers() {
+    let finalized = Rc::new(Cell::new(0));
+    let container = local::Container::new(
+        Container::new(async_registry! {
+            scope(App) [
+                provide(async || Ok(Settings(1))),
+            ],
+        }),
+        local::Registry::new().provide_with_finalizer(
+            App,
+            |Inject(settings): Inject<Settings>| async move { Ok::<_, InstantiateErrorKind>(Counter(settings.0)) },
+            Config::default(),
+            {
+                let finalized = finalized.clone();
+                move |counter: RcThreadSafety<Counter>| {
+                    let finalized = finalized.clone();
+                    async move {
+                        yield_now().await;
+                        finalized.set(finalized.get() + counter.0);
+                    }
+                }
+            },
+        ),
+    );
+
+    container.get::<Counter>().await.unwrap();
+    container.get::<Counter>().await.unwrap();
+    container.close().await;
+    assert_eq!(finalized.get(), 1);
+
+    container.close().await;
+    assert_eq!(finalized.get(), 1);
+}
+
+async fn local_factory_is_retried() {
+    let calls = Rc::new(Cell::new(0));
+    let container = local::Container::new(
+        Container::new(async_registry! {
+            scope(App) [
+                provide(async || Ok(Settings(1))),
+            ],
+        }),
+        local::Registry::new().provide_with_config(
+            App,
+            {
+                let calls = calls.clone();
+                move || {
+                    let calls = calls.clone();
+                    async move {
+                        calls.set(calls.get() + 1);
+                        if calls.get() == 1 {
+                            return Err(InstantiateErrorKind::Custom(anyhow::anyhow!("first attempt")));
+                        }
+                        Ok(Counter(calls.get()))
+                    }
+                }
+            },
+            Config::default().retry(RetryPolicy::new(2, Duration::ZERO)),
+        ),
+    );
+
+    assert_eq!(container.get::<Counter>().await.unwrap().0, 2);
+    assert_eq!(calls.get(), 2);
+    container.close().await;
+}
+
+async fn enter_unknown_scope_returns_error() {
+    let calls = Rc::new(Cell::new(0));
+    let container = container(&calls);
+
+    assert!(matches!(
+        container.clone().enter().with_scope_name("unknown").build(),
+        Err(ScopeWithErrorKind::NoChildRegistriesWithScope { .. })
+    ));
+    container.close().await;
+}
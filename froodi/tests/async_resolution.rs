@@ -2,9 +2,37 @@
 
 use core::sync::atomic::{AtomicU8, Ordering};
 use froodi::utils::thread_safety::RcThreadSafety;
-use froodi::{async_impl::Container, async_registry, DefaultScope::*, Inject, InstantiateErrorKind, ResolveErrorKind};
+use froodi::{async_impl::Container, async_registry, DefaultScope::*, Inject, InjectWeak, InstantiateErrorKind, ResolveErrorKind};
+
+/// Runs each test on the Tokio runtime and on the executor of `futures`, the async container isn't tied to either
+macro_rules! on_executors {
+    ($($name:ident),* $(,)?) => {
+        $(
+            mod $name {
+                #[tokio::test]
+                async fn on_tokio() {
+                    super::$name().await;
+                }
+
+                #[test]
+                fn on_futures() {
+                    futures::executor::block_on(super::$name());
+                }
+            }
+        )*
+    };
+}
+
+on_executors!(
+    get_caches_same_instance_and_runs_instantiator_once,
+    get_transient_is_fresh_each_call,
+    inject_across_scopes_shares_leaf,
+    get_unregistered_returns_no_instantiator,
+    get_request_scoped_from_app_returns_no_accessible,
+    close_runs_finalizer_and_resets_cache,
+    inject_weak_points_back_at_dependent,
+);
 
-#[tokio::test]
 async fn get_caches_same_instance_and_runs_instantiator_once() {
     struct Cached(u8);
 
@@ -35,7 +63,6 @@ async fn get_caches_same_instance_and_runs_instantiator_once() {
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
 }
 
-#[tokio::test]
 async fn get_transient_is_fresh_each_call() {
     struct Transient(u8);
 
@@ -66,7 +93,6 @@ async fn get_transient_is_fresh_each_call() {
     assert_eq!(call_count.load(Ordering::SeqCst), 3);
 }
 
-#[tokio::test]
 async fn inject_across_scopes_shares_leaf() {
     struct Leaf(u8);
     struct Mid(RcThreadSafety<Leaf>);
@@ -102,7 +128,6 @@ async fn inject_across_scopes_shares_leaf() {
     assert_eq!(leaf_count.load(Ordering::SeqCst), 1);
 }
 
-#[tokio::test]
 async fn get_unregistered_returns_no_instantiator() {
     struct Registered;
     #[derive(Debug)]
@@ -118,7 +143,6 @@ async fn get_unregistered_returns_no_instantiator() {
     assert!(matches!(err, ResolveErrorKind::NoInstantiator { .. }));
 }
 
-#[tokio::test]
 async fn get_request_scoped_from_app_returns_no_accessible() {
     struct AppDep;
     #[derive(Debug)]
@@ -145,7 +169,6 @@ async fn get_request_scoped_from_app_returns_no_accessible() {
     ));
 }
 
-#[tokio::test]
 async fn close_runs_finalizer_and_resets_cache() {
     struct Closable(u8);
 
@@ -196,3 +219,51 @@ async fn close_runs_finalizer_and_resets_cache() {
     assert_eq!(after.0, 1);
     assert_eq!(inst_count.load(Ordering::SeqCst), 2);
 }
+
+async fn instantiate_times_out() {
+    let app_container = Container::new(async_registry! {
+        scope(App) [
+            provide(
+                async || {
+                    core::future::pending::<()>().await;
+                    Ok::<_, InstantiateErrorKind>(0u8)
+                },
+                config = froodi::Config::default().timeout(Some(core::time::Duration::from_millis(10))),
+            ),
+        ],
+    });
+
+    let err = app_container.get::<u8>().await.unwrap_err();
+    assert!(matches!(err, ResolveErrorKind::Timeout { attempts: 1, .. }));
+}
+
+/// Timers of the Tokio backend need the Tokio runtime, the executor-agnostic backend runs them on any executor
+mod instantiate_times_out {
+    #[tokio::test]
+    async fn on_tokio() {
+        super::instantiate_times_out().await;
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    #[test]
+    fn on_futures() {
+        futures::executor::block_on(super::instantiate_times_out());
+    }
+}
+
+async fn inject_weak_points_back_at_dependent() {
+    struct Parent(RcThreadSafety<Child>);
+    struct Child(InjectWeak<Parent>);
+
+    // The cycle passes validation, because one of its edges is weak
+    let app_container = Container::new(async_registry! {
+        scope(App) [
+            provide(async |Inject(child): Inject<Child>| Ok(Parent(child))),
+            provide(async |weak: InjectWeak<Parent>| Ok(Child(weak))),
+        ],
+    });
+
+    // The child is instantiated while the parent is, its reference points at the parent once it's cached
+    let parent = app_container.get::<Parent>().await.unwrap();
+    assert!(RcThreadSafety::ptr_eq(&parent.0 .0.upgrade().unwrap(), &parent));
+}
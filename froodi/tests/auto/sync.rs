@@ -5,9 +5,9 @@ extern crate alloc;
 use froodi::{
     registry,
     utils::thread_safety::RcThreadSafety,
-    Config, Container,
+    Config, ConflictPolicy, Container,
     DefaultScope::{App, Request, Session},
-    Inject, InstantiateErrorKind,
+    Inject, InstantiateErrorKind, ValidationErrorKind,
 };
 use froodi_auto::{entry_getters::__ENTRY_GETTERS, injectable, AutoRegistries as _};
 
@@ -70,3 +70,22 @@ fn test_entries() {
 
     container.close();
 }
+
+#[test]
+fn test_entries_obey_conflict_policy() {
+    let registry = registry! {
+        provide(App, || Ok(C)),
+    }
+    .with_conflict_policy(ConflictPolicy::Error)
+    .provide_auto_registries();
+
+    assert!(matches!(registry.validate(), Err(ValidationErrorKind::DuplicateProvider { .. })));
+
+    let registry = registry! {
+        provide(App, || Ok(C)),
+    }
+    .with_conflict_policy(ConflictPolicy::KeepFirst)
+    .provide_auto_registries();
+
+    registry.validate().unwrap();
+}
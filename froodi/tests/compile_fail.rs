@@ -1,11 +1,11 @@
-//! Compile-fail (UI) tests for the `registry!` / `async_registry!` macro diagnostics, via
+//! Compile-fail (UI) tests for the `registry!` / `async_registry!` / `static_registry!` / `#[derive(Scope)]` diagnostics, via
 //! `trybuild`. Each fixture in `tests/ui/` deliberately misuses a macro; its `.stderr` snapshot
 //! pins the exact `compile_error!` message produced for every error branch.
 //!
 //! Snapshots are toolchain-sensitive (they capture rustc's rendered diagnostic). Run these on a
 //! stable toolchain and regenerate after a compiler/macro change with:
 //!     TRYBUILD=overwrite cargo test -p froodi --test compile_fail
-//! (add `--features async` to also refresh the async fixture).
+//! (add `--features async` and `--features macros` to also refresh the async and `froodi-macros` fixtures).
 
 #[test]
 fn registry_macro_errors() {
@@ -17,3 +17,11 @@ fn registry_macro_errors() {
 fn async_registry_macro_errors() {
     trybuild::TestCases::new().compile_fail("tests/ui/async_registry_errors.rs");
 }
+
+#[cfg(feature = "macros")]
+#[test]
+fn froodi_macros_errors() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/static_registry_errors.rs");
+    cases.compile_fail("tests/ui/derive_scope_errors.rs");
+}
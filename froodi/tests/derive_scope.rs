@@ -0,0 +1,130 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use froodi::{registry, Container, Inject, ResolveErrorKind, Scope, ScopeData, Scopes};
+
+#[derive(Scope, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum JobScope {
+    #[scope(skip)]
+    Runtime,
+    App,
+    #[scope(name = "job")]
+    BackgroundJob,
+    #[scope(skip, name = "retry")]
+    RetryAttempt,
+    Step,
+}
+
+#[derive(Scope, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum TreeScope {
+    App,
+    Request,
+    #[scope(parent = App)]
+    BackgroundJob,
+    Step,
+}
+
+#[derive(Scope, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Single {
+    Only,
+}
+
+struct Settings;
+struct Job(u8);
+
+#[test]
+fn test_derived_scope_data() {
+    let (root, children) = <JobScope as Scopes<4>>::all();
+    assert_eq!(root, JobScope::Runtime);
+    assert_eq!(
+        children,
+        [JobScope::App, JobScope::BackgroundJob, JobScope::RetryAttempt, JobScope::Step]
+    );
+
+    let data: Vec<ScopeData> = core::iter::once(root).chain(children).map(Into::into).collect();
+    let names: Vec<_> = data.iter().map(|data| &*data.name).collect();
+    let priorities: Vec<_> = data.iter().map(|data| data.priority).collect();
+    let skipped: Vec<_> = data.iter().map(|data| data.is_skipped_by_default).collect();
+    assert_eq!(names, ["runtime", "app", "job", "retry", "step"]);
+    assert_eq!(priorities, [0, 1, 2, 3, 4]);
+    assert_eq!(skipped, [true, false, false, true, false]);
+
+    assert_eq!(<Single as Scopes<0>>::all(), (Single::Only, []));
+    assert_eq!(Single::Only.name(), "only");
+}
+
+#[test]
+fn test_container_with_derived_scope() {
+    let app = Container::new(registry! {
+        scope(JobScope::App) [
+            provide(|| Ok(Settings)),
+        ],
+        scope(JobScope::BackgroundJob) [
+            provide(|Inject(_): Inject<Settings>| Ok(Job(1))),
+        ],
+        scope(JobScope::Step) [
+            provide(|Inject(job): Inject<Job>| Ok(job.0 + 1)),
+        ],
+    });
+
+    let job = app.enter_build().unwrap();
+    assert_eq!(job.get::<Job>().unwrap().0, 1);
+
+    // `RetryAttempt` is skipped on the way to `Step`
+    let step = job.enter_build().unwrap();
+    assert_eq!(*step.get::<u8>().unwrap(), 2);
+    let scopes: Vec<_> = step.describe().scope_path().map(|scope| scope.name.clone()).collect();
+    assert_eq!(scopes, ["runtime", "app", "job", "retry", "step"]);
+}
+
+#[test]
+fn test_derived_scope_tree() {
+    assert_eq!(TreeScope::App.parent(), None);
+    assert_eq!(TreeScope::Request.parent(), None);
+    assert_eq!(TreeScope::BackgroundJob.parent(), Some(TreeScope::App));
+    assert_eq!(ScopeData::from(TreeScope::BackgroundJob).parent, Some(0));
+    assert_eq!(ScopeData::from(TreeScope::Step).parent, None);
+
+    let registry = registry! {
+        scope(TreeScope::App) [
+            provide(|| Ok(Settings)),
+        ],
+        scope(TreeScope::Request) [
+            provide(|| Ok(1u8)),
+        ],
+        scope(TreeScope::BackgroundJob) [
+            provide(|Inject(_): Inject<Settings>| Ok(Job(2))),
+        ],
+        scope(TreeScope::Step) [
+            provide(|Inject(job): Inject<Job>| Ok(u16::from(job.0))),
+        ],
+    };
+    registry.validate().unwrap();
+
+    let app = Container::new(registry);
+    let request = app.clone().enter_build().unwrap();
+    assert_eq!(*request.get::<u8>().unwrap(), 1);
+    assert!(matches!(request.get::<Job>(), Err(ResolveErrorKind::NoAccessible { .. })));
+
+    // `Step` is a child of `BackgroundJob`, so the job level is created on the way
+    let step = app.enter().with_scope(TreeScope::Step).build().unwrap();
+    assert_eq!(*step.get::<u16>().unwrap(), 2);
+    assert!(matches!(step.get::<u8>(), Err(ResolveErrorKind::NoAccessible { .. })));
+    let scopes: Vec<_> = step.describe().scope_path().map(|scope| scope.name.clone()).collect();
+    assert_eq!(scopes, ["app", "background_job", "step"]);
+}
+
+#[test]
+#[should_panic(expected = "`Job` (scope `background_job`, priority 2) depends on `u8` (scope `request`, priority 1)")]
+fn test_validate_sibling_scopes() {
+    let _ = registry! {
+        scope(TreeScope::Request) [
+            provide(|| Ok(1u8)),
+        ],
+        scope(TreeScope::BackgroundJob) [
+            provide(|Inject(_): Inject<u8>| Ok(Job(2))),
+        ],
+    };
+}
@@ -0,0 +1,92 @@
+use froodi::{facade, registry, Container, DefaultScope::*, Inject, ResolveErrorKind, ValidationErrorKind};
+use std::sync::Arc;
+
+struct UserRepo;
+struct Clock(u64);
+struct Missing;
+
+#[facade(scope = Request)]
+trait RequestDeps {
+    fn repo(&self) -> Arc<UserRepo>;
+    fn clock(&self) -> Result<Clock, ResolveErrorKind>;
+
+    fn now(&self) -> u64 {
+        self.clock().unwrap().0
+    }
+}
+
+#[facade(scope = App)]
+trait AppDeps {
+    fn clock(&self) -> Clock;
+}
+
+#[facade]
+trait MissingDeps {
+    fn missing(&self) -> Result<Arc<Missing>, ResolveErrorKind>;
+}
+
+#[test]
+fn test_facade() {
+    let registry = registry! {
+        scope(App) [
+            provide(|| Ok(UserRepo)),
+        ],
+        scope(Request) [
+            provide(|Inject(_): Inject<UserRepo>| Ok(Clock(1))),
+        ],
+    };
+    <Container as RequestDeps>::check(&registry).unwrap();
+    assert!(matches!(
+        <Container as AppDeps>::check(&registry),
+        Err(ValidationErrorKind::UnreachableFacadeDependency { method: "clock", .. })
+    ));
+    assert!(matches!(
+        <Container as MissingDeps>::check(&registry),
+        Err(ValidationErrorKind::MissingFacadeDependency { method: "missing", .. })
+    ));
+
+    let request = Container::new(registry).enter_build().unwrap();
+    assert!(Arc::ptr_eq(&request.repo(), &request.repo()));
+    assert_eq!(request.now(), 1);
+    // Checks are static, the request container still resolves the dependency
+    assert_eq!(AppDeps::clock(&request).0, 1);
+    assert!(request.missing().is_err());
+}
+
+#[cfg(feature = "async")]
+mod async_impl {
+    use froodi::{async_impl::Container, async_registry, facade, DefaultScope::*};
+    use std::sync::Arc;
+
+    use super::{Clock, UserRepo};
+
+    #[facade(scope = Request)]
+    trait RequestDeps {
+        async fn repo(&self) -> Arc<UserRepo>;
+        async fn clock(&self) -> Clock;
+    }
+
+    #[tokio::test]
+    async fn test_async_facade() {
+        let registry = async_registry! {
+            scope(App) [
+                provide(async || Ok(UserRepo)),
+            ],
+            scope(Request) [
+                provide(async || Ok(Clock(2))),
+            ],
+        };
+        <Container as RequestDeps>::check(&registry).unwrap();
+
+        let request = Container::new(registry).enter_build().unwrap();
+        assert!(Arc::ptr_eq(&request.repo().await, &request.repo().await));
+        assert_eq!(request.clock().await.0, 2);
+        // The sync container implements async facades with ready values
+        let sync = froodi::Container::new(froodi::registry! {
+            scope(App) [
+                provide(|| Ok(UserRepo)),
+            ],
+        });
+        sync.repo().await;
+    }
+}
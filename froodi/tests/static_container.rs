@@ -0,0 +1,88 @@
+#![no_std]
+
+extern crate alloc;
+
+use alloc::{sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicU8, Ordering};
+use froodi::{
+    static_container, Config,
+    DefaultScope::{App, Request, Step},
+    Inject, InjectTransient, InstantiateErrorKind,
+};
+use parking_lot::Mutex;
+
+struct Settings(u8);
+struct Pool(u8);
+struct Service(u8);
+struct Handler(u8);
+struct Counter;
+struct Stage(u8);
+
+static CREATED_COUNTERS: AtomicU8 = AtomicU8::new(0);
+static FINALIZED: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+
+static_container! {
+    scope(App) as AppContainer [
+        provide(|| Ok(Settings(1))),
+        provide(|Inject(settings): Inject<Settings>| Ok(Pool(settings.0 + 1)), finalizer = |_: Arc<Pool>| FINALIZED.lock().push("pool")),
+    ],
+    scope(Request) as RequestContainer [
+        provide(|Inject(pool): Inject<Pool>| Ok(Service(pool.0 + 1)), finalizer = |_: Arc<Service>| FINALIZED.lock().push("service")),
+        provide(
+            |Inject(service): Inject<Service>, InjectTransient(settings): InjectTransient<Settings>| -> Result<Handler, InstantiateErrorKind> {
+                Ok(Handler(service.0 + settings.0))
+            },
+            finalizer = |_: Arc<Handler>| FINALIZED.lock().push("handler"),
+        ),
+        provide(
+            || {
+                CREATED_COUNTERS.fetch_add(1, Ordering::SeqCst);
+                Ok(Counter)
+            },
+            config = Config::default().cache_provides(false),
+        ),
+    ],
+    scope(Step) as StepContainer [
+        provide(|Inject(handler): Inject<Handler>| Ok(Stage(handler.0))),
+    ],
+}
+
+#[test]
+fn test_static_container() {
+    let app = AppContainer::new();
+    let pool = app.get::<Pool>().unwrap();
+    assert_eq!(pool.0, 2);
+
+    let request = app.clone().enter();
+    let handler = request.get::<Handler>().unwrap();
+    assert_eq!(handler.0, 4);
+    assert!(Arc::ptr_eq(&handler, &request.get::<Handler>().unwrap()));
+    assert!(Arc::ptr_eq(&pool, &request.get::<Pool>().unwrap()));
+    assert_eq!(request.get_transient::<Settings>().unwrap().0, 1);
+
+    request.get::<Counter>().unwrap();
+    request.get::<Counter>().unwrap();
+    assert_eq!(CREATED_COUNTERS.load(Ordering::SeqCst), 2);
+
+    let step = request.clone().enter();
+    assert_eq!(step.get::<Stage>().unwrap().0, 4);
+    drop(step);
+
+    request.close();
+    assert_eq!(*FINALIZED.lock(), ["handler", "service"]);
+    // Closed containers create new instances
+    assert!(!Arc::ptr_eq(&handler, &request.get::<Handler>().unwrap()));
+
+    drop(request);
+    assert_eq!(*FINALIZED.lock(), ["handler", "service", "handler", "service"]);
+    drop(app);
+    assert_eq!(*FINALIZED.lock(), ["handler", "service", "handler", "service", "pool"]);
+
+    // The dynamic container shares instances of the static one
+    let request = AppContainer::new().enter();
+    let service = request.get::<Service>().unwrap();
+    let container = request.into_container();
+    assert!(Arc::ptr_eq(&service, &container.get::<Service>().unwrap()));
+    assert_eq!(container.get_transient::<Handler>().unwrap().0, 4);
+    assert_eq!(container.get::<Settings>().unwrap().0, 1);
+}
@@ -0,0 +1,86 @@
+#![no_std]
+
+extern crate alloc;
+
+use froodi::{
+    registry, static_registry, Config, Container, Context,
+    DefaultScope::{self, App, Request},
+    Inject, InjectTransient, InstantiateErrorKind,
+};
+
+struct Settings(u8);
+struct Service(u8);
+struct Handler(u8);
+struct FromContext(u8);
+
+fn make_settings() -> Result<Settings, InstantiateErrorKind> {
+    Ok(Settings(1))
+}
+
+#[test]
+fn test_generates_same_registry() {
+    let registry = static_registry! {
+        scope(App) [
+            provide(|| Ok(Settings(1))),
+        ],
+        scope(Request) [
+            provide(|InjectTransient(settings): InjectTransient<Settings>| Ok(Service(settings.0 + 1)), config = Config::default()),
+            provide(|Inject(service): Inject<Service>, Inject(from_context): Inject<u8>| {
+                Ok::<_, InstantiateErrorKind>(Handler(service.0 + *from_context))
+            }),
+        ],
+        external(u8),
+    };
+    registry.validate().unwrap();
+
+    let expected = registry! {
+        scope(App) [
+            provide(|| Ok(Settings(1))),
+        ],
+        scope(Request) [
+            provide(|InjectTransient(settings): InjectTransient<Settings>| Ok(Service(settings.0 + 1)), config = Config::default()),
+            provide(|Inject(service): Inject<Service>, Inject(from_context): Inject<u8>| {
+                Ok::<_, InstantiateErrorKind>(Handler(service.0 + *from_context))
+            }),
+        ],
+    };
+    assert_eq!(registry.to_dot(), expected.with_external::<u8>().to_dot());
+
+    let mut context = Context::new();
+    context.insert(3u8);
+    let request = Container::new(registry)
+        .enter()
+        .with_scope(Request)
+        .with_context(context)
+        .build()
+        .unwrap();
+    assert_eq!(request.get_transient::<Handler>().unwrap().0, 5);
+}
+
+#[test]
+fn test_resolves_from_container() {
+    let app = Container::new(static_registry! {
+        provide(App, make_settings),
+        provide(Request, |Inject(settings): Inject<Settings>, Inject(value): Inject<FromContext>| {
+            Ok(Service(settings.0 + value.0))
+        }),
+        external(FromContext),
+    });
+
+    let mut context = Context::new();
+    context.insert(FromContext(2));
+    let request = app.enter().with_scope(Request).with_context(context).build().unwrap();
+    assert_eq!(request.get::<Service>().unwrap().0, 3);
+}
+
+#[test]
+fn test_unseen_providers_are_checked_at_runtime() {
+    // `make_settings` hides its type, so the missing `Handler` can't be reported at compile time
+    let registry = static_registry! {
+        scope(DefaultScope::App) [
+            provide(make_settings),
+            provide(|Inject(_): Inject<Handler>| Ok(Service(0))),
+        ],
+    };
+    assert!(registry.validate().is_err());
+}
@@ -148,8 +148,7 @@ fn scope_error_no_child_registries_at_leaf() {
     let action = request.enter_build().unwrap();
     let step = action.enter_build().unwrap(); // Step is the leaf scope
 
-    // Container does not implement Debug, so unwrap the error via `.err()`.
-    let err = step.enter_build().err().unwrap();
+    let err = step.enter_build().unwrap_err();
 
     assert!(
         matches!(err, ScopeErrorKind::NoChildRegistries),
@@ -172,7 +171,7 @@ fn scope_with_error_no_child_registries_with_scope_for_own_scope() {
     // with_scope requires a strict descendant, so requesting the container's own scope (App) fails.
     let app = app_container();
 
-    let err = app.enter().with_scope(App).build().err().unwrap();
+    let err = app.enter().with_scope(App).build().unwrap_err();
 
     assert!(
         matches!(err, ScopeWithErrorKind::NoChildRegistriesWithScope { .. }),
@@ -180,19 +179,25 @@ fn scope_with_error_no_child_registries_with_scope_for_own_scope() {
     );
 
     let display = err.to_string();
-    // Display format: "Registry with name app and priority N not found in container"
+    // Display format: "Registry with name app not found in child scopes of container"
     assert!(display.contains("app"), "display should contain scope name 'app': {display}");
     assert!(display.contains("not found"), "display should contain 'not found': {display}");
 
     if let ScopeWithErrorKind::NoChildRegistriesWithScope { name, priority } = err {
         assert_eq!(name, "app", "name field should be the App scope name");
         // App priority is 1 in DefaultScope ordering.
-        assert_eq!(priority, 1, "App priority should be 1");
+        assert_eq!(priority, Some(1), "App priority should be 1");
     } else {
         panic!("variant changed unexpectedly");
     }
 
-    let debug = format!("{:?}", ScopeWithErrorKind::NoChildRegistriesWithScope { name: "app", priority: 1 });
+    let debug = format!(
+        "{:?}",
+        ScopeWithErrorKind::NoChildRegistriesWithScope {
+            name: "app".into(),
+            priority: Some(1),
+        }
+    );
     assert!(!debug.is_empty(), "debug must be non-empty");
     assert!(
         debug.contains("NoChildRegistriesWithScope"),
@@ -212,7 +217,7 @@ fn display_strings_are_distinct_across_error_kinds() {
 
     let no_inst: String = app.get::<Missing>().unwrap_err().to_string();
     let no_acc: String = app.get::<RequestOnly>().unwrap_err().to_string();
-    let scope_with: String = app.clone().enter().with_scope(App).build().err().unwrap().to_string();
+    let scope_with: String = app.clone().enter().with_scope(App).build().unwrap_err().to_string();
 
     assert_ne!(no_inst, no_acc);
     assert_ne!(no_inst, scope_with);
@@ -188,7 +188,7 @@ fn test_finalizer_runs_with_cache_provides_false() {
                         Ok::<_, InstantiateErrorKind>(NoCacheFinalized)
                     }
                 },
-                config = Config { cache_provides: false },
+                config = Config::default().cache_provides(false),
                 finalizer = {
                     let fin_count = fin_count.clone();
                     move |_: RcThreadSafety<NoCacheFinalized>| {
@@ -9,7 +9,7 @@ use froodi::{
     utils::thread_safety::RcThreadSafety,
     Config, Container,
     DefaultScope::{App, Request},
-    Inject, InjectTransient, ResolveErrorKind,
+    Inject, InjectTransient, InjectWeak, ResolveErrorKind,
 };
 
 #[test]
@@ -126,7 +126,7 @@ fn test_cache_provides_false_returns_new_instance() {
                         Ok(Uncached(prev + 1))
                     }
                 },
-                config = Config { cache_provides: false },
+                config = Config::default().cache_provides(false),
             ),
         ]
     });
@@ -243,3 +243,31 @@ fn test_inject_transient_yields_fresh_subinstances() {
     assert_eq!(direct.0, 3);
     assert_eq!(sub_calls.load(Ordering::SeqCst), 3);
 }
+
+#[test]
+fn test_inject_weak_fills_once_cached_and_dangles_after_close() {
+    struct Parent(RcThreadSafety<Child>);
+    struct Child(InjectWeak<Parent>);
+
+    // The cycle passes validation, because one of its edges is weak
+    let container = Container::new(registry! {
+        scope(App) [
+            provide(|Inject(child): Inject<Child>| Ok(Parent(child))),
+            provide(|weak: InjectWeak<Parent>| Ok(Child(weak))),
+        ]
+    });
+
+    // The parent isn't instantiated by the weak reference
+    let child = container.get::<Child>().unwrap();
+    assert!(child.0.upgrade().is_none());
+
+    // The reference points at the parent once it's cached
+    let parent = container.get::<Parent>().unwrap();
+    assert!(RcThreadSafety::ptr_eq(&parent.0, &child));
+    assert!(RcThreadSafety::ptr_eq(&child.0.upgrade().unwrap(), &parent));
+
+    // The container doesn't keep the parent alive after close
+    container.close();
+    drop(parent);
+    assert!(child.0.upgrade().is_none());
+}
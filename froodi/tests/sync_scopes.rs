@@ -6,7 +6,7 @@ use alloc::string::ToString as _;
 use froodi::{
     registry, Container, Context,
     DefaultScope::{self, Action, App, Request, Runtime, Session, Step},
-    ResolveErrorKind, Scope, ScopeErrorKind, ScopeWithErrorKind, Scopes,
+    Inject, ResolveErrorKind, Scope, ScopeErrorKind, ScopeWithErrorKind, Scopes,
 };
 
 struct AppDep(u32);
@@ -151,8 +151,7 @@ fn error_no_child_registries_at_leaf() {
         .enter_build()
         .expect("Action -> Step");
 
-    // Container does not implement Debug, so use `.err()` instead of expect_err.
-    let err = step_container.enter_build().err().expect("descending past leaf Step must fail");
+    let err = step_container.enter_build().expect_err("descending past leaf Step must fail");
     assert!(matches!(err, ScopeErrorKind::NoChildRegistries));
     assert!(!err.to_string().is_empty());
 }
@@ -166,8 +165,7 @@ fn error_no_child_registries_with_scope_for_own_scope() {
         .enter()
         .with_scope(App)
         .build()
-        .err()
-        .expect("with_scope(App) from an App container must fail");
+        .expect_err("with_scope(App) from an App container must fail");
     assert!(matches!(err, ScopeWithErrorKind::NoChildRegistriesWithScope { .. }));
 
     // Compute the Display string before destructuring moves `err`.
@@ -176,7 +174,7 @@ fn error_no_child_registries_with_scope_for_own_scope() {
 
     if let ScopeWithErrorKind::NoChildRegistriesWithScope { name, priority } = err {
         assert_eq!(name, "app");
-        assert_eq!(priority, App.priority());
+        assert_eq!(priority, Some(App.priority()));
     } else {
         unreachable!("error variant already asserted above");
     }
@@ -193,3 +191,57 @@ fn unregistered_type_no_instantiator() {
         .expect_err("unregistered type has no instantiator");
     assert!(matches!(err, ResolveErrorKind::NoInstantiator { .. }));
 }
+
+fn runtime_scopes() -> alloc::vec::Vec<froodi::ScopeData> {
+    ["app", "pipeline", "stage"]
+        .into_iter()
+        .zip(0..)
+        .map(|(name, priority)| froodi::ScopeData::new(priority, alloc::string::String::from(name)))
+        .collect()
+}
+
+#[test]
+fn runtime_scopes_entered_by_name() {
+    let scopes = runtime_scopes();
+    let registry = registry! {
+        scope(scopes[0].clone()) [
+            provide(|| Ok(AppDep(1))),
+        ],
+        scope(scopes[2].clone()) [
+            provide(|Inject(app): Inject<AppDep>| Ok(StepDep(app.0 + 1))),
+        ],
+    }
+    .with_scopes(scopes);
+
+    let app_container = Container::new(registry);
+    assert_eq!(app_container.get::<AppDep>().unwrap().0, 1);
+
+    let pipeline = app_container.clone().enter_build().unwrap();
+    assert!(matches!(pipeline.get::<StepDep>(), Err(ResolveErrorKind::NoAccessible { .. })));
+
+    let stage = pipeline.enter().with_scope_name("stage").build().unwrap();
+    assert_eq!(stage.get::<StepDep>().unwrap().0, 2);
+
+    let stage = app_container
+        .enter()
+        .with_context(Context::new())
+        .with_scope_name("stage")
+        .build()
+        .unwrap();
+    assert_eq!(stage.get::<StepDep>().unwrap().0, 2);
+}
+
+#[test]
+fn error_no_child_registries_with_scope_name() {
+    let app_container = Container::new(build_registry());
+
+    let err = app_container.clone().enter().with_scope_name("unknown").build().unwrap_err();
+    assert!(err.to_string().contains("unknown"));
+    assert!(matches!(err, ScopeWithErrorKind::NoChildRegistriesWithScope { priority: None, .. }));
+
+    let err = app_container.enter().with_scope_name("app").build().unwrap_err();
+    assert!(matches!(
+        err,
+        ScopeWithErrorKind::NoChildRegistriesWithScope { priority: Some(1), .. }
+    ));
+}
@@ -0,0 +1,52 @@
+//! Every error of `#[derive(Scope)]`. Compiled by `trybuild`; not a normal test target
+//! (cargo does not auto-discover files under `tests/ui/`).
+#![allow(unused)]
+
+use froodi::Scope;
+
+fn main() {}
+
+// 1. Not an enum
+#[derive(Scope)]
+struct NotEnum;
+
+// 2. No variants
+#[derive(Scope)]
+enum Empty {}
+
+// 3. Variant with fields
+#[derive(Scope)]
+enum WithFields {
+    App(u8),
+}
+
+// 4. Unknown attribute argument
+#[derive(Scope)]
+enum UnknownArgument {
+    #[scope(hidden)]
+    App,
+}
+
+// 5. Duplicate name
+#[derive(Scope)]
+enum DuplicateName {
+    App,
+    #[scope(name = "app")]
+    Other,
+}
+
+// 6. Argument specified twice
+#[derive(Scope)]
+enum DuplicateArgument {
+    #[scope(skip)]
+    #[scope(skip)]
+    App,
+}
+
+// 7. Parent declared after the scope
+#[derive(Scope)]
+enum ParentAfter {
+    #[scope(parent = Request)]
+    App,
+    Request,
+}
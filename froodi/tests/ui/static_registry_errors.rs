@@ -0,0 +1,50 @@
+//! Every compile-time check of `static_registry!`. Compiled by `trybuild`; not a normal test
+//! target (cargo does not auto-discover files under `tests/ui/`).
+#![allow(unused)]
+
+use froodi::{static_registry, DefaultScope::{self, *}, Inject, InjectTransient, InstantiateErrorKind};
+
+struct A;
+struct B;
+struct C;
+
+fn main() {}
+
+// 1. Dependency on a type of a child scope, checked only for scopes qualified with `DefaultScope::`
+fn e01() {
+    static_registry! {
+        scope(DefaultScope::App) [
+            provide(|Inject(_): Inject<B>| Ok(A)),
+        ],
+        scope(DefaultScope::Request) [
+            provide(|| Ok(B)),
+        ],
+    };
+}
+// 2. Cyclic dependency
+fn e02() {
+    static_registry! {
+        scope(App) [
+            provide(|Inject(_): Inject<B>| Ok(A)),
+            provide(|Inject(_): Inject<C>| -> Result<B, InstantiateErrorKind> { Ok(B) }),
+            provide(|InjectTransient(_): InjectTransient<A>| Ok::<C, InstantiateErrorKind>(C)),
+        ],
+    };
+}
+// 3. Missing dependency
+fn e03() {
+    static_registry! {
+        provide(App, |Inject(_): Inject<B>| Ok(A)),
+    };
+}
+// 4. Missing dependency isn't reported for external types
+fn e04() {
+    static_registry! {
+        provide(App, |Inject(_): Inject<B>| Ok(A)),
+        external(B),
+    };
+}
+// 5. Syntax errors are reported by `registry!`
+fn e05() {
+    static_registry! { scope(App) [] };
+}